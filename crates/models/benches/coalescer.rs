@@ -0,0 +1,154 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+//! Compares delivering every service event to the webview against delivering coalesced batches.
+//!
+//! Each delivery is serialized to JSON, as emitting an event does, then parsed and applied to a
+//! sorted list of services, as the webview does for every event it receives.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use models::{EventCoalescer, ResolvedService, ServiceRemovedEvent, ServicesBatchEvent, TxtRecord};
+use std::hint::black_box;
+use std::net::{IpAddr, Ipv4Addr};
+
+const SERVICES: usize = 200;
+/// How often each service is re-announced, chatty devices re-announce on every query.
+const ANNOUNCEMENTS: usize = 25;
+/// Events arriving within one batch interval.
+const EVENTS_PER_FRAME: usize = 100;
+
+enum Event {
+    Resolved(ResolvedService),
+    Removed(ServiceRemovedEvent),
+}
+
+fn service(index: usize, updated_at_micros: u64) -> ResolvedService {
+    ResolvedService {
+        instance_fullname: format!("device-{index}._http._tcp.local."),
+        service_type: "_http._tcp.local.".to_string(),
+        hostname: format!("device-{index}.local."),
+        port: 80,
+        addresses: vec![IpAddr::V4(Ipv4Addr::new(192, 168, 1, (index % 250) as u8)).into()],
+        subtype: None,
+        txt: vec![TxtRecord::new("path", Some(b"/"))],
+        updated_at_micros,
+        dead: false,
+        sightings: vec![],
+    }
+}
+
+/// Every service is announced repeatedly without changes, every tenth one goes away in the end.
+fn chatty_network() -> Vec<Event> {
+    let mut events = Vec::with_capacity(SERVICES * ANNOUNCEMENTS);
+    let mut now = 0;
+    for _ in 0..ANNOUNCEMENTS {
+        for index in 0..SERVICES {
+            now += 1;
+            events.push(Event::Resolved(service(index, now)));
+        }
+    }
+    for index in (0..SERVICES).step_by(10) {
+        now += 1;
+        events.push(Event::Removed(ServiceRemovedEvent {
+            instance_name: format!("device-{index}._http._tcp.local."),
+            at_micros: now,
+        }));
+    }
+    events
+}
+
+/// Applies resolved and removed services like the webview, which sorts after every delivery.
+fn apply(
+    services: &mut Vec<ResolvedService>,
+    resolved: Vec<ResolvedService>,
+    removed: Vec<ServiceRemovedEvent>,
+) {
+    for service in resolved {
+        match services
+            .iter_mut()
+            .find(|s| s.instance_fullname == service.instance_fullname)
+        {
+            Some(existing) => *existing = service,
+            None => services.push(service),
+        }
+    }
+    for removed in removed {
+        if let Some(existing) = services
+            .iter_mut()
+            .find(|s| s.instance_fullname == removed.instance_name && !s.dead)
+        {
+            existing.die_at(removed.at_micros);
+        }
+    }
+    services.sort_by(|a, b| a.instance_fullname.cmp(&b.instance_fullname));
+}
+
+fn deliver_each(events: &[Event]) -> usize {
+    let mut services = Vec::new();
+    let mut deliveries = 0;
+    for event in events {
+        match event {
+            Event::Resolved(service) => {
+                let json = serde_json::to_string(service).expect("To serialize");
+                let service = serde_json::from_str(&json).expect("To deserialize");
+                apply(&mut services, vec![service], vec![]);
+            }
+            Event::Removed(removed) => {
+                let json = serde_json::to_string(removed).expect("To serialize");
+                let removed = serde_json::from_str(&json).expect("To deserialize");
+                apply(&mut services, vec![], vec![removed]);
+            }
+        }
+        deliveries += 1;
+    }
+    black_box(services);
+    deliveries
+}
+
+fn deliver_batched(events: &[Event]) -> usize {
+    let mut coalescer = EventCoalescer::default();
+    let mut services = Vec::new();
+    let mut deliveries = 0;
+    for frame in events.chunks(EVENTS_PER_FRAME) {
+        for event in frame {
+            match event {
+                Event::Resolved(service) => coalescer.push_resolved(service.clone()),
+                Event::Removed(removed) => coalescer.push_removed(removed.clone()),
+            }
+        }
+        if let Some(batch) = coalescer.take_batch() {
+            let json = serde_json::to_string(&batch).expect("To serialize");
+            let batch: ServicesBatchEvent = serde_json::from_str(&json).expect("To deserialize");
+            apply(&mut services, batch.resolved, batch.removed);
+            deliveries += 1;
+        }
+    }
+    black_box(services);
+    deliveries
+}
+
+fn bench_delivery(c: &mut Criterion) {
+    let events = chatty_network();
+    let mut group = c.benchmark_group("service_events");
+    group.throughput(Throughput::Elements(events.len() as u64));
+    group.bench_with_input(
+        BenchmarkId::new("each", events.len()),
+        &events,
+        |b, events| b.iter(|| deliver_each(events)),
+    );
+    group.bench_with_input(
+        BenchmarkId::new("batched", events.len()),
+        &events,
+        |b, events| b.iter(|| deliver_batched(events)),
+    );
+    group.finish();
+    println!(
+        "deliveries to the webview for {} events: each {}, batched {}",
+        events.len(),
+        deliver_each(&events),
+        deliver_batched(&events)
+    );
+}
+
+criterion_group!(benches, bench_delivery);
+criterion_main!(benches);
@@ -1,13 +1,22 @@
 // Copyright 2026 hrzlgnm
 // SPDX-License-Identifier: MIT-0
 
+use base64::{Engine, engine::general_purpose::STANDARD};
 use reactive_stores::Store;
 use serde::{Deserialize, Serialize};
+use shared_constants::{
+    BROWSE_RETRY_ATTEMPTS, BROWSE_RETRY_DELAY, INTERFACES_LIST_CHECK_INTERVAL,
+    MDNS_SD_IP_CHECK_INTERVAL, METRICS_CHECK_INTERVAL, UPDATE_CHECK_INTERVAL_HOURS,
+    UPDATE_REMIND_LATER_DELAY, VERIFY_TIMEOUT,
+};
 use std::{
+    cmp::Ordering,
     collections::{BTreeSet, HashMap},
     fmt::Display,
     net::IpAddr,
-    time::SystemTime,
+    ops::RangeInclusive,
+    str::FromStr,
+    time::{Duration, SystemTime},
 };
 
 pub type ServiceTypes = Vec<String>;
@@ -15,7 +24,49 @@ pub type ServiceTypes = Vec<String>;
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Store)]
 pub struct TxtRecord {
     pub key: String,
+    /// The value for display, control characters escaped and binary values hexlified
     pub val: Option<String>,
+    /// The value as received, `None` for an attribute without `=`
+    #[serde(default)]
+    pub raw: Option<Vec<u8>>,
+}
+
+/// What the value of a TXT record holds, see RFC 6763 section 6.4.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxtValueKind {
+    /// The attribute has no `=`, it is a boolean attribute
+    Absent,
+    /// The attribute has an `=` but nothing after it
+    Empty,
+    /// The value is valid UTF-8
+    Text,
+    /// The value is not valid UTF-8
+    Binary,
+}
+
+/// Representations the value of a TXT record can be shown in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TxtValueFormat {
+    #[default]
+    Text,
+    Hex,
+    Base64,
+}
+
+impl TxtValueFormat {
+    pub const ALL: [TxtValueFormat; 3] = [
+        TxtValueFormat::Text,
+        TxtValueFormat::Hex,
+        TxtValueFormat::Base64,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TxtValueFormat::Text => "Text",
+            TxtValueFormat::Hex => "Hex",
+            TxtValueFormat::Base64 => "Base64",
+        }
+    }
 }
 
 impl Display for TxtRecord {
@@ -29,6 +80,56 @@ impl Display for TxtRecord {
 }
 
 impl TxtRecord {
+    pub fn new(key: impl Into<String>, val: Option<&[u8]>) -> Self {
+        Self {
+            key: key.into(),
+            val: bytes_option_to_string_option_with_escaping(val),
+            raw: val.map(|bytes| bytes.to_vec()),
+        }
+    }
+
+    pub fn value_kind(&self) -> TxtValueKind {
+        match (&self.raw, &self.val) {
+            (Some(raw), _) if raw.is_empty() => TxtValueKind::Empty,
+            (Some(raw), _) if std::str::from_utf8(raw).is_err() => TxtValueKind::Binary,
+            (Some(_), _) => TxtValueKind::Text,
+            // Sent by a backend that did not keep the raw bytes yet
+            (None, Some(val)) if val.is_empty() => TxtValueKind::Empty,
+            (None, Some(_)) => TxtValueKind::Text,
+            (None, None) => TxtValueKind::Absent,
+        }
+    }
+
+    /// Returns the value in the given representation, `None` for an attribute without value.
+    ///
+    /// As text, bytes that are not valid UTF-8 are shown as `\xNN`, so readable parts of binary
+    /// values stay readable.
+    pub fn format_value(&self, format: TxtValueFormat) -> Option<String> {
+        let Some(raw) = &self.raw else {
+            return match format {
+                TxtValueFormat::Text => self.val.clone(),
+                TxtValueFormat::Hex => self
+                    .val
+                    .as_deref()
+                    .map(|v| byte_array_hexlified(v.as_bytes())),
+                TxtValueFormat::Base64 => self.val.as_deref().map(|v| STANDARD.encode(v)),
+            };
+        };
+        Some(match format {
+            TxtValueFormat::Text => bytes_with_escaping(raw),
+            TxtValueFormat::Hex => byte_array_hexlified(raw),
+            TxtValueFormat::Base64 => STANDARD.encode(raw),
+        })
+    }
+
+    /// Formats the record as `key=value` with the value in the given representation.
+    pub fn to_string_as(&self, format: TxtValueFormat) -> String {
+        match self.format_value(format) {
+            Some(val) => format!("{}={}", self.key, val),
+            None => self.key.clone(),
+        }
+    }
+
     pub fn matches_query(&self, query: &str) -> bool {
         let query = query.to_lowercase();
         if query.is_empty() {
@@ -36,6 +137,108 @@ impl TxtRecord {
         }
         self.to_string().to_lowercase().contains(&query)
     }
+
+    /// Returns the size of the record on the wire, including its length byte.
+    pub fn encoded_len(&self) -> usize {
+        let val_len = match (&self.raw, &self.val) {
+            (Some(raw), _) => Some(raw.len()),
+            (None, val) => val.as_ref().map(String::len),
+        };
+        1 + self.key.len() + val_len.map_or(0, |len| 1 + len)
+    }
+}
+
+/// TXT sets larger than this do not fit into a single Ethernet packet, see RFC 6763 section 6.2.
+pub const TXT_RECOMMENDED_MAX_SIZE: usize = 1300;
+/// TXT sets larger than this do not fit into a multicast DNS packet, see RFC 6763 section 6.2.
+pub const TXT_MAX_SIZE: usize = 8900;
+/// Keys longer than this are not recommended, see RFC 6763 section 6.4.
+pub const TXT_RECOMMENDED_MAX_KEY_LENGTH: usize = 9;
+
+/// A finding about a TXT set that does not follow the recommendations of RFC 6763.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum TxtWarning {
+    /// The TXT set is larger than [`TXT_RECOMMENDED_MAX_SIZE`] or [`TXT_MAX_SIZE`]
+    TooLarge { size: usize, limit: usize },
+    /// The key is longer than [`TXT_RECOMMENDED_MAX_KEY_LENGTH`]
+    LongKey(String),
+    /// The key contains characters other than printable US-ASCII
+    NonAsciiKey(String),
+    /// The key occurs more than once, compared case insensitively
+    DuplicateKey(String),
+    /// There is an entry without key, like `=value`
+    EmptyKey,
+    /// The TXT set has attributes, but no `txtvers`
+    MissingTxtvers,
+}
+
+impl Display for TxtWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxtWarning::TooLarge { size, limit } => {
+                write!(f, "TXT set has {size} bytes, more than {limit} bytes")
+            }
+            TxtWarning::LongKey(key) => write!(
+                f,
+                "Key {key} is longer than {TXT_RECOMMENDED_MAX_KEY_LENGTH} characters"
+            ),
+            TxtWarning::NonAsciiKey(key) => {
+                write!(
+                    f,
+                    "Key {key} contains non printable or non ASCII characters"
+                )
+            }
+            TxtWarning::DuplicateKey(key) => write!(f, "Key {key} occurs more than once"),
+            TxtWarning::EmptyKey => write!(f, "Entry without key"),
+            TxtWarning::MissingTxtvers => write!(f, "No txtvers key"),
+        }
+    }
+}
+
+/// Checks a TXT set against the size and key recommendations of RFC 6763 sections 6.2 to 6.7.
+pub fn check_txt_records(records: &[TxtRecord]) -> Vec<TxtWarning> {
+    let mut warnings = Vec::new();
+    // An empty TXT set is sent as a single empty string
+    let size = records
+        .iter()
+        .map(TxtRecord::encoded_len)
+        .sum::<usize>()
+        .max(1);
+    if size > TXT_MAX_SIZE {
+        warnings.push(TxtWarning::TooLarge {
+            size,
+            limit: TXT_MAX_SIZE,
+        });
+    } else if size > TXT_RECOMMENDED_MAX_SIZE {
+        warnings.push(TxtWarning::TooLarge {
+            size,
+            limit: TXT_RECOMMENDED_MAX_SIZE,
+        });
+    }
+
+    let mut seen = BTreeSet::new();
+    let mut duplicates = BTreeSet::new();
+    for record in records {
+        if record.key.is_empty() {
+            warnings.push(TxtWarning::EmptyKey);
+            continue;
+        }
+        if !record.key.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+            warnings.push(TxtWarning::NonAsciiKey(record.key.clone()));
+        }
+        if record.key.chars().count() > TXT_RECOMMENDED_MAX_KEY_LENGTH {
+            warnings.push(TxtWarning::LongKey(record.key.clone()));
+        }
+        let key = record.key.to_lowercase();
+        if !seen.insert(key.clone()) && duplicates.insert(key) {
+            warnings.push(TxtWarning::DuplicateKey(record.key.clone()));
+        }
+    }
+
+    if !records.is_empty() && !seen.contains("txtvers") {
+        warnings.push(TxtWarning::MissingTxtvers);
+    }
+    warnings
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Store)]
@@ -86,6 +289,70 @@ impl ScopedAddr {
     }
 }
 
+/// An interface a service was heard on, with the IP families seen on it and
+/// when it was first and last heard there.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Store)]
+pub struct InterfaceSighting {
+    pub interface: InterfaceScope,
+    pub ipv4: bool,
+    pub ipv6: bool,
+    #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+    pub first_seen_micros: u64,
+    #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+    pub last_seen_micros: u64,
+}
+
+impl InterfaceSighting {
+    /// Returns the IP families seen on the interface, e.g. `IPv4, IPv6`.
+    pub fn families(&self) -> String {
+        match (self.ipv4, self.ipv6) {
+            (true, true) => "IPv4, IPv6".to_string(),
+            (true, false) => "IPv4".to_string(),
+            (false, true) => "IPv6".to_string(),
+            (false, false) => String::new(),
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.ipv4 |= other.ipv4;
+        self.ipv6 |= other.ipv6;
+        self.first_seen_micros = self.first_seen_micros.min(other.first_seen_micros);
+        self.last_seen_micros = self.last_seen_micros.max(other.last_seen_micros);
+    }
+}
+
+impl Display for InterfaceSighting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.interface.name, self.families())
+    }
+}
+
+/// Breaks the addresses of a resolution down by the interface they were heard
+/// on, stamping every interface as first and last seen at `at_micros`.
+pub fn interface_sightings(addresses: &[ScopedAddr], at_micros: u64) -> Vec<InterfaceSighting> {
+    let mut sightings: Vec<InterfaceSighting> = Vec::new();
+    for addr in addresses {
+        for interface in &addr.interfaces {
+            let sighting = InterfaceSighting {
+                interface: interface.clone(),
+                ipv4: addr.addr.is_ipv4(),
+                ipv6: addr.addr.is_ipv6(),
+                first_seen_micros: at_micros,
+                last_seen_micros: at_micros,
+            };
+            match sightings
+                .iter_mut()
+                .find(|s| s.interface == sighting.interface)
+            {
+                Some(existing) => existing.merge(&sighting),
+                None => sightings.push(sighting),
+            }
+        }
+    }
+    sightings.sort_by(|a, b| a.interface.cmp(&b.interface));
+    sightings
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Store)]
 pub struct ResolvedService {
     pub instance_fullname: String,
@@ -98,9 +365,12 @@ pub struct ResolvedService {
     #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
     pub updated_at_micros: u64,
     pub dead: bool,
+    #[serde(default)]
+    pub sightings: Vec<InterfaceSighting>,
 }
 
 impl ResolvedService {
+    /// Returns the instance name, the instance label as mdns-sd reports it, without DNS escapes.
     pub fn get_instance_name(&self) -> String {
         self.instance_fullname
             .strip_suffix(&self.service_type)
@@ -110,21 +380,61 @@ impl ResolvedService {
             .to_string()
     }
 
+    /// Returns the full instance name in DNS presentation format, with dots and backslashes of
+    /// the instance label escaped, as tools like `dns-sd` or `avahi-browse` expect it.
+    pub fn get_escaped_instance_fullname(&self) -> String {
+        match self
+            .instance_fullname
+            .strip_suffix(&self.service_type)
+            .and_then(|instance| instance.strip_suffix('.'))
+        {
+            Some(instance) => format!("{}.{}", escape_dns_label(instance), self.service_type),
+            None => self.instance_fullname.clone(),
+        }
+    }
+
     pub fn die_at(&mut self, at_micros: u64) {
         self.dead = true;
         self.updated_at_micros = at_micros;
     }
 
+    /// Returns for how long the service has been dead at `now_micros`, `None` if it is alive.
+    pub fn dead_for_micros(&self, now_micros: u64) -> Option<u64> {
+        self.dead
+            .then(|| now_micros.saturating_sub(self.updated_at_micros))
+    }
+
+    /// Merges the interface sightings of a previous resolution of this service
+    /// into the current ones, so interfaces and IP families heard earlier are
+    /// kept and first seen timestamps survive re-resolutions.
+    pub fn merge_sightings(&mut self, previous: &[InterfaceSighting]) {
+        for sighting in previous {
+            match self
+                .sightings
+                .iter_mut()
+                .find(|s| s.interface == sighting.interface)
+            {
+                Some(existing) => existing.merge(sighting),
+                None => self.sightings.push(sighting.clone()),
+            }
+        }
+        self.sightings.sort_by(|a, b| a.interface.cmp(&b.interface));
+    }
+
+    /// Returns the findings about the TXT set, see [`check_txt_records`].
+    pub fn txt_warnings(&self) -> Vec<TxtWarning> {
+        check_txt_records(&self.txt)
+    }
+
+    /// Whether `other` differs from this service in nothing but `updated_at_micros`.
+    ///
+    /// Interface sightings are compared including their timestamps, so an update that only
+    /// refreshes when the service was last seen on an interface still counts as a change.
     pub fn matches_except_updated_at(&self, other: &Self) -> bool {
-        let self_normalized = ResolvedService {
-            updated_at_micros: 0,
+        ResolvedService {
+            updated_at_micros: other.updated_at_micros,
             ..self.clone()
-        };
-        let other_normalized = ResolvedService {
-            updated_at_micros: 0,
-            ..other.clone()
-        };
-        self_normalized == other_normalized
+        } == *other
     }
 
     pub fn matches_query(&self, query: &str) -> bool {
@@ -150,6 +460,25 @@ impl ResolvedService {
     }
 }
 
+/// Escapes a DNS label in presentation format, see RFC 1035 section 5.1 and RFC 6763 section 4.3.
+///
+/// Dots and backslashes are escaped with a backslash, ASCII control characters as `\DDD` decimal
+/// escapes. Everything else, including spaces and non-ASCII characters, is kept as is.
+pub fn escape_dns_label(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '.' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_ascii_control() => escaped.push_str(&format!("\\{:03}", u32::from(c))),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 pub fn timestamp_micros() -> u64 {
     let now = SystemTime::now();
     let since_epoch = now
@@ -159,6 +488,143 @@ pub fn timestamp_micros() -> u64 {
     since_epoch.as_secs() * 1_000_000 + u64::from(since_epoch.subsec_micros())
 }
 
+/// Compares strings the way humans expect, so `printer-2` sorts before `printer-10`.
+///
+/// Runs of digits compare by their numeric value, everything else compares case insensitive.
+/// Strings that only differ in case or leading zeros fall back to a plain comparison, so the
+/// ordering is total.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x_digits = take_digits(&mut a_chars);
+                let y_digits = take_digits(&mut b_chars);
+                let x_trimmed = x_digits.trim_start_matches('0');
+                let y_trimmed = y_digits.trim_start_matches('0');
+                let ordering = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits
+}
+
+/// Compares address lists numerically, IPv4 addresses before IPv6 addresses.
+///
+/// Each list is compared by its addresses in ascending order, regardless of the order they were
+/// resolved in, services without addresses sort last.
+pub fn cmp_addresses(a: &[ScopedAddr], b: &[ScopedAddr]) -> Ordering {
+    fn sorted(addresses: &[ScopedAddr]) -> Vec<IpAddr> {
+        let mut addrs: Vec<IpAddr> = addresses.iter().map(|addr| addr.addr).collect();
+        // `IpAddr` orders all IPv4 addresses before IPv6 addresses, each numerically
+        addrs.sort();
+        addrs
+    }
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => sorted(a).cmp(&sorted(b)),
+    }
+}
+
+/// A property of a resolved service to sort by.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SortField {
+    Instance,
+    Hostname,
+    ServiceType,
+    Port,
+    Address,
+    LastUpdated,
+}
+
+impl SortField {
+    pub fn compare(&self, a: &ResolvedService, b: &ResolvedService) -> Ordering {
+        match self {
+            SortField::Instance => natural_cmp(&a.instance_fullname, &b.instance_fullname),
+            SortField::Hostname => natural_cmp(&a.hostname, &b.hostname),
+            SortField::ServiceType => natural_cmp(&a.service_type, &b.service_type),
+            SortField::Port => a.port.cmp(&b.port),
+            SortField::Address => cmp_addresses(&a.addresses, &b.addresses),
+            SortField::LastUpdated => a.updated_at_micros.cmp(&b.updated_at_micros),
+        }
+    }
+}
+
+/// A single key of a multi-key sort.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SortCriterion {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl SortCriterion {
+    pub fn ascending(field: SortField) -> Self {
+        Self {
+            field,
+            descending: false,
+        }
+    }
+
+    pub fn descending(field: SortField) -> Self {
+        Self {
+            field,
+            descending: true,
+        }
+    }
+
+    pub fn compare(&self, a: &ResolvedService, b: &ResolvedService) -> Ordering {
+        let ordering = self.field.compare(a, b);
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// Compares services by each criterion in turn, the next criterion breaks ties of the previous.
+///
+/// Services equal by all criteria are ordered by their instance name, so the resulting order does
+/// not depend on the order the services were resolved in.
+pub fn compare_services(
+    a: &ResolvedService,
+    b: &ResolvedService,
+    criteria: &[SortCriterion],
+) -> Ordering {
+    criteria
+        .iter()
+        .map(|criterion| criterion.compare(a, b))
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or_else(|| SortField::Instance.compare(a, b))
+}
+
 fn string_with_control_characters_escaped(input: String) -> String {
     input
         .chars()
@@ -187,13 +653,71 @@ fn byte_array_hexlified(byte_array: &[u8]) -> String {
         .join("")
 }
 
-/// A network interface that can be selected for mDNS browsing.
+/// Escapes control characters like [`bytes_option_to_string_option_with_escaping`] does, and
+/// bytes that are not part of valid UTF-8 as `\xNN`.
+fn bytes_with_escaping(bytes: &[u8]) -> String {
+    bytes
+        .utf8_chunks()
+        .map(|chunk| {
+            let mut escaped = string_with_control_characters_escaped(chunk.valid().to_string());
+            for byte in chunk.invalid() {
+                escaped.push_str(&format!(r"\x{byte:02x}"));
+            }
+            escaped
+        })
+        .collect()
+}
+
+/// Link level flags of a network interface.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceFlags {
+    pub up: bool,
+    pub multicast: bool,
+    /// `None` where the platform does not report it, like on Windows.
+    pub broadcast: Option<bool>,
+    pub point_to_point: bool,
+    pub loopback: bool,
+}
+
+impl Display for InterfaceFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let flags = [
+            (self.up, "UP"),
+            (self.multicast, "MULTICAST"),
+            (self.broadcast == Some(true), "BROADCAST"),
+            (self.point_to_point, "POINTOPOINT"),
+            (self.loopback, "LOOPBACK"),
+        ];
+        let set = flags
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>();
+        write!(f, "{}", set.join(", "))
+    }
+}
+
+/// A network interface of the host, together with the details that determine whether it can be
+/// selected for mDNS browsing.
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug, Store)]
 #[serde(rename_all = "camelCase")]
 pub struct NetworkInterface {
     pub name: String,
     pub addresses: Vec<String>,
     pub enabled: bool,
+    pub index: u32,
+    pub mac: Option<String>,
+    pub flags: InterfaceFlags,
+    pub mtu: Option<u32>,
+    /// Why the interface cannot be used for mDNS, `None` if it can be selected.
+    pub incapable_reason: Option<String>,
+}
+
+impl NetworkInterface {
+    pub fn is_mdns_capable(&self) -> bool {
+        self.incapable_reason.is_none()
+    }
 }
 
 /// Event emitted when the set of mDNS-capable network interfaces changes.
@@ -203,19 +727,25 @@ pub struct InterfacesChangedEvent {
     pub interfaces: Vec<NetworkInterface>,
 }
 
+/// Event emitted when a profile was activated by a change of the network interfaces.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
+pub struct ProfilesChangedEvent {
+    pub profiles: ProfileSettings,
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
 pub struct MetricsChangedEvent {
     pub metrics: HashMap<String, i64>,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct ServiceResolvedEvent {
-    pub service: ResolvedService,
+pub struct ServiceTypeFoundEvent {
+    pub service_type: String,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct ServiceTypeFoundEvent {
-    pub service_type: String,
+pub struct ServiceSubtypeFoundEvent {
+    pub subtype: String,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -225,6 +755,93 @@ pub struct ServiceRemovedEvent {
     pub at_micros: u64,
 }
 
+/// Resolved and removed services coalesced over a short interval, delivered as one event.
+///
+/// Consumers apply `resolved` before `removed`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct ServicesBatchEvent {
+    pub resolved: Vec<ResolvedService>,
+    pub removed: Vec<ServiceRemovedEvent>,
+}
+
+impl ServicesBatchEvent {
+    pub fn is_empty(&self) -> bool {
+        self.resolved.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Coalesces service events into batches, so chatty networks do not flood the webview.
+///
+/// Repeated resolutions of a service within a batch collapse into the latest one, and resolutions
+/// that do not differ from the last delivered version, apart from the update timestamp, are
+/// dropped.
+#[derive(Debug, Default)]
+pub struct EventCoalescer {
+    pending: ServicesBatchEvent,
+    delivered: HashMap<String, ResolvedService>,
+}
+
+impl EventCoalescer {
+    pub fn push_resolved(&mut self, service: ResolvedService) {
+        let name = &service.instance_fullname;
+        // A service coming back within the same batch is alive again
+        self.pending
+            .removed
+            .retain(|removed| &removed.instance_name != name);
+        if let Some(pending) = self
+            .pending
+            .resolved
+            .iter_mut()
+            .find(|pending| &pending.instance_fullname == name)
+        {
+            *pending = service;
+            return;
+        }
+        if self
+            .delivered
+            .get(name)
+            .is_some_and(|delivered| delivered.matches_except_updated_at(&service))
+        {
+            return;
+        }
+        self.pending.resolved.push(service);
+    }
+
+    pub fn push_removed(&mut self, removed: ServiceRemovedEvent) {
+        self.delivered.remove(&removed.instance_name);
+        if !self
+            .pending
+            .removed
+            .iter()
+            .any(|pending| pending.instance_name == removed.instance_name)
+        {
+            self.pending.removed.push(removed);
+        }
+    }
+
+    /// Takes the pending batch, `None` if nothing changed since the last batch.
+    pub fn take_batch(&mut self) -> Option<ServicesBatchEvent> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let batch = std::mem::take(&mut self.pending);
+        for service in &batch.resolved {
+            self.delivered
+                .insert(service.instance_fullname.clone(), service.clone());
+        }
+        for removed in &batch.removed {
+            self.delivered.remove(&removed.instance_name);
+        }
+        Some(batch)
+    }
+
+    /// Forgets what was delivered, e.g. when a fresh browse starts.
+    pub fn reset(&mut self) {
+        self.pending = ServicesBatchEvent::default();
+        self.delivered.clear();
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct ThemeChangedEvent {
     pub theme: String,
@@ -250,98 +867,601 @@ impl Default for ProtocolFlags {
 pub struct UpdateMetadata {
     pub version: String,
     pub current_version: String,
+    pub notes: Option<String>,
+    pub pub_date: Option<String>,
 }
 
-#[derive(Debug, PartialEq, thiserror::Error)]
-pub enum MdnsError {
-    #[error("The trailing dot is missing")]
-    MissingTrailingDot,
-    #[error("The service type label is not well formed")]
-    InvalidService,
-    #[error("The service sub type label is not well formed")]
-    InvalidSubType,
-    #[error("The service sub label is invalid, expected `_sub`")]
-    InvalidSublabel,
-    #[error("The protocol is invalid, expected `_tcp` or `_udp`")]
-    InvalidProtocol,
-    #[error("The domain is invalid, expected `.local.`")]
-    InvalidDomain,
-    #[error("The service type format is incorrect, expected to contain 3 or 5 parts")]
-    IncorrectFormat,
+/// Timings that can be tuned at runtime, e.g. for large networks that need longer verify windows
+/// and slower polling.
+#[derive(Deserialize, Serialize, Clone, Copy, Eq, PartialEq, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimingConfig {
+    pub verify_timeout_secs: u64,
+    pub metrics_check_interval_secs: u64,
+    pub interfaces_check_interval_secs: u64,
+    pub ip_check_interval_secs: u64,
+    pub browse_retry_delay_millis: u64,
+    pub browse_retry_attempts: u64,
 }
 
-#[derive(PartialEq, Eq, Debug)]
-pub enum MdnsLabelType {
-    ServiceType,
-    SubType,
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self {
+            verify_timeout_secs: VERIFY_TIMEOUT.as_secs(),
+            metrics_check_interval_secs: METRICS_CHECK_INTERVAL.as_secs(),
+            interfaces_check_interval_secs: INTERFACES_LIST_CHECK_INTERVAL.as_secs(),
+            ip_check_interval_secs: MDNS_SD_IP_CHECK_INTERVAL.as_secs(),
+            browse_retry_delay_millis: BROWSE_RETRY_DELAY.as_millis() as u64,
+            browse_retry_attempts: BROWSE_RETRY_ATTEMPTS,
+        }
+    }
 }
 
-fn check_mdns_label(label: &str, is_subtype: bool) -> Result<MdnsLabelType, MdnsError> {
-    let valid_dns_chars = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
-    let error = if is_subtype {
-        MdnsError::InvalidSubType
-    } else {
-        MdnsError::InvalidService
-    };
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum TimingConfigError {
+    #[error("{field} must be between {min} and {max}, got {value}")]
+    OutOfRange {
+        field: &'static str,
+        min: u64,
+        max: u64,
+        value: u64,
+    },
+}
 
-    if !label.starts_with('_') {
-        return Err(error);
+impl TimingConfig {
+    pub const VERIFY_TIMEOUT_SECS: RangeInclusive<u64> = 1..=300;
+    pub const CHECK_INTERVAL_SECS: RangeInclusive<u64> = 1..=300;
+    pub const BROWSE_RETRY_DELAY_MILLIS: RangeInclusive<u64> = 1..=10_000;
+    pub const BROWSE_RETRY_ATTEMPTS: RangeInclusive<u64> = 0..=1_000;
+
+    /// Checks that all timings are within their supported ranges.
+    pub fn validate(&self) -> Result<(), TimingConfigError> {
+        let check = |field: &'static str, value: u64, range: RangeInclusive<u64>| {
+            if range.contains(&value) {
+                Ok(())
+            } else {
+                Err(TimingConfigError::OutOfRange {
+                    field,
+                    min: *range.start(),
+                    max: *range.end(),
+                    value,
+                })
+            }
+        };
+        check(
+            "Verify timeout",
+            self.verify_timeout_secs,
+            Self::VERIFY_TIMEOUT_SECS,
+        )?;
+        check(
+            "Metrics check interval",
+            self.metrics_check_interval_secs,
+            Self::CHECK_INTERVAL_SECS,
+        )?;
+        check(
+            "Interfaces check interval",
+            self.interfaces_check_interval_secs,
+            Self::CHECK_INTERVAL_SECS,
+        )?;
+        check(
+            "IP check interval",
+            self.ip_check_interval_secs,
+            Self::CHECK_INTERVAL_SECS,
+        )?;
+        check(
+            "Browse retry delay",
+            self.browse_retry_delay_millis,
+            Self::BROWSE_RETRY_DELAY_MILLIS,
+        )?;
+        check(
+            "Browse retry attempts",
+            self.browse_retry_attempts,
+            Self::BROWSE_RETRY_ATTEMPTS,
+        )
     }
 
-    let label_content = &label[1..];
-
-    // Ensure the label content doesn't start with an underscore
-    if label_content.starts_with('_') {
-        return Err(error);
+    pub fn verify_timeout(&self) -> Duration {
+        Duration::from_secs(self.verify_timeout_secs)
     }
 
-    // Ensure the label content doesn't end with an underscore
-    if label_content.ends_with('_') {
-        return Err(error);
+    pub fn metrics_check_interval(&self) -> Duration {
+        Duration::from_secs(self.metrics_check_interval_secs)
     }
 
-    if !label_content.chars().all(valid_dns_chars) {
-        return Err(error);
+    pub fn interfaces_check_interval(&self) -> Duration {
+        Duration::from_secs(self.interfaces_check_interval_secs)
     }
 
-    // Ensure no double hyphens are present
-    if label_content.contains("--") {
-        return Err(error);
+    pub fn browse_retry_delay(&self) -> Duration {
+        Duration::from_millis(self.browse_retry_delay_millis)
     }
+}
 
-    // Ensure the label does not start or end with a hyphen
-    if label_content.starts_with('-') || label_content.ends_with('-') {
-        return Err(error);
-    }
+/// Policy for services that were removed from the network.
+///
+/// By default dead services are kept forever, until browsing is restarted.
+#[derive(Deserialize, Serialize, Clone, Copy, Eq, PartialEq, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DeadServicePolicy {
+    /// Seconds after which dead services are hidden, `None` to keep showing them.
+    pub hide_after_secs: Option<u64>,
+    /// Minutes after which dead services are purged, `None` to keep them.
+    pub purge_after_mins: Option<u64>,
+}
 
-    if is_subtype {
-        Ok(MdnsLabelType::SubType)
-    } else {
-        Ok(MdnsLabelType::ServiceType)
-    }
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum DeadServicePolicyError {
+    #[error("{field} must be between {min} and {max}, got {value}")]
+    OutOfRange {
+        field: &'static str,
+        min: u64,
+        max: u64,
+        value: u64,
+    },
 }
 
-pub fn check_service_type_fully_qualified(service_type: &str) -> Result<MdnsLabelType, MdnsError> {
-    // The service type must end with a trailing dot
-    if !service_type.ends_with('.') {
-        return Err(MdnsError::MissingTrailingDot);
+impl DeadServicePolicy {
+    pub const HIDE_AFTER_SECS: RangeInclusive<u64> = 1..=24 * 60 * 60;
+    pub const PURGE_AFTER_MINS: RangeInclusive<u64> = 1..=7 * 24 * 60;
+
+    /// Checks that the configured delays are within their supported ranges.
+    pub fn validate(&self) -> Result<(), DeadServicePolicyError> {
+        let check =
+            |field: &'static str, value: Option<u64>, range: RangeInclusive<u64>| match value {
+                Some(value) if !range.contains(&value) => Err(DeadServicePolicyError::OutOfRange {
+                    field,
+                    min: *range.start(),
+                    max: *range.end(),
+                    value,
+                }),
+                _ => Ok(()),
+            };
+        check("Hide delay", self.hide_after_secs, Self::HIDE_AFTER_SECS)?;
+        check("Purge delay", self.purge_after_mins, Self::PURGE_AFTER_MINS)
     }
 
-    // Remove the trailing dot for validation purposes
-    let service_type = service_type.strip_suffix('.').expect("To end with .");
+    /// Whether the service should no longer be shown at `now_micros`.
+    ///
+    /// Purged services are hidden as well, even if no hide delay is configured.
+    pub fn is_hidden(&self, service: &ResolvedService, now_micros: u64) -> bool {
+        self.hide_after_secs
+            .is_some_and(|secs| Self::dead_for_at_least(service, now_micros, secs))
+            || self.is_purged(service, now_micros)
+    }
 
-    // Split into parts based on dots
-    let parts: Vec<&str> = service_type.split('.').collect();
+    /// Whether the service should be dropped entirely at `now_micros`.
+    pub fn is_purged(&self, service: &ResolvedService, now_micros: u64) -> bool {
+        self.purge_after_mins.is_some_and(|mins| {
+            Self::dead_for_at_least(service, now_micros, mins.saturating_mul(60))
+        })
+    }
 
-    // Validate the number of parts for formats:
-    // 1) _service._protocol.local
-    // 2) _subtype._sub._service._protocol.local
-    if parts.len() != 3 && parts.len() != 5 {
-        return Err(MdnsError::IncorrectFormat);
+    fn dead_for_at_least(service: &ResolvedService, now_micros: u64, secs: u64) -> bool {
+        service
+            .dead_for_micros(now_micros)
+            .is_some_and(|dead_for| dead_for >= secs.saturating_mul(1_000_000))
     }
+}
 
-    let domain = parts.last().expect("To have a domain"); // Domain is always the last component
-    let protocol = parts[parts.len() - 2]; // Protocol is the second-to-last component
+/// Settings of the scheduled update checks, along with the user's answers to the updates they
+/// offered.
+#[derive(Deserialize, Serialize, Clone, Eq, PartialEq, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdateCheckSettings {
+    /// Whether updates are checked for periodically.
+    pub enabled: bool,
+    /// Hours between two scheduled checks.
+    pub interval_hours: u64,
+    /// Whether scheduled checks are skipped while the network is metered, like mobile data.
+    pub skip_on_metered: bool,
+    /// A version the user chose to skip, which scheduled checks do not offer again.
+    pub skipped_version: Option<String>,
+    /// Until when the user asked to be reminded later, in microseconds since the unix epoch.
+    pub remind_after_micros: Option<u64>,
+    /// When the last scheduled check started, in microseconds since the unix epoch.
+    pub last_check_micros: Option<u64>,
+}
+
+impl Default for UpdateCheckSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: UPDATE_CHECK_INTERVAL_HOURS,
+            skip_on_metered: true,
+            skipped_version: None,
+            remind_after_micros: None,
+            last_check_micros: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum UpdateCheckSettingsError {
+    #[error("Update check interval must be between {min} and {max} hours, got {value}")]
+    IntervalOutOfRange { min: u64, max: u64, value: u64 },
+}
+
+impl UpdateCheckSettings {
+    pub const INTERVAL_HOURS: RangeInclusive<u64> = 1..=24 * 30;
+
+    pub fn validate(&self) -> Result<(), UpdateCheckSettingsError> {
+        if Self::INTERVAL_HOURS.contains(&self.interval_hours) {
+            Ok(())
+        } else {
+            Err(UpdateCheckSettingsError::IntervalOutOfRange {
+                min: *Self::INTERVAL_HOURS.start(),
+                max: *Self::INTERVAL_HOURS.end(),
+                value: self.interval_hours,
+            })
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_hours.saturating_mul(60 * 60))
+    }
+
+    /// Whether a scheduled check is due at `now_micros`, on a network that is `metered` or not.
+    pub fn is_check_due(&self, now_micros: u64, metered: bool) -> bool {
+        self.enabled
+            && !(metered && self.skip_on_metered)
+            && self.last_check_micros.is_none_or(|last_check| {
+                now_micros.saturating_sub(last_check) >= self.interval().as_micros() as u64
+            })
+    }
+
+    /// Whether a scheduled check offers the update to `version` at `now_micros`.
+    ///
+    /// Checks started by the user always offer updates.
+    pub fn offers(&self, version: &str, now_micros: u64) -> bool {
+        self.skipped_version.as_deref() != Some(version)
+            && self
+                .remind_after_micros
+                .is_none_or(|remind_after| now_micros >= remind_after)
+    }
+
+    /// Stops offering `version` in scheduled checks, newer versions are offered again.
+    pub fn skip_version(&mut self, version: &str) {
+        self.skipped_version = Some(version.to_string());
+    }
+
+    /// Stops offering updates in scheduled checks for a while from `now_micros` on.
+    pub fn remind_later(&mut self, now_micros: u64) {
+        self.remind_after_micros =
+            Some(now_micros.saturating_add(UPDATE_REMIND_LATER_DELAY.as_micros() as u64));
+    }
+}
+
+/// An IP network in CIDR notation, like `192.168.10.0/24` or `fd00::/8`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Subnet {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl Subnet {
+    /// Whether `addr` lies in the subnet, addresses of the other IP family never do.
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        // A shift by the full width yields `None`, which is the case for a prefix length of 0
+        match (self.addr, addr) {
+            (IpAddr::V4(network), IpAddr::V4(addr)) => (u32::from(network) ^ u32::from(*addr))
+                .checked_shr(32 - u32::from(self.prefix_len))
+                .is_none_or(|diff| diff == 0),
+            (IpAddr::V6(network), IpAddr::V6(addr)) => (u128::from(network) ^ u128::from(*addr))
+                .checked_shr(128 - u32::from(self.prefix_len))
+                .is_none_or(|diff| diff == 0),
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Subnet {
+    type Err = ProfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ProfileError::InvalidSubnet(s.to_string());
+        let (addr, prefix_len) = s.split_once('/').ok_or_else(invalid)?;
+        let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+        let prefix_len: u8 = prefix_len.parse().map_err(|_| invalid())?;
+        let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max_prefix_len {
+            return Err(invalid());
+        }
+        Ok(Self { addr, prefix_len })
+    }
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ProfileError {
+    #[error("Profile name must not be empty")]
+    EmptyName,
+    #[error("Invalid service type {service_type}: {source}")]
+    InvalidServiceType {
+        service_type: String,
+        source: MdnsError,
+    },
+    #[error("Invalid subnet {0}, expected an address and a prefix length like 192.168.1.0/24")]
+    InvalidSubnet(String),
+}
+
+/// Settings for browsing a particular network, like the office, a lab or a customer's network.
+#[derive(Deserialize, Serialize, Clone, Eq, PartialEq, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Profile {
+    pub name: String,
+    /// Names of the interfaces that are not used for browsing.
+    pub disabled_interfaces: BTreeSet<String>,
+    pub protocol_flags: ProtocolFlags,
+    /// Service types to browse for, all service types found on the network if empty.
+    pub service_types: ServiceTypes,
+    /// Interface names or subnets, like `192.168.10.0/24`, whose presence activates the profile.
+    pub auto_activate: Vec<String>,
+}
+
+impl Profile {
+    pub fn validate(&self) -> Result<(), ProfileError> {
+        if self.name.trim().is_empty() {
+            return Err(ProfileError::EmptyName);
+        }
+        for service_type in &self.service_types {
+            check_service_type_fully_qualified(service_type).map_err(|source| {
+                ProfileError::InvalidServiceType {
+                    service_type: service_type.clone(),
+                    source,
+                }
+            })?;
+        }
+        for rule in self
+            .auto_activate
+            .iter()
+            .filter(|rule| is_subnet_rule(rule))
+        {
+            rule.parse::<Subnet>()?;
+        }
+        Ok(())
+    }
+
+    /// Whether one of the auto-activation rules matches the present `interfaces`, either by the
+    /// name of an interface or by one of its addresses lying in a subnet.
+    pub fn matches(&self, interfaces: &[NetworkInterface]) -> bool {
+        self.auto_activate.iter().any(|rule| {
+            if is_subnet_rule(rule) {
+                rule.parse::<Subnet>().is_ok_and(|subnet| {
+                    interfaces
+                        .iter()
+                        .flat_map(|interface| &interface.addresses)
+                        .filter_map(|addr| addr.parse::<IpAddr>().ok())
+                        .any(|addr| subnet.contains(&addr))
+                })
+            } else {
+                interfaces.iter().any(|interface| interface.name == *rule)
+            }
+        })
+    }
+}
+
+fn is_subnet_rule(rule: &str) -> bool {
+    rule.contains('/')
+}
+
+/// The saved profiles along with the one in use.
+#[derive(Deserialize, Serialize, Clone, Eq, PartialEq, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProfileSettings {
+    pub profiles: Vec<Profile>,
+    /// Name of the active profile, `None` while no profile is active.
+    pub active: Option<String>,
+}
+
+impl ProfileSettings {
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    pub fn active_profile(&self) -> Option<&Profile> {
+        self.active.as_deref().and_then(|name| self.get(name))
+    }
+
+    /// The service types the active profile browses for, `None` to browse all service types.
+    pub fn watched_service_types(&self) -> Option<&ServiceTypes> {
+        self.active_profile()
+            .map(|profile| &profile.service_types)
+            .filter(|service_types| !service_types.is_empty())
+    }
+
+    /// Adds `profile`, replacing a profile of the same name.
+    pub fn save(&mut self, profile: Profile) {
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    /// Removes the profile named `name`, deactivating it if it is active.
+    pub fn remove(&mut self, name: &str) {
+        self.profiles.retain(|profile| profile.name != name);
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+    }
+
+    /// Returns the first profile whose auto-activation rules match the present `interfaces`.
+    pub fn auto_activated(&self, interfaces: &[NetworkInterface]) -> Option<&Profile> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.matches(interfaces))
+    }
+}
+
+/// Authoritative table of the services resolved while browsing, keyed by instance fullname.
+#[derive(Clone, Debug, Default)]
+pub struct ServiceCache {
+    services: HashMap<String, ResolvedService>,
+}
+
+impl ServiceCache {
+    /// Inserts or updates a resolved service and returns the stored version.
+    ///
+    /// Interface sightings of a previous resolution are merged into the new one, so the stored
+    /// service reflects every interface it was heard on.
+    pub fn upsert(&mut self, mut service: ResolvedService) -> ResolvedService {
+        if let Some(previous) = self.services.get(&service.instance_fullname) {
+            service.merge_sightings(&previous.sightings);
+        }
+        self.services
+            .insert(service.instance_fullname.clone(), service.clone());
+        service
+    }
+
+    /// Marks a service as dead, returns the updated service if it was alive.
+    pub fn mark_dead(
+        &mut self,
+        instance_fullname: &str,
+        at_micros: u64,
+    ) -> Option<ResolvedService> {
+        let service = self
+            .services
+            .get_mut(instance_fullname)
+            .filter(|service| !service.dead)?;
+        service.die_at(at_micros);
+        Some(service.clone())
+    }
+
+    pub fn get(&self, instance_fullname: &str) -> Option<&ResolvedService> {
+        self.services.get(instance_fullname)
+    }
+
+    /// Returns all services, sorted by instance fullname.
+    pub fn list(&self) -> Vec<ResolvedService> {
+        self.since(0)
+    }
+
+    /// Returns the services updated at or after `since_micros`, sorted by instance fullname.
+    pub fn since(&self, since_micros: u64) -> Vec<ResolvedService> {
+        let mut services: Vec<_> = self
+            .services
+            .values()
+            .filter(|service| service.updated_at_micros >= since_micros)
+            .cloned()
+            .collect();
+        services.sort_by(|a, b| a.instance_fullname.cmp(&b.instance_fullname));
+        services
+    }
+
+    /// Drops the services that are expired according to `policy`, returns how many were dropped.
+    pub fn purge(&mut self, policy: &DeadServicePolicy, now_micros: u64) -> usize {
+        let before = self.services.len();
+        self.services
+            .retain(|_, service| !policy.is_purged(service, now_micros));
+        before - self.services.len()
+    }
+
+    pub fn clear_dead(&mut self) {
+        self.services.retain(|_, service| !service.dead);
+    }
+
+    pub fn clear(&mut self) {
+        self.services.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.services.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.services.is_empty()
+    }
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum MdnsError {
+    #[error("The trailing dot is missing")]
+    MissingTrailingDot,
+    #[error("The service type label is not well formed")]
+    InvalidService,
+    #[error("The service sub type label is not well formed")]
+    InvalidSubType,
+    #[error("The service sub label is invalid, expected `_sub`")]
+    InvalidSublabel,
+    #[error("The protocol is invalid, expected `_tcp` or `_udp`")]
+    InvalidProtocol,
+    #[error("The domain is invalid, expected `.local.`")]
+    InvalidDomain,
+    #[error("The service type format is incorrect, expected to contain 3 or 5 parts")]
+    IncorrectFormat,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum MdnsLabelType {
+    ServiceType,
+    SubType,
+}
+
+fn check_mdns_label(label: &str, is_subtype: bool) -> Result<MdnsLabelType, MdnsError> {
+    let valid_dns_chars = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let error = if is_subtype {
+        MdnsError::InvalidSubType
+    } else {
+        MdnsError::InvalidService
+    };
+
+    if !label.starts_with('_') {
+        return Err(error);
+    }
+
+    let label_content = &label[1..];
+
+    // Ensure the label content doesn't start with an underscore
+    if label_content.starts_with('_') {
+        return Err(error);
+    }
+
+    // Ensure the label content doesn't end with an underscore
+    if label_content.ends_with('_') {
+        return Err(error);
+    }
+
+    if !label_content.chars().all(valid_dns_chars) {
+        return Err(error);
+    }
+
+    // Ensure no double hyphens are present
+    if label_content.contains("--") {
+        return Err(error);
+    }
+
+    // Ensure the label does not start or end with a hyphen
+    if label_content.starts_with('-') || label_content.ends_with('-') {
+        return Err(error);
+    }
+
+    if is_subtype {
+        Ok(MdnsLabelType::SubType)
+    } else {
+        Ok(MdnsLabelType::ServiceType)
+    }
+}
+
+pub fn check_service_type_fully_qualified(service_type: &str) -> Result<MdnsLabelType, MdnsError> {
+    // The service type must end with a trailing dot
+    if !service_type.ends_with('.') {
+        return Err(MdnsError::MissingTrailingDot);
+    }
+
+    // Remove the trailing dot for validation purposes
+    let service_type = service_type.strip_suffix('.').expect("To end with .");
+
+    // Split into parts based on dots
+    let parts: Vec<&str> = service_type.split('.').collect();
+
+    // Validate the number of parts for formats:
+    // 1) _service._protocol.local
+    // 2) _subtype._sub._service._protocol.local
+    if parts.len() != 3 && parts.len() != 5 {
+        return Err(MdnsError::IncorrectFormat);
+    }
+
+    let domain = parts.last().expect("To have a domain"); // Domain is always the last component
+    let protocol = parts[parts.len() - 2]; // Protocol is the second-to-last component
 
     // Validate protocol name (must be either _tcp or _udp)
     if protocol != "_tcp" && protocol != "_udp" {
@@ -373,541 +1493,2024 @@ pub fn check_service_type_fully_qualified(service_type: &str) -> Result<MdnsLabe
     Ok(MdnsLabelType::ServiceType)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::net::{IpAddr, Ipv4Addr};
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintSeverity {
+    /// The service type can not be browsed.
+    Error,
+    /// The service type can be browsed, but violates an RFC.
+    Warning,
+}
 
-    #[test]
-    fn test_string_with_control_characters_escaped() {
-        assert_eq!(
-            string_with_control_characters_escaped("Hello\nWorld".to_string()),
-            "Hello\\u000aWorld"
-        );
+/// A single finding of [`lint_service_type`] or [`lint_instance_name`].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct LintDiagnostic {
+    pub severity: LintSeverity,
+    pub message: String,
+    /// The offending label, empty if the finding is about the whole input.
+    pub label: String,
+    /// Character position of the finding in the input.
+    pub position: usize,
+    /// A replacement for the offending label that fixes the finding.
+    pub replacement: Option<String>,
+}
 
-        assert_eq!(
-            string_with_control_characters_escaped("Hello\tWorld".to_string()),
-            "Hello\\u0009World"
-        );
+impl Display for LintDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            LintSeverity::Error => "error",
+            LintSeverity::Warning => "warning",
+        };
+        write!(f, "{severity}: {}", self.message)?;
+        if self.label.is_empty() {
+            write!(f, " at position {}", self.position)?;
+        } else {
+            write!(f, " in `{}` at position {}", self.label, self.position)?;
+        }
+        if let Some(replacement) = &self.replacement {
+            write!(f, ", did you mean `{replacement}`?")?;
+        }
+        Ok(())
+    }
+}
 
-        assert_eq!(
-            string_with_control_characters_escaped("Hello World".to_string()),
-            "Hello World"
+/// Findings for a service type, along with a corrected service type if all errors can be fixed.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ServiceTypeLint {
+    pub diagnostics: Vec<LintDiagnostic>,
+    pub suggestion: Option<String>,
+}
+
+impl ServiceTypeLint {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == LintSeverity::Error)
+    }
+}
+
+/// Maximum length of a service name, without the leading underscore, see RFC 6335 section 5.1.
+pub const MAX_SERVICE_NAME_LENGTH: usize = 15;
+/// Maximum length of a DNS label in bytes, see RFC 6763 sections 4.1.1 and 7.2.
+pub const MAX_LABEL_LENGTH: usize = 63;
+/// Maximum length of a DNS name in bytes, see RFC 1035 section 2.3.4.
+pub const MAX_NAME_LENGTH: usize = 255;
+
+struct Linter {
+    diagnostics: Vec<LintDiagnostic>,
+    fixable: bool,
+}
+
+impl Linter {
+    fn report(
+        &mut self,
+        severity: LintSeverity,
+        message: impl Into<String>,
+        label: &str,
+        position: usize,
+        replacement: Option<String>,
+    ) {
+        if severity == LintSeverity::Error && replacement.is_none() {
+            self.fixable = false;
+        }
+        self.diagnostics.push(LintDiagnostic {
+            severity,
+            message: message.into(),
+            label: label.to_string(),
+            position,
+            replacement,
+        });
+    }
+
+    fn error(&mut self, message: impl Into<String>, label: &str, position: usize) {
+        self.report(LintSeverity::Error, message, label, position, None);
+    }
+
+    fn fix(&mut self, message: impl Into<String>, label: &str, position: usize, fixed: &str) {
+        self.report(
+            LintSeverity::Error,
+            message,
+            label,
+            position,
+            Some(fixed.to_string()),
         );
+    }
 
-        assert_eq!(string_with_control_characters_escaped("".to_string()), "");
+    fn warn(&mut self, message: impl Into<String>, label: &str, position: usize) {
+        self.report(LintSeverity::Warning, message, label, position, None);
+    }
+
+    /// Checks a service or subtype label, returning the label with all fixable issues fixed.
+    fn lint_label(&mut self, label: &str, position: usize, is_subtype: bool) -> String {
+        let kind = if is_subtype {
+            "subtype"
+        } else {
+            "service name"
+        };
+        let mut fixed = label.to_string();
+        if !label.starts_with('_') {
+            fixed.insert(0, '_');
+            self.fix(
+                format!("The {kind} must start with an underscore"),
+                label,
+                position,
+                &fixed,
+            );
+        }
+        let content = fixed[1..].to_string();
+        if content.is_empty() {
+            self.error(format!("The {kind} is empty"), label, position);
+            return fixed;
+        }
+        if let Some((index, c)) = content
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_'))
+        {
+            let offset = usize::from(label.starts_with('_'));
+            self.error(
+                format!("The {kind} contains the invalid character `{c}`"),
+                label,
+                position + offset + index,
+            );
+        }
+        let mut trimmed = content.trim_matches(|c| c == '_' || c == '-').to_string();
+        while trimmed.contains("--") {
+            trimmed = trimmed.replace("--", "-");
+        }
+        if trimmed != content {
+            let replacement = format!("_{trimmed}");
+            if trimmed.is_empty() {
+                self.error(format!("The {kind} is not well formed"), label, position);
+            } else {
+                self.fix(
+                    format!("The {kind} must not start or end with `_` or `-`, nor contain `--`"),
+                    label,
+                    position,
+                    &replacement,
+                );
+            }
+            fixed = replacement;
+        }
+        let name = &fixed[1..];
+        if is_subtype {
+            if fixed.len() > MAX_LABEL_LENGTH {
+                self.warn(
+                    format!("The subtype is longer than {MAX_LABEL_LENGTH} bytes (RFC 6763)"),
+                    label,
+                    position,
+                );
+            }
+        } else {
+            if name.chars().count() > MAX_SERVICE_NAME_LENGTH {
+                self.warn(
+                    format!(
+                        "The service name is longer than {MAX_SERVICE_NAME_LENGTH} characters \
+                         (RFC 6335)"
+                    ),
+                    label,
+                    position,
+                );
+            }
+            if name.contains('_') {
+                self.warn(
+                    "The service name should only contain letters, digits and hyphens (RFC 6335)",
+                    label,
+                    position,
+                );
+            }
+            if !name.chars().any(|c| c.is_ascii_alphabetic()) {
+                self.warn(
+                    "The service name should contain at least one letter (RFC 6335)",
+                    label,
+                    position,
+                );
+            }
+        }
+        fixed
+    }
+}
+
+/// Checks a service type in detail, reporting the offending label and its position.
+///
+/// Unlike [`check_service_type_fully_qualified`], this reports all findings, including RFC
+/// violations that do not prevent browsing, and proposes corrections for common mistakes like
+/// a missing trailing dot or `tcp` instead of `_tcp`.
+pub fn lint_service_type(input: &str) -> ServiceTypeLint {
+    let mut linter = Linter {
+        diagnostics: Vec::new(),
+        fixable: true,
+    };
+    let trimmed = input.trim();
+    let leading = input.chars().count() - input.trim_start().chars().count();
+    if trimmed.len() != input.len() {
+        linter.fix("Surrounding whitespace", "", 0, trimmed);
+    }
+    let body = match trimmed.strip_suffix('.') {
+        Some(body) => body,
+        None => {
+            linter.fix(
+                "The trailing dot is missing",
+                "",
+                leading + trimmed.chars().count(),
+                &format!("{trimmed}."),
+            );
+            trimmed
+        }
+    };
+
+    let mut labels = Vec::new();
+    let mut position = leading;
+    for label in body.split('.') {
+        labels.push((label, position));
+        position += label.chars().count() + 1;
+    }
+    let mut fixed: Vec<String> = labels.iter().map(|(label, _)| label.to_string()).collect();
+
+    let is_protocol_like = |label: &str| {
+        matches!(
+            label.trim_start_matches('_').to_ascii_lowercase().as_str(),
+            "tcp" | "udp"
+        )
+    };
+    let missing_domain = match labels.as_slice() {
+        [_, (protocol, _)] => is_protocol_like(protocol),
+        [_, (sub, _), _, (protocol, _)] => {
+            sub.trim_start_matches('_').eq_ignore_ascii_case("sub") && is_protocol_like(protocol)
+        }
+        _ => false,
+    };
+    if missing_domain {
+        linter.fix(
+            "The domain is missing, expected `local`",
+            "",
+            position.saturating_sub(1),
+            "local",
+        );
+        fixed.push("local".to_string());
+        labels.push(("", position));
+    }
+
+    let count = labels.len();
+    if count != 3 && count != 5 {
+        linter.error(
+            "The service type format is incorrect, expected `_service._tcp.local.` or \
+             `_subtype._sub._service._tcp.local.`",
+            "",
+            leading,
+        );
+        return ServiceTypeLint {
+            diagnostics: linter.diagnostics,
+            suggestion: None,
+        };
+    }
+
+    let (domain, domain_position) = labels[count - 1];
+    if !missing_domain && domain != "local" {
+        if domain.eq_ignore_ascii_case("local") {
+            linter.fix(
+                "The domain must be lowercase",
+                domain,
+                domain_position,
+                "local",
+            );
+        } else {
+            linter.error(
+                "The domain is invalid, expected `local`",
+                domain,
+                domain_position,
+            );
+        }
+        fixed[count - 1] = "local".to_string();
+    }
+
+    let (protocol, protocol_position) = labels[count - 2];
+    if protocol != "_tcp" && protocol != "_udp" {
+        if is_protocol_like(protocol) {
+            let replacement = format!("_{}", protocol.trim_start_matches('_').to_ascii_lowercase());
+            linter.fix(
+                "The protocol must be `_tcp` or `_udp`",
+                protocol,
+                protocol_position,
+                &replacement,
+            );
+            fixed[count - 2] = replacement;
+        } else {
+            linter.error(
+                "The protocol is invalid, expected `_tcp` or `_udp`",
+                protocol,
+                protocol_position,
+            );
+        }
+    }
+
+    let (service, service_position) = labels[count - 3];
+    fixed[count - 3] = linter.lint_label(service, service_position, false);
+
+    if count == 5 {
+        let (sub, sub_position) = labels[1];
+        if sub != "_sub" {
+            if sub.trim_start_matches('_').eq_ignore_ascii_case("sub") {
+                linter.fix("Expected `_sub`", sub, sub_position, "_sub");
+            } else {
+                linter.error(
+                    "The sub label is invalid, expected `_sub`",
+                    sub,
+                    sub_position,
+                );
+            }
+            fixed[1] = "_sub".to_string();
+        }
+        let (subtype, subtype_position) = labels[0];
+        fixed[0] = linter.lint_label(subtype, subtype_position, true);
+    }
+
+    let suggestion = format!("{}.", fixed.join("."));
+    let has_errors = linter
+        .diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == LintSeverity::Error);
+    ServiceTypeLint {
+        suggestion: (has_errors && linter.fixable && suggestion != input).then_some(suggestion),
+        diagnostics: linter.diagnostics,
+    }
+}
+
+/// Checks a service instance name against the rules of RFC 6763 section 4.1.1.
+///
+/// Instance names are arbitrary UTF-8 of at most 63 bytes, without control characters.
+pub fn lint_instance_name(name: &str) -> Vec<LintDiagnostic> {
+    let mut linter = Linter {
+        diagnostics: Vec::new(),
+        fixable: true,
+    };
+    if name.is_empty() {
+        linter.error("The instance name is empty", "", 0);
+    }
+    if name.len() > MAX_LABEL_LENGTH {
+        linter.warn(
+            format!("The instance name is longer than {MAX_LABEL_LENGTH} bytes (RFC 6763)"),
+            name,
+            0,
+        );
+    }
+    if let Some((index, c)) = name.chars().enumerate().find(|(_, c)| c.is_control()) {
+        linter.warn(
+            format!(
+                "The instance name contains the control character {:#04x} (RFC 6763)",
+                u32::from(c)
+            ),
+            name,
+            index,
+        );
+    }
+    linter.diagnostics
+}
+
+/// Checks the host name a service resolved to against the rules of RFC 6762 section 16.
+///
+/// Host names are UTF-8 labels of at most 63 bytes each, separated by dots, at most 255 bytes in
+/// total and without control characters. A trailing dot is allowed.
+pub fn lint_hostname(hostname: &str) -> Vec<LintDiagnostic> {
+    let mut linter = Linter {
+        diagnostics: Vec::new(),
+        fixable: true,
+    };
+    let name = hostname.strip_suffix('.').unwrap_or(hostname);
+    if name.is_empty() {
+        linter.error("The host name is empty", "", 0);
+        return linter.diagnostics;
+    }
+    if hostname.len() > MAX_NAME_LENGTH {
+        linter.warn(
+            format!("The host name is longer than {MAX_NAME_LENGTH} bytes (RFC 1035)"),
+            "",
+            0,
+        );
+    }
+    let mut position = 0;
+    for label in name.split('.') {
+        if label.is_empty() {
+            linter.error("The host name contains an empty label", label, position);
+        } else if label.len() > MAX_LABEL_LENGTH {
+            linter.warn(
+                format!("The label is longer than {MAX_LABEL_LENGTH} bytes (RFC 1035)"),
+                label,
+                position,
+            );
+        }
+        if let Some((index, c)) = label.chars().enumerate().find(|(_, c)| c.is_control()) {
+            linter.warn(
+                format!(
+                    "The host name contains the control character {:#04x} (RFC 6762)",
+                    u32::from(c)
+                ),
+                label,
+                position + index,
+            );
+        }
+        position += label.chars().count() + 1;
+    }
+    linter.diagnostics
+}
+
+/// Returns the service type a subtype query like `_printer._sub._http._tcp.local.` belongs to.
+///
+/// Returns `None` if the input is not a subtype query.
+pub fn subtype_service_type(subtype: &str) -> Option<&str> {
+    subtype
+        .split_once("._sub.")
+        .map(|(_, service_type)| service_type)
+}
+
+/// Returns the label of a subtype query like `_printer._sub._http._tcp.local.`, i.e. `_printer`.
+///
+/// Returns `None` if the input is not a subtype query.
+pub fn subtype_label(subtype: &str) -> Option<&str> {
+    subtype.split_once("._sub.").map(|(label, _)| label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[test]
+    fn test_string_with_control_characters_escaped() {
+        assert_eq!(
+            string_with_control_characters_escaped("Hello\nWorld".to_string()),
+            "Hello\\u000aWorld"
+        );
+
+        assert_eq!(
+            string_with_control_characters_escaped("Hello\tWorld".to_string()),
+            "Hello\\u0009World"
+        );
+
+        assert_eq!(
+            string_with_control_characters_escaped("Hello World".to_string()),
+            "Hello World"
+        );
+
+        assert_eq!(string_with_control_characters_escaped("".to_string()), "");
+
+        assert_eq!(
+            string_with_control_characters_escaped("\n\r\t".to_string()),
+            "\\u000a\\u000d\\u0009"
+        );
+    }
+
+    #[test]
+    fn test_bytes_option_to_string_option_with_escaping() {
+        assert_eq!(
+            bytes_option_to_string_option_with_escaping(Some(b"Hello World")),
+            Some("Hello World".to_string())
+        );
+
+        assert_eq!(
+            bytes_option_to_string_option_with_escaping(Some(b"Hello\nWorld")),
+            Some("Hello\\u000aWorld".to_string())
+        );
+
+        assert_eq!(
+            bytes_option_to_string_option_with_escaping(Some(&[0xff, 0xfe, 0xfd])),
+            Some("fffefd".to_string()) // expected hexadecimal string
+        );
+
+        assert_eq!(bytes_option_to_string_option_with_escaping(None), None);
+    }
+
+    #[test]
+    fn test_byte_array_hexlified() {
+        assert_eq!(byte_array_hexlified(&[0x01, 0x02, 0x03]), "010203");
+
+        assert_eq!(byte_array_hexlified(&[]), "");
+
+        assert_eq!(byte_array_hexlified(&[0xff, 0x00, 0x80]), "ff0080");
+    }
+
+    #[test]
+    fn test_txt_record_value_kind() {
+        assert_eq!(
+            TxtRecord::new("flag", None).value_kind(),
+            TxtValueKind::Absent
+        );
+        assert_eq!(
+            TxtRecord::new("key", Some(b"")).value_kind(),
+            TxtValueKind::Empty
+        );
+        assert_eq!(
+            TxtRecord::new("key", Some(b"value")).value_kind(),
+            TxtValueKind::Text
+        );
+        assert_eq!(
+            TxtRecord::new("key", Some(&[0x01, 0xff])).value_kind(),
+            TxtValueKind::Binary
+        );
+    }
+
+    #[test]
+    fn test_txt_record_keeps_distinction_in_display() {
+        assert_eq!(TxtRecord::new("flag", None).to_string(), "flag");
+        assert_eq!(TxtRecord::new("key", Some(b"")).to_string(), "key=");
+        assert_eq!(
+            TxtRecord::new("key", Some(&[0xff, 0x00])).to_string(),
+            "key=ff00"
+        );
+    }
+
+    #[test]
+    fn test_txt_record_format_value() {
+        let record = TxtRecord::new("key", Some(b"ab\x01\xffcd"));
+        assert_eq!(
+            record.format_value(TxtValueFormat::Text),
+            Some(r"ab\u0001\xffcd".to_string())
+        );
+        assert_eq!(
+            record.format_value(TxtValueFormat::Hex),
+            Some("616201ff6364".to_string())
+        );
+        assert_eq!(
+            record.format_value(TxtValueFormat::Base64),
+            Some("YWIB/2Nk".to_string())
+        );
+        assert_eq!(record.to_string_as(TxtValueFormat::Hex), "key=616201ff6364");
+
+        let flag = TxtRecord::new("flag", None);
+        for format in TxtValueFormat::ALL {
+            assert_eq!(flag.format_value(format), None);
+            assert_eq!(flag.to_string_as(format), "flag");
+        }
+    }
+
+    #[test]
+    fn test_txt_record_without_raw_bytes() {
+        let record: TxtRecord = serde_json::from_str(r#"{"key":"key","val":"hi"}"#).unwrap();
+        assert_eq!(record.raw, None);
+        assert_eq!(record.value_kind(), TxtValueKind::Text);
+        assert_eq!(
+            record.format_value(TxtValueFormat::Hex),
+            Some("6869".to_string())
+        );
+        assert_eq!(
+            record.format_value(TxtValueFormat::Base64),
+            Some("aGk=".to_string())
+        );
+    }
+
+    #[test]
+    fn test_txt_record_encoded_len() {
+        assert_eq!(TxtRecord::new("flag", None).encoded_len(), 5);
+        assert_eq!(TxtRecord::new("key", Some(b"")).encoded_len(), 5);
+        assert_eq!(TxtRecord::new("key", Some(&[0xff, 0x00])).encoded_len(), 7);
+    }
+
+    #[test]
+    fn test_check_txt_records_compliant() {
+        assert!(check_txt_records(&[]).is_empty());
+        assert!(
+            check_txt_records(&[
+                TxtRecord::new("txtvers", Some(b"1")),
+                TxtRecord::new("path", Some(b"/")),
+                TxtRecord::new("flag", None),
+            ])
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_check_txt_records_size() {
+        let large = |len: usize| {
+            vec![
+                TxtRecord::new("txtvers", Some(b"1")),
+                TxtRecord::new("blob", Some(&vec![0u8; len])),
+            ]
+        };
+        // 10 bytes for txtvers=1 and 6 bytes for blob=, each including the length byte
+        assert!(check_txt_records(&large(1300 - 16)).is_empty());
+        assert_eq!(
+            check_txt_records(&large(1300 - 15)),
+            vec![TxtWarning::TooLarge {
+                size: 1301,
+                limit: TXT_RECOMMENDED_MAX_SIZE
+            }]
+        );
+        assert_eq!(
+            check_txt_records(&large(9000)),
+            vec![TxtWarning::TooLarge {
+                size: 9016,
+                limit: TXT_MAX_SIZE
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_txt_records_keys() {
+        let warnings = check_txt_records(&[
+            TxtRecord::new("txtvers", Some(b"1")),
+            TxtRecord::new("averylongkey", Some(b"1")),
+            TxtRecord::new("schlüssel", Some(b"1")),
+            TxtRecord::new("Path", Some(b"/")),
+            TxtRecord::new("path", Some(b"/other")),
+            TxtRecord::new("PATH", None),
+            TxtRecord::new("", Some(b"value")),
+        ]);
+        assert_eq!(
+            warnings,
+            vec![
+                TxtWarning::LongKey("averylongkey".to_string()),
+                TxtWarning::NonAsciiKey("schlüssel".to_string()),
+                TxtWarning::DuplicateKey("path".to_string()),
+                TxtWarning::EmptyKey,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_txt_records_missing_txtvers() {
+        assert_eq!(
+            check_txt_records(&[TxtRecord::new("path", Some(b"/"))]),
+            vec![TxtWarning::MissingTxtvers]
+        );
+        assert!(check_txt_records(&[TxtRecord::new("TXTVERS", Some(b"1"))]).is_empty());
+        assert_eq!(TxtWarning::MissingTxtvers.to_string(), "No txtvers key");
+    }
+
+    #[test]
+    fn test_bytes_with_escaping() {
+        assert_eq!(bytes_with_escaping(b"plain"), "plain");
+        assert_eq!(bytes_with_escaping(b"a\nb"), r"a\u000ab");
+        assert_eq!(
+            bytes_with_escaping(&[0xe2, 0x82, 0xac, 0xe2, 0x82]),
+            r"€\xe2\x82"
+        );
+    }
+
+    #[test]
+    fn test_resolved_service_initialization() {
+        // Arrange
+        let instance_name = "test_service".to_string();
+        let service_type = "_banan._tcp.local".to_string();
+        let hostname = "test.local".to_string();
+        let port = 8080;
+        let addresses = vec![IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)).into()];
+        let subtype = Some("test_subtype".to_string());
+        let txt = vec![];
+        let updated_at_ms = 1620000000000;
+        let dead = false;
+
+        // Act
+        let service = ResolvedService {
+            instance_fullname: instance_name.clone(),
+            service_type: service_type.clone(),
+            hostname: hostname.clone(),
+            port,
+            addresses: addresses.clone(),
+            subtype: subtype.clone(),
+            txt: txt.clone(),
+            updated_at_micros: updated_at_ms,
+            dead,
+            sightings: vec![],
+        };
+
+        // Assert
+        assert_eq!(service.instance_fullname, instance_name);
+        assert_eq!(service.service_type, service_type);
+        assert_eq!(service.hostname, hostname);
+        assert_eq!(service.port, port);
+        assert_eq!(service.addresses, addresses);
+        assert_eq!(service.subtype, subtype);
+        assert_eq!(service.txt, txt);
+        assert_eq!(service.updated_at_micros, updated_at_ms);
+        assert_eq!(service.dead, dead);
+    }
+
+    #[test]
+    fn test_die_at_method() {
+        // Arrange
+        let mut service = ResolvedService {
+            instance_fullname: "test_service".to_string(),
+            service_type: "_banan._tcp.local.".to_string(),
+            hostname: "test.local".to_string(),
+            port: 8080,
+            addresses: vec![IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)).into()],
+            subtype: None,
+            txt: vec![],
+            updated_at_micros: 1620000000000,
+            dead: false,
+            sightings: vec![],
+        };
+
+        let new_updated_at_ms = 1620000005000;
+
+        // Act
+        service.die_at(new_updated_at_ms);
+
+        // Assert
+        assert!(service.dead);
+        assert_eq!(service.updated_at_micros, new_updated_at_ms);
+    }
+
+    #[test]
+    fn test_die_at_when_already_dead() {
+        // Arrange
+        let mut service = ResolvedService {
+            instance_fullname: "test_service".to_string(),
+            service_type: "_banan._tcp.local.".to_string(),
+            hostname: "test.local".to_string(),
+            port: 8080,
+            addresses: vec![IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)).into()],
+            subtype: None,
+            txt: vec![],
+            updated_at_micros: 1620000000000,
+            dead: true,
+            sightings: vec![],
+        };
+
+        let new_updated_at_ms = 1620000010000;
+
+        // Act
+        service.die_at(new_updated_at_ms);
+
+        // Assert
+        assert!(service.dead);
+        assert_eq!(service.updated_at_micros, new_updated_at_ms);
+
+        // Assert matches query for dead status
+        assert!(service.matches_query("dead"));
+        assert!(!service.matches_query("alive"));
+    }
+
+    #[test]
+    fn test_die_at_with_boundary_timestamp() {
+        // Arrange
+        let mut service = ResolvedService {
+            instance_fullname: "test_service".to_string(),
+            service_type: "_banan._tcp.local.".to_string(),
+            hostname: "test.local".to_string(),
+            port: 8080,
+            addresses: vec![IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)).into()],
+            subtype: None,
+            txt: vec![],
+            updated_at_micros: 1620000000000,
+            dead: false,
+            sightings: vec![],
+        };
+
+        // Act with boundary timestamp (0 ms)
+        service.die_at(0);
+
+        // Assert
+        assert!(service.dead);
+        assert_eq!(service.updated_at_micros, 0);
+    }
+
+    #[test]
+    fn test_get_instance_name() {
+        // Test with standard service name format
+        let service = ResolvedService {
+            instance_fullname: "My Service._http._tcp.local".to_string(),
+            service_type: "_http._tcp.local".to_string(),
+            hostname: "hostname.local".to_string(),
+            port: 80,
+            addresses: vec![IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)).into()],
+            subtype: None,
+            txt: vec![],
+            updated_at_micros: 0,
+            dead: false,
+            sightings: vec![],
+        };
+        assert_eq!(service.get_instance_name(), "My Service");
+
+        // Test with dot in the instance name
+        let service = ResolvedService {
+            instance_fullname: "My.Complex.Service._http._tcp.local".to_string(),
+            service_type: "_http._tcp.local".to_string(),
+            hostname: "hostname.local".to_string(),
+            port: 80,
+            addresses: vec![IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)).into()],
+            subtype: None,
+            txt: vec![],
+            updated_at_micros: 0,
+            dead: false,
+            sightings: vec![],
+        };
+        assert_eq!(service.get_instance_name(), "My.Complex.Service");
+    }
+
+    #[test]
+    fn test_matches_query() {
+        let service = ResolvedService {
+            instance_fullname: "MyService".to_string(),
+            service_type: "_http._tcp".to_string(),
+            hostname: "my-host.local".to_string(),
+            port: 8080,
+            addresses: vec![
+                "192.168.1.1".parse::<IpAddr>().unwrap().into(),
+                "fe80::1".parse::<IpAddr>().unwrap().into(),
+            ],
+            subtype: Some("sub-type".to_string()),
+            txt: vec![TxtRecord::new("key", Some(b"value"))],
+            updated_at_micros: 0,
+            dead: false,
+            sightings: vec![],
+        };
+
+        // Test matches for instance_name
+        assert!(service.matches_query("MyService"));
+        assert!(service.matches_query("myservice")); // Case-insensitive
+        assert!(!service.matches_query("OtherService"));
+
+        // Test matches for service_type
+        assert!(service.matches_query("_http._tcp"));
+        assert!(service.matches_query("http"));
+        assert!(!service.matches_query("_ftp._tcp"));
+
+        // Test matches for hostname
+        assert!(service.matches_query("my-host"));
+        assert!(service.matches_query("local"));
+        assert!(!service.matches_query("other-host"));
+
+        // Test matches for port
+        assert!(service.matches_query("8080"));
+        assert!(!service.matches_query("9090"));
+
+        // Test matches for addresses
+        assert!(service.matches_query("192.168.1.1"));
+        assert!(service.matches_query("fe80::1"));
+        assert!(!service.matches_query("10.0.0.1"));
+
+        // Test matches for subtype
+        assert!(service.matches_query("sub-type"));
+        assert!(service.matches_query("type"));
+        assert!(!service.matches_query("other-type"));
+
+        // Test matches for txt
+        assert!(service.matches_query("key=value"));
+        assert!(service.matches_query("key"));
+        assert!(service.matches_query("value"));
+        assert!(!service.matches_query("other-key"));
+
+        // Test for dead status
+        assert!(!service.matches_query("dead"));
+        assert!(service.matches_query("alive"));
+    }
+
+    #[test]
+    fn test_matches_query_empty_fields() {
+        let service = ResolvedService {
+            instance_fullname: "".to_string(),
+            service_type: "".to_string(),
+            hostname: "".to_string(),
+            port: 0,
+            addresses: vec![],
+            subtype: None,
+            txt: vec![],
+            updated_at_micros: 0,
+            dead: true,
+            sightings: vec![],
+        };
+
+        assert!(!service.matches_query("anything"));
+        assert!(service.matches_query(""));
+    }
+
+    #[test]
+    fn test_matches_query_partial_matches() {
+        let service = ResolvedService {
+            instance_fullname: "MyService".to_string(),
+            service_type: "_http._tcp".to_string(),
+            hostname: "my-host.local".to_string(),
+            port: 8080,
+            addresses: vec![
+                "192.168.1.1".parse::<IpAddr>().unwrap().into(),
+                "fe80::1".parse::<IpAddr>().unwrap().into(),
+            ],
+            subtype: None,
+            txt: vec![TxtRecord::new("key", Some(b"val"))],
+            updated_at_micros: 2349284,
+            dead: false,
+            sightings: vec![],
+        };
+
+        assert!(service.matches_query("my"));
+        assert!(service.matches_query("host"));
+        assert!(service.matches_query("192"));
+        assert!(service.matches_query("http"));
+        assert!(service.matches_query("808"));
+    }
+
+    fn sample_service(updated_at: u64, dead: bool) -> ResolvedService {
+        ResolvedService {
+            instance_fullname: "example._http._tcp.local".to_string(),
+            service_type: "_http._tcp".to_string(),
+            hostname: "host.local".to_string(),
+            port: 8080,
+            addresses: vec!["127.0.0.1".parse::<IpAddr>().unwrap().into()],
+            subtype: Some("printer".to_string()),
+            txt: vec![],
+            updated_at_micros: updated_at,
+            dead,
+            sightings: vec![],
+        }
+    }
+
+    #[test]
+    fn test_matches_except_updated_at_ignores_updated_at() {
+        let a = sample_service(100, false);
+        let b = sample_service(200, false);
+        assert!(a.matches_except_updated_at(&b));
+    }
+
+    #[test]
+    fn test_matches_except_updated_at_detects_difference() {
+        let a = sample_service(100, false);
+        let mut b = sample_service(100, false);
+        b.port = 9090;
+        assert!(!a.matches_except_updated_at(&b));
+    }
+
+    #[test]
+    fn test_matches_except_updated_at_compares_sightings() {
+        let mut a = sample_service(100, false);
+        let mut b = sample_service(200, false);
+        a.sightings = interface_sightings(&[scoped_v4_on("eth0", 2)], 100);
+        b.sightings = interface_sightings(&[scoped_v4_on("eth0", 2)], 100);
+        assert!(a.matches_except_updated_at(&b));
+        // Refreshing only when the service was last seen on an interface is a change
+        b.sightings = interface_sightings(&[scoped_v4_on("eth0", 2)], 200);
+        assert!(!a.matches_except_updated_at(&b));
+        b.sightings = interface_sightings(&[scoped_v4_on("wlan0", 4)], 100);
+        assert!(!a.matches_except_updated_at(&b));
+    }
+
+    fn scoped_v4_on(name: &str, index: u32) -> ScopedAddr {
+        let mut scoped = ScopedAddr::from(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)));
+        scoped.interfaces.insert(InterfaceScope {
+            name: name.to_string(),
+            index,
+        });
+        scoped
+    }
+
+    fn scoped_v6_on(name: &str, index: u32) -> ScopedAddr {
+        let mut scoped = ScopedAddr::from("fe80::1".parse::<IpAddr>().unwrap());
+        scoped.interfaces.insert(InterfaceScope {
+            name: name.to_string(),
+            index,
+        });
+        scoped
+    }
+
+    #[test]
+    fn test_interface_sightings_groups_families_per_interface() {
+        let sightings = interface_sightings(
+            &[
+                scoped_v4_on("wlan0", 4),
+                scoped_v4_on("eth0", 2),
+                scoped_v6_on("eth0", 2),
+            ],
+            42,
+        );
+        assert_eq!(sightings.len(), 2);
+        assert_eq!(sightings[0].interface.name, "eth0");
+        assert!(sightings[0].ipv4 && sightings[0].ipv6);
+        assert_eq!(sightings[0].to_string(), "eth0 (IPv4, IPv6)");
+        assert_eq!(sightings[1].interface.name, "wlan0");
+        assert!(sightings[1].ipv4 && !sightings[1].ipv6);
+        assert_eq!(sightings[1].first_seen_micros, 42);
+        assert_eq!(sightings[1].last_seen_micros, 42);
+    }
+
+    #[test]
+    fn test_interface_sightings_without_interfaces_is_empty() {
+        let addr = ScopedAddr::from(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        assert!(interface_sightings(&[addr], 42).is_empty());
+    }
+
+    #[test]
+    fn test_merge_sightings_keeps_first_seen_and_earlier_interfaces() {
+        let mut previous = sample_service(100, false);
+        previous.sightings =
+            interface_sightings(&[scoped_v4_on("eth0", 2), scoped_v4_on("wlan0", 4)], 100);
+
+        let mut current = sample_service(200, false);
+        current.sightings = interface_sightings(&[scoped_v6_on("eth0", 2)], 200);
+        current.merge_sightings(&previous.sightings);
+
+        assert_eq!(current.sightings.len(), 2);
+        let eth0 = &current.sightings[0];
+        assert_eq!(eth0.interface.name, "eth0");
+        assert!(eth0.ipv4 && eth0.ipv6);
+        assert_eq!(eth0.first_seen_micros, 100);
+        assert_eq!(eth0.last_seen_micros, 200);
+        let wlan0 = &current.sightings[1];
+        assert_eq!(wlan0.interface.name, "wlan0");
+        assert_eq!(wlan0.first_seen_micros, 100);
+        assert_eq!(wlan0.last_seen_micros, 100);
+    }
+
+    #[test]
+    fn test_valid_service_types() {
+        assert_eq!(
+            check_service_type_fully_qualified("_http._tcp.local."),
+            Ok(MdnsLabelType::ServiceType)
+        );
+        assert_eq!(
+            check_service_type_fully_qualified("_printer._udp.local."),
+            Ok(MdnsLabelType::ServiceType)
+        );
+        assert_eq!(
+            check_service_type_fully_qualified("_myprinter._sub._http._tcp.local."),
+            Ok(MdnsLabelType::SubType)
+        );
+    }
+
+    #[test]
+    fn test_invalid_service_types() {
+        assert_eq!(
+            check_service_type_fully_qualified("_http._tcp.local"),
+            Err(MdnsError::MissingTrailingDot)
+        );
+        assert_eq!(
+            check_service_type_fully_qualified("_http._tcp."),
+            Err(MdnsError::IncorrectFormat)
+        );
+        assert_eq!(
+            check_service_type_fully_qualified("_http._ftp.local."),
+            Err(MdnsError::InvalidProtocol)
+        );
+        assert_eq!(
+            check_service_type_fully_qualified("http._tcp.local."),
+            Err(MdnsError::InvalidService)
+        );
+        assert_eq!(
+            check_service_type_fully_qualified("_http_._tcp.local."),
+            Err(MdnsError::InvalidService)
+        );
+        assert_eq!(
+            check_service_type_fully_qualified("_http._tcp.nonlocal."),
+            Err(MdnsError::InvalidDomain)
+        );
+        assert_eq!(
+            check_service_type_fully_qualified("__._tcp.local."),
+            Err(MdnsError::InvalidService)
+        );
+        assert_eq!(
+            check_service_type_fully_qualified("_myprinter._sub._http._ftp.local."),
+            Err(MdnsError::InvalidProtocol)
+        ); // Invalid protocol with subtype
+        assert_eq!(
+            check_service_type_fully_qualified("_myprinter._sub._tcp.nonlocal."),
+            Err(MdnsError::IncorrectFormat)
+        ); // Missing service in format
+        assert_eq!(
+            check_service_type_fully_qualified("_sub._http._tcp.local."),
+            Err(MdnsError::IncorrectFormat)
+        ); // Missing subtype
+        assert_eq!(
+            check_service_type_fully_qualified("_-http_tcp._tcp.local."),
+            Err(MdnsError::InvalidService)
+        ); // Invalid service name format
+        assert_eq!(
+            check_service_type_fully_qualified("_-printer._sub._http._tcp.local."),
+            Err(MdnsError::InvalidSubType)
+        ); // Invalid subtype name format
+        assert_eq!(
+            check_service_type_fully_qualified("_printer-._sub._http._tcp.local."),
+            Err(MdnsError::InvalidSubType)
+        ); // Invalid subtype name format
+        assert_eq!(
+            check_service_type_fully_qualified("_printer._pub._http._tcp.local."),
+            Err(MdnsError::InvalidSublabel)
+        ); // Invalid subtype name format
+        assert_eq!(
+            check_service_type_fully_qualified("_http-._tcp.local."),
+            Err(MdnsError::InvalidService)
+        ); // Invalid service name format
+        assert_eq!(
+            check_service_type_fully_qualified("_myprinter._sub-type._tcp.local."),
+            Err(MdnsError::IncorrectFormat)
+        ); // Invalid subtype without _sub keyword
+        assert_eq!(
+            check_service_type_fully_qualified("_myprinter.____._sub._tcp.local."),
+            Err(MdnsError::InvalidSublabel)
+        ); // Invalid subtype format
+    }
+
+    #[test]
+    fn test_scoped_addr_display_no_interfaces() {
+        let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+        let scoped = ScopedAddr::from(addr);
+        assert_eq!(scoped.to_string(), "192.168.1.1");
+    }
+
+    #[test]
+    fn test_scoped_addr_display_single_interface() {
+        let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+        let mut scoped = ScopedAddr::from(addr);
+        scoped.interfaces.insert(InterfaceScope {
+            name: "eth0".to_string(),
+            index: 2,
+        });
+        assert_eq!(scoped.to_string(), "192.168.1.1 via eth0");
+    }
+
+    #[test]
+    fn test_scoped_addr_display_multiple_interfaces() {
+        let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+        let mut scoped = ScopedAddr::from(addr);
+        scoped.interfaces.insert(InterfaceScope {
+            name: "eth0".to_string(),
+            index: 2,
+        });
+        scoped.interfaces.insert(InterfaceScope {
+            name: "wlan0".to_string(),
+            index: 4,
+        });
+        assert_eq!(scoped.to_string(), "192.168.1.1 via eth0, wlan0");
+    }
+
+    #[test]
+    fn test_scoped_addr_eq_different_order_interfaces() {
+        let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+        let mut scoped1 = ScopedAddr::from(addr);
+        scoped1.interfaces.insert(InterfaceScope {
+            name: "eth0".to_string(),
+            index: 2,
+        });
+        scoped1.interfaces.insert(InterfaceScope {
+            name: "wlan0".to_string(),
+            index: 4,
+        });
+
+        let mut scoped2 = ScopedAddr::from(addr);
+        scoped2.interfaces.insert(InterfaceScope {
+            name: "wlan0".to_string(),
+            index: 4,
+        });
+        scoped2.interfaces.insert(InterfaceScope {
+            name: "eth0".to_string(),
+            index: 2,
+        });
+
+        assert_eq!(scoped1, scoped2);
+    }
+
+    #[test]
+    fn test_scoped_addr_ne_different_interfaces() {
+        let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+        let mut scoped1 = ScopedAddr::from(addr);
+        scoped1.interfaces.insert(InterfaceScope {
+            name: "eth0".to_string(),
+            index: 2,
+        });
+
+        let mut scoped2 = ScopedAddr::from(addr);
+        scoped2.interfaces.insert(InterfaceScope {
+            name: "wlan0".to_string(),
+            index: 4,
+        });
+
+        assert_ne!(scoped1, scoped2);
+    }
+
+    #[test]
+    fn test_scoped_addr_display_ipv6_with_interfaces() {
+        use std::net::Ipv6Addr;
+        let addr = IpAddr::V6(Ipv6Addr::new(
+            0x2003, 0xe8, 0xbf0c, 0xea00, 0xca0e, 0x14ff, 0xfeff, 0x416,
+        ));
+        let mut scoped = ScopedAddr::from(addr);
+        scoped.interfaces.insert(InterfaceScope {
+            name: "enp12s0".to_string(),
+            index: 2,
+        });
+        scoped.interfaces.insert(InterfaceScope {
+            name: "wlan0".to_string(),
+            index: 4,
+        });
+        assert_eq!(
+            scoped.to_string(),
+            "2003:e8:bf0c:ea00:ca0e:14ff:feff:416 via enp12s0, wlan0"
+        );
+    }
+
+    #[test]
+    fn test_scoped_addr_scope_id_handling() {
+        use std::net::Ipv6Addr;
+        let addr = IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1));
+        let scoped = ScopedAddr {
+            addr,
+            interfaces: BTreeSet::new(),
+            scope_id: Some("7".to_string()),
+        };
+        assert_eq!(scoped.to_string(), "fe80::1%7");
+        assert_eq!(scoped.to_ip_string(), "fe80::1%7");
+    }
+
+    #[test]
+    fn test_interface_flags_display_lists_set_flags() {
+        let flags = InterfaceFlags {
+            up: true,
+            multicast: true,
+            broadcast: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(flags.to_string(), "UP, MULTICAST, BROADCAST");
+    }
+
+    #[test]
+    fn test_interface_flags_display_omits_unknown_broadcast() {
+        let flags = InterfaceFlags {
+            up: true,
+            broadcast: None,
+            ..Default::default()
+        };
+        assert_eq!(flags.to_string(), "UP");
+    }
+
+    #[test]
+    fn test_interface_flags_display_empty_when_nothing_set() {
+        assert_eq!(InterfaceFlags::default().to_string(), "");
+    }
+
+    #[test]
+    fn test_timing_config_default_is_valid() {
+        assert_eq!(TimingConfig::default().validate(), Ok(()));
+    }
 
+    #[test]
+    fn test_timing_config_rejects_out_of_range_values() {
+        let config = TimingConfig {
+            verify_timeout_secs: 0,
+            ..Default::default()
+        };
         assert_eq!(
-            string_with_control_characters_escaped("\n\r\t".to_string()),
-            "\\u000a\\u000d\\u0009"
+            config.validate(),
+            Err(TimingConfigError::OutOfRange {
+                field: "Verify timeout",
+                min: 1,
+                max: 300,
+                value: 0,
+            })
         );
+        let config = TimingConfig {
+            browse_retry_delay_millis: 10_001,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
     }
 
     #[test]
-    fn test_bytes_option_to_string_option_with_escaping() {
+    fn test_timing_config_deserializes_missing_fields_as_defaults() {
+        let config: TimingConfig =
+            serde_json::from_str(r#"{"verifyTimeoutSecs": 30}"#).expect("To deserialize");
         assert_eq!(
-            bytes_option_to_string_option_with_escaping(Some(b"Hello World")),
-            Some("Hello World".to_string())
+            config,
+            TimingConfig {
+                verify_timeout_secs: 30,
+                ..Default::default()
+            }
         );
+    }
 
-        assert_eq!(
-            bytes_option_to_string_option_with_escaping(Some(b"Hello\nWorld")),
-            Some("Hello\\u000aWorld".to_string())
-        );
+    const HOUR_MICROS: u64 = 60 * 60 * 1_000_000;
 
+    #[test]
+    fn test_update_check_settings_validate_interval() {
+        assert_eq!(UpdateCheckSettings::default().validate(), Ok(()));
+        let settings = UpdateCheckSettings {
+            interval_hours: 0,
+            ..Default::default()
+        };
         assert_eq!(
-            bytes_option_to_string_option_with_escaping(Some(&[0xff, 0xfe, 0xfd])),
-            Some("fffefd".to_string()) // expected hexadecimal string
+            settings.validate(),
+            Err(UpdateCheckSettingsError::IntervalOutOfRange {
+                min: 1,
+                max: 720,
+                value: 0
+            })
         );
+    }
 
-        assert_eq!(bytes_option_to_string_option_with_escaping(None), None);
+    #[test]
+    fn test_update_check_due_after_interval() {
+        let mut settings = UpdateCheckSettings::default();
+        assert!(settings.is_check_due(0, false));
+        settings.last_check_micros = Some(HOUR_MICROS);
+        assert!(!settings.is_check_due(24 * HOUR_MICROS, false));
+        assert!(settings.is_check_due(25 * HOUR_MICROS, false));
+        settings.enabled = false;
+        assert!(!settings.is_check_due(25 * HOUR_MICROS, false));
     }
 
     #[test]
-    fn test_byte_array_hexlified() {
-        assert_eq!(byte_array_hexlified(&[0x01, 0x02, 0x03]), "010203");
+    fn test_update_check_respects_metered_networks() {
+        let mut settings = UpdateCheckSettings::default();
+        assert!(!settings.is_check_due(0, true));
+        settings.skip_on_metered = false;
+        assert!(settings.is_check_due(0, true));
+    }
 
-        assert_eq!(byte_array_hexlified(&[]), "");
+    #[test]
+    fn test_update_check_skipped_version_is_not_offered() {
+        let mut settings = UpdateCheckSettings::default();
+        assert!(settings.offers("2.0.0", 0));
+        settings.skip_version("2.0.0");
+        assert!(!settings.offers("2.0.0", 0));
+        assert!(settings.offers("2.0.1", 0));
+    }
 
-        assert_eq!(byte_array_hexlified(&[0xff, 0x00, 0x80]), "ff0080");
+    #[test]
+    fn test_update_check_remind_later() {
+        let mut settings = UpdateCheckSettings::default();
+        settings.remind_later(HOUR_MICROS);
+        assert!(!settings.offers("2.0.0", 2 * HOUR_MICROS));
+        assert!(settings.offers("2.0.0", 25 * HOUR_MICROS));
     }
 
     #[test]
-    fn test_resolved_service_initialization() {
-        // Arrange
-        let instance_name = "test_service".to_string();
-        let service_type = "_banan._tcp.local".to_string();
-        let hostname = "test.local".to_string();
-        let port = 8080;
-        let addresses = vec![IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)).into()];
-        let subtype = Some("test_subtype".to_string());
-        let txt = vec![];
-        let updated_at_ms = 1620000000000;
-        let dead = false;
+    fn test_update_check_settings_deserialize_missing_fields_as_defaults() {
+        let settings: UpdateCheckSettings =
+            serde_json::from_str(r#"{"skippedVersion": "2.0.0"}"#).expect("To deserialize");
+        assert_eq!(
+            settings,
+            UpdateCheckSettings {
+                skipped_version: Some("2.0.0".to_string()),
+                ..Default::default()
+            }
+        );
+    }
 
-        // Act
-        let service = ResolvedService {
-            instance_fullname: instance_name.clone(),
-            service_type: service_type.clone(),
-            hostname: hostname.clone(),
-            port,
-            addresses: addresses.clone(),
-            subtype: subtype.clone(),
-            txt: txt.clone(),
-            updated_at_micros: updated_at_ms,
-            dead,
-        };
+    fn interface(name: &str, addresses: &[&str]) -> NetworkInterface {
+        NetworkInterface {
+            name: name.to_string(),
+            addresses: addresses.iter().map(|addr| addr.to_string()).collect(),
+            enabled: true,
+            index: 1,
+            mac: None,
+            flags: InterfaceFlags::default(),
+            mtu: None,
+            incapable_reason: None,
+        }
+    }
 
-        // Assert
-        assert_eq!(service.instance_fullname, instance_name);
-        assert_eq!(service.service_type, service_type);
-        assert_eq!(service.hostname, hostname);
-        assert_eq!(service.port, port);
-        assert_eq!(service.addresses, addresses);
-        assert_eq!(service.subtype, subtype);
-        assert_eq!(service.txt, txt);
-        assert_eq!(service.updated_at_micros, updated_at_ms);
-        assert_eq!(service.dead, dead);
+    fn profile(name: &str, auto_activate: &[&str]) -> Profile {
+        Profile {
+            name: name.to_string(),
+            auto_activate: auto_activate.iter().map(|rule| rule.to_string()).collect(),
+            ..Default::default()
+        }
     }
 
     #[test]
-    fn test_die_at_method() {
-        // Arrange
-        let mut service = ResolvedService {
-            instance_fullname: "test_service".to_string(),
-            service_type: "_banan._tcp.local.".to_string(),
-            hostname: "test.local".to_string(),
-            port: 8080,
-            addresses: vec![IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)).into()],
-            subtype: None,
-            txt: vec![],
-            updated_at_micros: 1620000000000,
-            dead: false,
-        };
-
-        let new_updated_at_ms = 1620000005000;
-
-        // Act
-        service.die_at(new_updated_at_ms);
+    fn test_subnet_contains() {
+        let subnet: Subnet = "192.168.10.0/24".parse().expect("To parse");
+        assert!(subnet.contains(&"192.168.10.42".parse().unwrap()));
+        assert!(!subnet.contains(&"192.168.11.42".parse().unwrap()));
+        assert!(!subnet.contains(&"fe80::1".parse().unwrap()));
+        let subnet: Subnet = "fd00::/8".parse().expect("To parse");
+        assert!(subnet.contains(&"fd12::1".parse().unwrap()));
+        assert!(!subnet.contains(&"fe80::1".parse().unwrap()));
+        let any: Subnet = "0.0.0.0/0".parse().expect("To parse");
+        assert!(any.contains(&"10.0.0.1".parse().unwrap()));
+    }
 
-        // Assert
-        assert!(service.dead);
-        assert_eq!(service.updated_at_micros, new_updated_at_ms);
+    #[test]
+    fn test_subnet_rejects_malformed_input() {
+        for input in [
+            "192.168.10.0",
+            "192.168.10.0/33",
+            "fd00::/129",
+            "lab/24",
+            "10.0.0.0/x",
+        ] {
+            assert_eq!(
+                input.parse::<Subnet>(),
+                Err(ProfileError::InvalidSubnet(input.to_string())),
+                "{input}"
+            );
+        }
     }
 
     #[test]
-    fn test_die_at_when_already_dead() {
-        // Arrange
-        let mut service = ResolvedService {
-            instance_fullname: "test_service".to_string(),
-            service_type: "_banan._tcp.local.".to_string(),
-            hostname: "test.local".to_string(),
-            port: 8080,
-            addresses: vec![IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)).into()],
-            subtype: None,
-            txt: vec![],
-            updated_at_micros: 1620000000000,
-            dead: true,
+    fn test_profile_validate() {
+        assert_eq!(profile("office", &["eth0"]).validate(), Ok(()));
+        assert_eq!(profile(" ", &[]).validate(), Err(ProfileError::EmptyName));
+        assert_eq!(
+            profile("lab", &["10.0.0.0/40"]).validate(),
+            Err(ProfileError::InvalidSubnet("10.0.0.0/40".to_string()))
+        );
+        let invalid_service_type = Profile {
+            service_types: vec!["_http._tcp.local".to_string()],
+            ..profile("lab", &[])
         };
+        assert_eq!(
+            invalid_service_type.validate(),
+            Err(ProfileError::InvalidServiceType {
+                service_type: "_http._tcp.local".to_string(),
+                source: MdnsError::MissingTrailingDot,
+            })
+        );
+    }
 
-        let new_updated_at_ms = 1620000010000;
-
-        // Act
-        service.die_at(new_updated_at_ms);
-
-        // Assert
-        assert!(service.dead);
-        assert_eq!(service.updated_at_micros, new_updated_at_ms);
-
-        // Assert matches query for dead status
-        assert!(service.matches_query("dead"));
-        assert!(!service.matches_query("alive"));
+    #[test]
+    fn test_profile_matches_interface_names_and_subnets() {
+        let interfaces = [
+            interface("eth0", &["192.168.10.5", "fe80::1"]),
+            interface("wg-lab", &[]),
+        ];
+        assert!(profile("lab", &["wg-lab"]).matches(&interfaces));
+        assert!(profile("office", &["192.168.10.0/24"]).matches(&interfaces));
+        assert!(!profile("customer", &["172.16.0.0/12", "eth1"]).matches(&interfaces));
+        assert!(!profile("manual", &[]).matches(&interfaces));
     }
 
     #[test]
-    fn test_die_at_with_boundary_timestamp() {
-        // Arrange
-        let mut service = ResolvedService {
-            instance_fullname: "test_service".to_string(),
-            service_type: "_banan._tcp.local.".to_string(),
-            hostname: "test.local".to_string(),
-            port: 8080,
-            addresses: vec![IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)).into()],
-            subtype: None,
-            txt: vec![],
-            updated_at_micros: 1620000000000,
-            dead: false,
+    fn test_profile_settings_auto_activates_first_match() {
+        let settings = ProfileSettings {
+            profiles: vec![
+                profile("customer", &["172.16.0.0/12"]),
+                profile("office", &["192.168.10.0/24"]),
+                profile("fallback", &["eth0"]),
+            ],
+            active: None,
         };
-
-        // Act with boundary timestamp (0 ms)
-        service.die_at(0);
-
-        // Assert
-        assert!(service.dead);
-        assert_eq!(service.updated_at_micros, 0);
+        let interfaces = [interface("eth0", &["192.168.10.5"])];
+        assert_eq!(
+            settings
+                .auto_activated(&interfaces)
+                .map(|profile| profile.name.as_str()),
+            Some("office")
+        );
+        assert_eq!(settings.auto_activated(&[]), None);
     }
 
     #[test]
-    fn test_get_instance_name() {
-        // Test with standard service name format
-        let service = ResolvedService {
-            instance_fullname: "My Service._http._tcp.local".to_string(),
-            service_type: "_http._tcp.local".to_string(),
-            hostname: "hostname.local".to_string(),
-            port: 80,
-            addresses: vec![IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)).into()],
-            subtype: None,
-            txt: vec![],
-            updated_at_micros: 0,
-            dead: false,
-        };
-        assert_eq!(service.get_instance_name(), "My Service");
+    fn test_profile_settings_save_replaces_and_remove_deactivates() {
+        let mut settings = ProfileSettings::default();
+        settings.save(profile("office", &[]));
+        settings.save(profile("lab", &[]));
+        settings.save(Profile {
+            service_types: vec!["_ssh._tcp.local.".to_string()],
+            ..profile("office", &[])
+        });
+        assert_eq!(settings.profiles.len(), 2);
+        settings.active = Some("office".to_string());
+        assert_eq!(
+            settings.watched_service_types(),
+            Some(&vec!["_ssh._tcp.local.".to_string()])
+        );
+        settings.remove("office");
+        assert_eq!(settings.active, None);
+        assert_eq!(settings.watched_service_types(), None);
+        assert!(settings.get("lab").is_some());
+    }
 
-        // Test with dot in the instance name
-        let service = ResolvedService {
-            instance_fullname: "My.Complex.Service._http._tcp.local".to_string(),
-            service_type: "_http._tcp.local".to_string(),
-            hostname: "hostname.local".to_string(),
+    fn dead_service(died_at_micros: u64) -> ResolvedService {
+        let mut service = ResolvedService {
+            instance_fullname: "test._http._tcp.local.".to_string(),
+            service_type: "_http._tcp.local.".to_string(),
+            hostname: "host.local.".to_string(),
             port: 80,
-            addresses: vec![IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)).into()],
+            addresses: vec![],
             subtype: None,
             txt: vec![],
             updated_at_micros: 0,
             dead: false,
+            sightings: vec![],
         };
-        assert_eq!(service.get_instance_name(), "My.Complex.Service");
+        service.die_at(died_at_micros);
+        service
     }
 
     #[test]
-    fn test_matches_query() {
-        let service = ResolvedService {
-            instance_fullname: "MyService".to_string(),
-            service_type: "_http._tcp".to_string(),
-            hostname: "my-host.local".to_string(),
-            port: 8080,
-            addresses: vec![
-                "192.168.1.1".parse::<IpAddr>().unwrap().into(),
-                "fe80::1".parse::<IpAddr>().unwrap().into(),
-            ],
-            subtype: Some("sub-type".to_string()),
-            txt: vec![TxtRecord {
-                key: "key".to_string(),
-                val: Some("value".to_string()),
-            }],
-            updated_at_micros: 0,
-            dead: false,
-        };
-
-        // Test matches for instance_name
-        assert!(service.matches_query("MyService"));
-        assert!(service.matches_query("myservice")); // Case-insensitive
-        assert!(!service.matches_query("OtherService"));
-
-        // Test matches for service_type
-        assert!(service.matches_query("_http._tcp"));
-        assert!(service.matches_query("http"));
-        assert!(!service.matches_query("_ftp._tcp"));
+    fn test_dead_for_micros_is_none_for_alive_services() {
+        let mut service = dead_service(1_000_000);
+        service.dead = false;
+        assert_eq!(service.dead_for_micros(5_000_000), None);
+        assert_eq!(
+            dead_service(1_000_000).dead_for_micros(5_000_000),
+            Some(4_000_000)
+        );
+    }
 
-        // Test matches for hostname
-        assert!(service.matches_query("my-host"));
-        assert!(service.matches_query("local"));
-        assert!(!service.matches_query("other-host"));
+    #[test]
+    fn test_dead_service_policy_default_keeps_dead_services() {
+        let policy = DeadServicePolicy::default();
+        let service = dead_service(0);
+        assert!(!policy.is_hidden(&service, u64::MAX));
+        assert!(!policy.is_purged(&service, u64::MAX));
+    }
 
-        // Test matches for port
-        assert!(service.matches_query("8080"));
-        assert!(!service.matches_query("9090"));
+    #[test]
+    fn test_dead_service_policy_hides_after_delay() {
+        let policy = DeadServicePolicy {
+            hide_after_secs: Some(10),
+            purge_after_mins: None,
+        };
+        let service = dead_service(1_000_000);
+        assert!(!policy.is_hidden(&service, 10_999_999));
+        assert!(policy.is_hidden(&service, 11_000_000));
+        assert!(!policy.is_purged(&service, u64::MAX));
+    }
 
-        // Test matches for addresses
-        assert!(service.matches_query("192.168.1.1"));
-        assert!(service.matches_query("fe80::1"));
-        assert!(!service.matches_query("10.0.0.1"));
+    #[test]
+    fn test_dead_service_policy_purges_after_delay_and_hides_purged() {
+        let policy = DeadServicePolicy {
+            hide_after_secs: None,
+            purge_after_mins: Some(1),
+        };
+        let service = dead_service(0);
+        assert!(!policy.is_purged(&service, 59_999_999));
+        assert!(policy.is_purged(&service, 60_000_000));
+        assert!(policy.is_hidden(&service, 60_000_000));
+    }
 
-        // Test matches for subtype
-        assert!(service.matches_query("sub-type"));
-        assert!(service.matches_query("type"));
-        assert!(!service.matches_query("other-type"));
+    #[test]
+    fn test_dead_service_policy_does_not_overflow_on_huge_purge_delay() {
+        let policy = DeadServicePolicy {
+            hide_after_secs: None,
+            purge_after_mins: Some(u64::MAX),
+        };
+        assert!(!policy.is_purged(&dead_service(0), u64::MAX - 1));
+    }
 
-        // Test matches for txt
-        assert!(service.matches_query("key=value"));
-        assert!(service.matches_query("key"));
-        assert!(service.matches_query("value"));
-        assert!(!service.matches_query("other-key"));
+    #[test]
+    fn test_dead_service_policy_validate() {
+        assert_eq!(DeadServicePolicy::default().validate(), Ok(()));
+        let policy = DeadServicePolicy {
+            hide_after_secs: Some(0),
+            purge_after_mins: Some(5),
+        };
+        assert_eq!(
+            policy.validate(),
+            Err(DeadServicePolicyError::OutOfRange {
+                field: "Hide delay",
+                min: 1,
+                max: 86_400,
+                value: 0,
+            })
+        );
+        let policy = DeadServicePolicy {
+            hide_after_secs: None,
+            purge_after_mins: Some(u64::MAX),
+        };
+        assert!(policy.validate().is_err());
+    }
 
-        // Test for dead status
-        assert!(!service.matches_query("dead"));
-        assert!(service.matches_query("alive"));
+    fn alive_service(name: &str, updated_at_micros: u64) -> ResolvedService {
+        ResolvedService {
+            instance_fullname: format!("{name}._http._tcp.local."),
+            updated_at_micros,
+            dead: false,
+            ..dead_service(0)
+        }
     }
 
     #[test]
-    fn test_matches_query_empty_fields() {
-        let service = ResolvedService {
-            instance_fullname: "".to_string(),
-            service_type: "".to_string(),
-            hostname: "".to_string(),
-            port: 0,
-            addresses: vec![],
-            subtype: None,
-            txt: vec![],
-            updated_at_micros: 0,
-            dead: true,
-        };
+    fn test_service_cache_upsert_merges_sightings() {
+        let mut cache = ServiceCache::default();
+        let mut first = alive_service("a", 1);
+        first.sightings = interface_sightings(&[scoped_v4_on("eth0", 1)], 1);
+        cache.upsert(first);
+        let mut second = alive_service("a", 2);
+        second.sightings = interface_sightings(&[scoped_v4_on("wlan0", 2)], 2);
+        let stored = cache.upsert(second);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(stored.sightings.len(), 2);
+        assert_eq!(cache.get("a._http._tcp.local."), Some(&stored));
+    }
 
-        assert!(!service.matches_query("anything"));
-        assert!(service.matches_query(""));
+    #[test]
+    fn test_service_cache_mark_dead_only_once() {
+        let mut cache = ServiceCache::default();
+        cache.upsert(alive_service("a", 1));
+        assert!(cache.mark_dead("a._http._tcp.local.", 5).is_some());
+        assert!(cache.mark_dead("a._http._tcp.local.", 6).is_none());
+        assert!(cache.mark_dead("unknown._http._tcp.local.", 6).is_none());
+        assert_eq!(
+            cache
+                .get("a._http._tcp.local.")
+                .map(|service| service.updated_at_micros),
+            Some(5)
+        );
     }
 
     #[test]
-    fn test_matches_query_partial_matches() {
-        let service = ResolvedService {
-            instance_fullname: "MyService".to_string(),
-            service_type: "_http._tcp".to_string(),
-            hostname: "my-host.local".to_string(),
-            port: 8080,
-            addresses: vec![
-                "192.168.1.1".parse::<IpAddr>().unwrap().into(),
-                "fe80::1".parse::<IpAddr>().unwrap().into(),
-            ],
-            subtype: None,
-            txt: vec![TxtRecord {
-                key: "key".to_string(),
-                val: Some("val".to_string()),
-            }],
-            updated_at_micros: 2349284,
-            dead: false,
+    fn test_service_cache_since_filters_and_sorts() {
+        let mut cache = ServiceCache::default();
+        cache.upsert(alive_service("c", 30));
+        cache.upsert(alive_service("a", 10));
+        cache.upsert(alive_service("b", 20));
+        let names = |services: Vec<ResolvedService>| {
+            services
+                .into_iter()
+                .map(|service| service.get_instance_name())
+                .collect::<Vec<_>>()
         };
+        assert_eq!(names(cache.list()), vec!["a", "b", "c"]);
+        assert_eq!(names(cache.since(20)), vec!["b", "c"]);
+    }
 
-        assert!(service.matches_query("my"));
-        assert!(service.matches_query("host"));
-        assert!(service.matches_query("192"));
-        assert!(service.matches_query("http"));
-        assert!(service.matches_query("808"));
+    #[test]
+    fn test_service_cache_purge_and_clear_dead() {
+        let mut cache = ServiceCache::default();
+        cache.upsert(alive_service("a", 0));
+        cache.upsert(alive_service("b", 0));
+        cache.upsert(alive_service("c", 0));
+        cache.mark_dead("a._http._tcp.local.", 0);
+        cache.mark_dead("b._http._tcp.local.", 50_000_000);
+        let policy = DeadServicePolicy {
+            hide_after_secs: None,
+            purge_after_mins: Some(1),
+        };
+        assert_eq!(cache.purge(&policy, 60_000_000), 1);
+        assert_eq!(cache.len(), 2);
+        cache.clear_dead();
+        assert_eq!(cache.len(), 1);
+        cache.clear();
+        assert!(cache.is_empty());
     }
 
-    fn sample_service(updated_at: u64, dead: bool) -> ResolvedService {
-        ResolvedService {
-            instance_fullname: "example._http._tcp.local".to_string(),
-            service_type: "_http._tcp".to_string(),
-            hostname: "host.local".to_string(),
-            port: 8080,
-            addresses: vec!["127.0.0.1".parse::<IpAddr>().unwrap().into()],
-            subtype: Some("printer".to_string()),
-            txt: vec![],
-            updated_at_micros: updated_at,
-            dead,
+    fn removed(name: &str, at_micros: u64) -> ServiceRemovedEvent {
+        ServiceRemovedEvent {
+            instance_name: format!("{name}._http._tcp.local."),
+            at_micros,
         }
     }
 
     #[test]
-    fn test_matches_except_updated_at_ignores_updated_at() {
-        let a = sample_service(100, false);
-        let b = sample_service(200, false);
-        assert!(a.matches_except_updated_at(&b));
+    fn test_event_coalescer_collapses_repeated_resolutions() {
+        let mut coalescer = EventCoalescer::default();
+        coalescer.push_resolved(alive_service("a", 1));
+        coalescer.push_resolved(alive_service("a", 2));
+        coalescer.push_resolved(alive_service("b", 3));
+        let batch = coalescer.take_batch().expect("To have a batch");
+        assert_eq!(batch.resolved.len(), 2);
+        assert_eq!(batch.resolved[0].updated_at_micros, 2);
+        assert!(coalescer.take_batch().is_none());
     }
 
     #[test]
-    fn test_matches_except_updated_at_detects_difference() {
-        let a = sample_service(100, false);
-        let mut b = sample_service(100, false);
-        b.port = 9090;
-        assert!(!a.matches_except_updated_at(&b));
+    fn test_event_coalescer_drops_resolutions_identical_to_delivered() {
+        let mut coalescer = EventCoalescer::default();
+        coalescer.push_resolved(alive_service("a", 1));
+        coalescer.take_batch();
+        coalescer.push_resolved(alive_service("a", 2));
+        assert!(coalescer.take_batch().is_none());
+        let mut changed = alive_service("a", 3);
+        changed.port = 8080;
+        coalescer.push_resolved(changed);
+        assert_eq!(
+            coalescer.take_batch().map(|batch| batch.resolved.len()),
+            Some(1)
+        );
     }
 
     #[test]
-    fn test_valid_service_types() {
-        assert_eq!(
-            check_service_type_fully_qualified("_http._tcp.local."),
-            Ok(MdnsLabelType::ServiceType)
-        );
-        assert_eq!(
-            check_service_type_fully_qualified("_printer._udp.local."),
-            Ok(MdnsLabelType::ServiceType)
-        );
+    fn test_event_coalescer_resolution_after_removal_revives() {
+        let mut coalescer = EventCoalescer::default();
+        coalescer.push_resolved(alive_service("a", 1));
+        coalescer.take_batch();
+        coalescer.push_removed(removed("a", 2));
+        coalescer.push_removed(removed("a", 3));
+        coalescer.push_resolved(alive_service("a", 4));
+        let batch = coalescer.take_batch().expect("To have a batch");
+        assert!(batch.removed.is_empty());
+        assert_eq!(batch.resolved.len(), 1);
+    }
+
+    #[test]
+    fn test_event_coalescer_removal_after_delivery_is_batched() {
+        let mut coalescer = EventCoalescer::default();
+        coalescer.push_resolved(alive_service("a", 1));
+        coalescer.push_removed(removed("a", 2));
+        let batch = coalescer.take_batch().expect("To have a batch");
+        assert_eq!(batch.resolved.len(), 1);
+        assert_eq!(batch.removed.len(), 1);
+        coalescer.push_resolved(alive_service("a", 3));
+        assert!(coalescer.take_batch().is_some());
+    }
+
+    #[test]
+    fn test_natural_cmp_orders_numbers_numerically() {
+        assert_eq!(natural_cmp("printer-2", "printer-10"), Ordering::Less);
+        assert_eq!(natural_cmp("printer-10", "printer-9"), Ordering::Greater);
+        assert_eq!(natural_cmp("a1b2", "a1b10"), Ordering::Less);
+        assert_eq!(natural_cmp("host", "host-1"), Ordering::Less);
+        assert_eq!(natural_cmp("2", "10"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_natural_cmp_ignores_case_and_leading_zeros() {
+        assert_eq!(natural_cmp("Printer-3", "printer-20"), Ordering::Less);
+        assert_eq!(natural_cmp("item-007", "item-8"), Ordering::Less);
+        // Only differing in case or zeros falls back to a plain comparison
+        assert_ne!(natural_cmp("Printer", "printer"), Ordering::Equal);
+        assert_ne!(natural_cmp("item-07", "item-7"), Ordering::Equal);
+        assert_eq!(natural_cmp("same", "same"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_natural_cmp_sorts_list() {
+        let mut names = vec![
+            "printer-10",
+            "Printer-1",
+            "printer-2",
+            "camera",
+            "printer-1",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
         assert_eq!(
-            check_service_type_fully_qualified("_myprinter._sub._http._tcp.local."),
-            Ok(MdnsLabelType::SubType)
+            names,
+            vec![
+                "camera",
+                "Printer-1",
+                "printer-1",
+                "printer-2",
+                "printer-10"
+            ]
         );
     }
 
+    fn addrs(addresses: &[&str]) -> Vec<ScopedAddr> {
+        addresses
+            .iter()
+            .map(|a| ScopedAddr::from(a.parse::<IpAddr>().expect("To parse address")))
+            .collect()
+    }
+
     #[test]
-    fn test_invalid_service_types() {
+    fn test_cmp_addresses_numeric_and_ipv4_first() {
         assert_eq!(
-            check_service_type_fully_qualified("_http._tcp.local"),
-            Err(MdnsError::MissingTrailingDot)
+            cmp_addresses(&addrs(&["192.168.1.9"]), &addrs(&["192.168.1.10"])),
+            Ordering::Less
         );
         assert_eq!(
-            check_service_type_fully_qualified("_http._tcp."),
-            Err(MdnsError::IncorrectFormat)
+            cmp_addresses(&addrs(&["fe80::1", "10.0.0.2"]), &addrs(&["10.0.0.1"])),
+            Ordering::Greater
         );
         assert_eq!(
-            check_service_type_fully_qualified("_http._ftp.local."),
-            Err(MdnsError::InvalidProtocol)
+            cmp_addresses(&addrs(&["fe80::1"]), &addrs(&["255.255.255.255"])),
+            Ordering::Greater
         );
         assert_eq!(
-            check_service_type_fully_qualified("http._tcp.local."),
-            Err(MdnsError::InvalidService)
+            cmp_addresses(
+                &addrs(&["fe80::1", "10.0.0.1"]),
+                &addrs(&["10.0.0.1", "fe80::1"])
+            ),
+            Ordering::Equal
         );
+    }
+
+    #[test]
+    fn test_cmp_addresses_empty_sorts_last() {
+        assert_eq!(cmp_addresses(&[], &addrs(&["fe80::1"])), Ordering::Greater);
+        assert_eq!(cmp_addresses(&addrs(&["10.0.0.1"]), &[]), Ordering::Less);
+        assert_eq!(cmp_addresses(&[], &[]), Ordering::Equal);
+    }
+
+    fn sortable_service(name: &str, service_type: &str, host: &str, port: u16) -> ResolvedService {
+        ResolvedService {
+            instance_fullname: format!("{name}.{service_type}"),
+            service_type: service_type.to_string(),
+            hostname: host.to_string(),
+            port,
+            ..dead_service(0)
+        }
+    }
+
+    #[test]
+    fn test_compare_services_multi_key() {
+        let mut services = [
+            sortable_service("d", "_ipp._tcp.local.", "printer-10.local.", 631),
+            sortable_service("c", "_http._tcp.local.", "printer-2.local.", 8080),
+            sortable_service("b", "_http._tcp.local.", "printer-2.local.", 80),
+            sortable_service("a", "_http._tcp.local.", "printer-10.local.", 80),
+        ];
+        let criteria = [
+            SortCriterion::ascending(SortField::ServiceType),
+            SortCriterion::ascending(SortField::Hostname),
+            SortCriterion::descending(SortField::Port),
+        ];
+        services.sort_by(|a, b| compare_services(a, b, &criteria));
+        let names: Vec<_> = services.iter().map(|s| s.get_instance_name()).collect();
+        assert_eq!(names, vec!["c", "b", "a", "d"]);
+    }
+
+    #[test]
+    fn test_compare_services_ties_broken_by_instance() {
+        let a = sortable_service("a", "_http._tcp.local.", "host.local.", 80);
+        let b = sortable_service("b", "_http._tcp.local.", "host.local.", 80);
+        let criteria = [SortCriterion::descending(SortField::Port)];
+        assert_eq!(compare_services(&a, &b, &criteria), Ordering::Less);
+        assert_eq!(compare_services(&b, &a, &[]), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_subtype_parts() {
         assert_eq!(
-            check_service_type_fully_qualified("_http_._tcp.local."),
-            Err(MdnsError::InvalidService)
+            subtype_service_type("_printer._sub._http._tcp.local."),
+            Some("_http._tcp.local.")
         );
         assert_eq!(
-            check_service_type_fully_qualified("_http._tcp.nonlocal."),
-            Err(MdnsError::InvalidDomain)
+            subtype_label("_printer._sub._http._tcp.local."),
+            Some("_printer")
         );
+        assert_eq!(subtype_service_type("_http._tcp.local."), None);
+        assert_eq!(subtype_label("_http._tcp.local."), None);
+    }
+
+    fn lint_messages(input: &str) -> Vec<String> {
+        lint_service_type(input)
+            .diagnostics
+            .iter()
+            .map(|d| d.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_service_type_valid() {
+        for service_type in [
+            "_http._tcp.local.",
+            "_printer._udp.local.",
+            "_myprinter._sub._http._tcp.local.",
+        ] {
+            assert_eq!(lint_service_type(service_type), ServiceTypeLint::default());
+        }
+    }
+
+    #[test]
+    fn test_lint_service_type_suggestions() {
+        for (input, suggestion) in [
+            ("_http._tcp.local", "_http._tcp.local."),
+            ("_http._tcp.", "_http._tcp.local."),
+            ("_http._tcp", "_http._tcp.local."),
+            ("_http.tcp.local.", "_http._tcp.local."),
+            ("_http._TCP.LOCAL.", "_http._tcp.local."),
+            ("http._tcp.local.", "_http._tcp.local."),
+            ("_http-._tcp.local.", "_http._tcp.local."),
+            (" _http._tcp.local. ", "_http._tcp.local."),
+            (
+                "_printer.sub._http._tcp.local.",
+                "_printer._sub._http._tcp.local.",
+            ),
+            ("printer._sub.http.tcp", "_printer._sub._http._tcp.local."),
+        ] {
+            let lint = lint_service_type(input);
+            assert!(lint.has_errors(), "{input}");
+            assert_eq!(lint.suggestion.as_deref(), Some(suggestion), "{input}");
+            assert_eq!(lint_service_type(suggestion), ServiceTypeLint::default());
+        }
+    }
+
+    #[test]
+    fn test_lint_service_type_reports_label_and_position() {
         assert_eq!(
-            check_service_type_fully_qualified("__._tcp.local."),
-            Err(MdnsError::InvalidService)
+            lint_messages("_http.tcp.local."),
+            vec![
+                "error: The protocol must be `_tcp` or `_udp` in `tcp` at position 6, did you mean `_tcp`?"
+            ]
         );
         assert_eq!(
-            check_service_type_fully_qualified("_myprinter._sub._http._ftp.local."),
-            Err(MdnsError::InvalidProtocol)
-        ); // Invalid protocol with subtype
-        assert_eq!(
-            check_service_type_fully_qualified("_myprinter._sub._tcp.nonlocal."),
-            Err(MdnsError::IncorrectFormat)
-        ); // Missing service in format
-        assert_eq!(
-            check_service_type_fully_qualified("_sub._http._tcp.local."),
-            Err(MdnsError::IncorrectFormat)
-        ); // Missing subtype
-        assert_eq!(
-            check_service_type_fully_qualified("_-http_tcp._tcp.local."),
-            Err(MdnsError::InvalidService)
-        ); // Invalid service name format
-        assert_eq!(
-            check_service_type_fully_qualified("_-printer._sub._http._tcp.local."),
-            Err(MdnsError::InvalidSubType)
-        ); // Invalid subtype name format
-        assert_eq!(
-            check_service_type_fully_qualified("_printer-._sub._http._tcp.local."),
-            Err(MdnsError::InvalidSubType)
-        ); // Invalid subtype name format
-        assert_eq!(
-            check_service_type_fully_qualified("_printer._pub._http._tcp.local."),
-            Err(MdnsError::InvalidSublabel)
-        ); // Invalid subtype name format
-        assert_eq!(
-            check_service_type_fully_qualified("_http-._tcp.local."),
-            Err(MdnsError::InvalidService)
-        ); // Invalid service name format
-        assert_eq!(
-            check_service_type_fully_qualified("_myprinter._sub-type._tcp.local."),
-            Err(MdnsError::IncorrectFormat)
-        ); // Invalid subtype without _sub keyword
+            lint_messages("_ht!p._tcp.local."),
+            vec![
+                "error: The service name contains the invalid character `!` in `_ht!p` at position 3"
+            ]
+        );
         assert_eq!(
-            check_service_type_fully_qualified("_myprinter.____._sub._tcp.local."),
-            Err(MdnsError::InvalidSublabel)
-        ); // Invalid subtype format
+            lint_messages("_http._tcp.nonlocal."),
+            vec!["error: The domain is invalid, expected `local` in `nonlocal` at position 11"]
+        );
+        let lint = lint_service_type("_http._ftp.local.");
+        assert!(lint.has_errors());
+        assert_eq!(lint.suggestion, None);
     }
 
     #[test]
-    fn test_scoped_addr_display_no_interfaces() {
-        let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
-        let scoped = ScopedAddr::from(addr);
-        assert_eq!(scoped.to_string(), "192.168.1.1");
+    fn test_lint_service_type_rfc_6335_warnings() {
+        let lint = lint_service_type("_averyveryverylongname._tcp.local.");
+        assert!(!lint.has_errors());
+        assert_eq!(
+            lint.diagnostics[0].to_string(),
+            "warning: The service name is longer than 15 characters (RFC 6335) in \
+             `_averyveryverylongname` at position 0"
+        );
+        let lint = lint_service_type("_my_service._tcp.local.");
+        assert!(!lint.has_errors());
+        assert_eq!(lint.diagnostics.len(), 1);
+        let lint = lint_service_type("_123._tcp.local.");
+        assert!(!lint.has_errors());
+        assert_eq!(lint.diagnostics.len(), 1);
     }
 
     #[test]
-    fn test_scoped_addr_display_single_interface() {
-        let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
-        let mut scoped = ScopedAddr::from(addr);
-        scoped.interfaces.insert(InterfaceScope {
-            name: "eth0".to_string(),
-            index: 2,
-        });
-        assert_eq!(scoped.to_string(), "192.168.1.1 via eth0");
+    fn test_lint_service_type_agrees_with_check() {
+        for service_type in [
+            "_http._tcp.local.",
+            "_myprinter._sub._http._tcp.local.",
+            "_http._tcp.local",
+            "_http._tcp.",
+            "_http._ftp.local.",
+            "http._tcp.local.",
+            "_http_._tcp.local.",
+            "_http._tcp.nonlocal.",
+            "__._tcp.local.",
+            "_myprinter._sub._http._ftp.local.",
+            "_myprinter._sub._tcp.nonlocal.",
+            "_sub._http._tcp.local.",
+            "_-http_tcp._tcp.local.",
+            "_-printer._sub._http._tcp.local.",
+            "_printer-._sub._http._tcp.local.",
+            "_printer._pub._http._tcp.local.",
+            "_http-._tcp.local.",
+            "_myprinter._sub-type._tcp.local.",
+            "_myprinter.____._sub._tcp.local.",
+        ] {
+            assert_eq!(
+                lint_service_type(service_type).has_errors(),
+                check_service_type_fully_qualified(service_type).is_err(),
+                "{service_type}"
+            );
+        }
     }
 
     #[test]
-    fn test_scoped_addr_display_multiple_interfaces() {
-        let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
-        let mut scoped = ScopedAddr::from(addr);
-        scoped.interfaces.insert(InterfaceScope {
-            name: "eth0".to_string(),
-            index: 2,
-        });
-        scoped.interfaces.insert(InterfaceScope {
-            name: "wlan0".to_string(),
-            index: 4,
-        });
-        assert_eq!(scoped.to_string(), "192.168.1.1 via eth0, wlan0");
+    fn test_lint_instance_name() {
+        assert!(lint_instance_name("My Printer (2nd floor)").is_empty());
+        assert_eq!(lint_instance_name("").len(), 1);
+        assert_eq!(lint_instance_name(&"a".repeat(64)).len(), 1);
+        let diagnostics = lint_instance_name("bad\u{7}name");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].position, 3);
+        assert_eq!(diagnostics[0].severity, LintSeverity::Warning);
     }
 
     #[test]
-    fn test_scoped_addr_eq_different_order_interfaces() {
-        let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
-        let mut scoped1 = ScopedAddr::from(addr);
-        scoped1.interfaces.insert(InterfaceScope {
-            name: "eth0".to_string(),
-            index: 2,
-        });
-        scoped1.interfaces.insert(InterfaceScope {
-            name: "wlan0".to_string(),
-            index: 4,
-        });
-
-        let mut scoped2 = ScopedAddr::from(addr);
-        scoped2.interfaces.insert(InterfaceScope {
-            name: "wlan0".to_string(),
-            index: 4,
-        });
-        scoped2.interfaces.insert(InterfaceScope {
-            name: "eth0".to_string(),
-            index: 2,
-        });
-
-        assert_eq!(scoped1, scoped2);
+    fn test_lint_hostname() {
+        assert!(lint_hostname("my-host.local.").is_empty());
+        assert!(lint_hostname("Küche.local").is_empty());
+        assert_eq!(lint_hostname("").len(), 1);
+        assert_eq!(lint_hostname(".").len(), 1);
+        let diagnostics = lint_hostname("host..local.");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, LintSeverity::Error);
+        assert_eq!(diagnostics[0].position, 5);
+        let diagnostics = lint_hostname(&format!("{}.local.", "a".repeat(64)));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, LintSeverity::Warning);
+        let long = vec!["a".repeat(63); 5].join(".");
+        assert_eq!(lint_hostname(&long).len(), 1);
+        let diagnostics = lint_hostname("my.bad\u{7}host.local.");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].label, "bad\u{7}host");
+        assert_eq!(diagnostics[0].position, 6);
     }
 
     #[test]
-    fn test_scoped_addr_ne_different_interfaces() {
-        let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
-        let mut scoped1 = ScopedAddr::from(addr);
-        scoped1.interfaces.insert(InterfaceScope {
-            name: "eth0".to_string(),
-            index: 2,
-        });
-
-        let mut scoped2 = ScopedAddr::from(addr);
-        scoped2.interfaces.insert(InterfaceScope {
-            name: "wlan0".to_string(),
-            index: 4,
-        });
-
-        assert_ne!(scoped1, scoped2);
+    fn test_escape_dns_label() {
+        assert_eq!(escape_dns_label("My Printer"), "My Printer");
+        assert_eq!(escape_dns_label("Kitchen.TV"), "Kitchen\\.TV");
+        assert_eq!(escape_dns_label("back\\slash"), "back\\\\slash");
+        assert_eq!(escape_dns_label("tab\there"), "tab\\009here");
+        assert_eq!(escape_dns_label("del\u{7f}"), "del\\127");
+        assert_eq!(escape_dns_label("Café 🖨️"), "Café 🖨️");
+        assert_eq!(escape_dns_label(""), "");
     }
 
     #[test]
-    fn test_scoped_addr_display_ipv6_with_interfaces() {
-        use std::net::Ipv6Addr;
-        let addr = IpAddr::V6(Ipv6Addr::new(
-            0x2003, 0xe8, 0xbf0c, 0xea00, 0xca0e, 0x14ff, 0xfeff, 0x416,
-        ));
-        let mut scoped = ScopedAddr::from(addr);
-        scoped.interfaces.insert(InterfaceScope {
-            name: "enp12s0".to_string(),
-            index: 2,
-        });
-        scoped.interfaces.insert(InterfaceScope {
-            name: "wlan0".to_string(),
-            index: 4,
-        });
+    fn test_get_instance_name_keeps_raw_label() {
+        // mdns-sd reports names raw, a dot or backslash in the instance label is not escaped
+        let service = ResolvedService {
+            instance_fullname: "My\\Printer.v2._ipp._tcp.local.".to_string(),
+            service_type: "_ipp._tcp.local.".to_string(),
+            ..dead_service(0)
+        };
+        assert_eq!(service.get_instance_name(), "My\\Printer.v2");
         assert_eq!(
-            scoped.to_string(),
-            "2003:e8:bf0c:ea00:ca0e:14ff:feff:416 via enp12s0, wlan0"
+            service.get_escaped_instance_fullname(),
+            "My\\\\Printer\\.v2._ipp._tcp.local."
+        );
+        let service = ResolvedService {
+            instance_fullname: "Living Room TV 🖨._airplay._tcp.local.".to_string(),
+            service_type: "_airplay._tcp.local.".to_string(),
+            ..dead_service(0)
+        };
+        assert_eq!(service.get_instance_name(), "Living Room TV 🖨");
+        assert_eq!(
+            service.get_escaped_instance_fullname(),
+            "Living Room TV 🖨._airplay._tcp.local."
         );
     }
 
     #[test]
-    fn test_scoped_addr_scope_id_handling() {
-        use std::net::Ipv6Addr;
-        let addr = IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1));
-        let scoped = ScopedAddr {
-            addr,
-            interfaces: BTreeSet::new(),
-            scope_id: Some("7".to_string()),
+    fn test_get_escaped_instance_fullname_without_type_suffix() {
+        let service = ResolvedService {
+            instance_fullname: "MyService".to_string(),
+            service_type: "_http._tcp.local.".to_string(),
+            ..dead_service(0)
         };
-        assert_eq!(scoped.to_string(), "fe80::1%7");
-        assert_eq!(scoped.to_ip_string(), "fe80::1%7");
+        assert_eq!(service.get_escaped_instance_fullname(), "MyService");
     }
 }
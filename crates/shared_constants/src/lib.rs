@@ -6,8 +6,15 @@ pub const MDNS_SD_META_SERVICE: &str = "_services._dns-sd._udp.local.";
 pub const MDNS_SD_IP_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 pub const METRICS_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 pub const INTERFACES_LIST_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+pub const INTERFACES_CHANGE_DEBOUNCE: Duration = Duration::from_millis(250);
+pub const SERVICES_BATCH_INTERVAL: Duration = Duration::from_millis(16);
 
 pub const AUTO_COMPLETE_AUTO_FOCUS_DELAY: Duration = Duration::from_secs(5);
 pub const SHOW_NO_UPDATE_DURATION: Duration = Duration::from_secs(3);
+pub const UPDATE_CHECK_INTERVAL_HOURS: u64 = 24;
+pub const UPDATE_CHECK_TICK: Duration = Duration::from_secs(15 * 60);
+pub const UPDATE_REMIND_LATER_DELAY: Duration = Duration::from_secs(24 * 60 * 60);
 pub const GITHUB_BASE_URL: &str = "https://github.com/hrzlgnm/mdns-browser";
 pub const VERIFY_TIMEOUT: Duration = Duration::from_secs(5);
+pub const BROWSE_RETRY_DELAY: Duration = Duration::from_millis(20);
+pub const BROWSE_RETRY_ATTEMPTS: u64 = 100;
@@ -0,0 +1,56 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+package com.plugin.androidupdate
+
+import android.app.Activity
+import android.content.Context
+import android.content.Intent
+import android.net.ConnectivityManager
+import androidx.core.content.FileProvider
+import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.JSObject
+import app.tauri.plugin.Plugin
+import java.io.File
+
+@InvokeArg
+class InstallApkArgs {
+    lateinit var path: String
+}
+
+@TauriPlugin
+class AndroidUpdatePlugin(private val activity: Activity) : Plugin(activity) {
+    /** Opens the package installer for a downloaded APK in the app cache. */
+    @Command
+    fun installApk(invoke: Invoke) {
+        val args = invoke.parseArgs(InstallApkArgs::class.java)
+        try {
+            val uri = FileProvider.getUriForFile(
+                activity,
+                "${activity.packageName}.androidupdate.fileprovider",
+                File(args.path)
+            )
+            val intent = Intent(Intent.ACTION_VIEW).apply {
+                setDataAndType(uri, "application/vnd.android.package-archive")
+                addFlags(Intent.FLAG_GRANT_READ_URI_PERMISSION or Intent.FLAG_ACTIVITY_NEW_TASK)
+            }
+            activity.startActivity(intent)
+            invoke.resolve()
+        } catch (e: Exception) {
+            invoke.reject(e.message ?: "failed to start package installer")
+        }
+    }
+
+    /** Reports whether the active network is metered, like mobile data. */
+    @Command
+    fun getNetworkStatus(invoke: Invoke) {
+        val connectivity =
+            activity.getSystemService(Context.CONNECTIVITY_SERVICE) as ConnectivityManager
+        val status = JSObject()
+        status.put("metered", connectivity.isActiveNetworkMetered)
+        invoke.resolve(status)
+    }
+}
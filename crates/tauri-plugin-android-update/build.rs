@@ -4,5 +4,7 @@
 const COMMANDS: &[&str] = &["check", "download_and_install"];
 
 fn main() {
-    tauri_plugin::Builder::new(COMMANDS).build();
+    tauri_plugin::Builder::new(COMMANDS)
+        .android_path("android")
+        .build();
 }
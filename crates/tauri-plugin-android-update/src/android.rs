@@ -0,0 +1,55 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+//! The platform APIs reached through the Kotlin half of the plugin in
+//! `android/`.
+
+use std::path::Path;
+
+use tauri::{
+    plugin::{PluginApi, PluginHandle},
+    Runtime,
+};
+
+const PLUGIN_IDENTIFIER: &str = "com.plugin.androidupdate";
+
+/// The Kotlin half of the plugin.
+///
+/// Managed as app state by [`crate::Builder::build`] on Android.
+pub(crate) struct AndroidPlugin<R: Runtime>(PluginHandle<R>);
+
+#[derive(serde::Serialize)]
+struct InstallApkArgs<'a> {
+    path: &'a Path,
+}
+
+#[derive(serde::Deserialize)]
+struct NetworkStatus {
+    metered: bool,
+}
+
+impl<R: Runtime> AndroidPlugin<R> {
+    pub(crate) fn register<C: serde::de::DeserializeOwned>(
+        api: PluginApi<R, C>,
+    ) -> Result<Self, String> {
+        api.register_android_plugin(PLUGIN_IDENTIFIER, "AndroidUpdatePlugin")
+            .map(Self)
+            .map_err(|e| format!("failed to register android plugin: {e}"))
+    }
+
+    /// Opens the package installer for the APK at `path`, which must be in
+    /// the `updates` directory of the app cache.
+    pub(crate) fn install(&self, path: &Path) -> Result<(), String> {
+        self.0
+            .run_mobile_plugin::<()>("installApk", InstallApkArgs { path })
+            .map_err(|e| format!("failed to start package installer: {e}"))
+    }
+
+    /// Whether the active network is metered, like mobile data.
+    pub(crate) fn is_network_metered(&self) -> Result<bool, String> {
+        self.0
+            .run_mobile_plugin::<NetworkStatus>("getNetworkStatus", ())
+            .map(|status| status.metered)
+            .map_err(|e| format!("failed to get network status: {e}"))
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+//! Direct download of the update artifact listed in the `latest.json`
+//! manifest, for [`crate::InstallMode::DownloadApk`].
+
+use std::fmt::Write;
+
+use sha2::{Digest, Sha256};
+
+use crate::signature::UpdaterKey;
+
+/// Progress of the download started by [`crate::download_and_install`],
+/// sent on its `on_event` channel in the format of `tauri-plugin-updater`'s
+/// download events.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "event", content = "data")]
+pub enum DownloadEvent {
+    /// The download started; the length is known if the server sent it.
+    #[serde(rename_all = "camelCase")]
+    Started { content_length: Option<u64> },
+    /// A chunk of `chunk_length` bytes was received.
+    #[serde(rename_all = "camelCase")]
+    Progress { chunk_length: usize },
+    /// The download is complete.
+    Finished,
+}
+
+/// The artifact of an update for the running platform.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Artifact {
+    pub(crate) url: String,
+    /// The minisign signature of the artifact.
+    pub(crate) signature: String,
+    /// The hex encoded SHA-256 digest of the artifact, if the manifest has
+    /// one.
+    pub(crate) sha256: Option<String>,
+}
+
+/// The manifest platform key of the running platform, in the `{os}-{arch}`
+/// form the Tauri updater uses, like `android-aarch64`.
+pub(crate) fn current_target() -> String {
+    let arch = match std::env::consts::ARCH {
+        "x86" => "i686",
+        "arm" => "armv7",
+        arch => arch,
+    };
+    format!("{}-{arch}", std::env::consts::OS)
+}
+
+/// Downloads `url`, reporting the progress to `on_event`.
+pub(crate) async fn download(
+    client: &reqwest::Client,
+    url: &str,
+    on_event: impl Fn(DownloadEvent),
+) -> Result<Vec<u8>, String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("failed to download update: {e}"))?;
+    on_event(DownloadEvent::Started {
+        content_length: response.content_length(),
+    });
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("failed to download update: {e}"))?
+    {
+        on_event(DownloadEvent::Progress {
+            chunk_length: chunk.len(),
+        });
+        bytes.extend_from_slice(&chunk);
+    }
+    on_event(DownloadEvent::Finished);
+    Ok(bytes)
+}
+
+/// Verifies a downloaded artifact against its checksum, if the manifest has
+/// one, and its signature.
+pub(crate) fn verify_artifact(
+    key: &UpdaterKey,
+    artifact: &Artifact,
+    bytes: &[u8],
+) -> Result<(), String> {
+    if let Some(expected) = &artifact.sha256 {
+        let actual = Sha256::digest(bytes)
+            .iter()
+            .fold(String::new(), |mut hex, byte| {
+                let _ = write!(hex, "{byte:02x}");
+                hex
+            });
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(format!(
+                "failed to verify update: SHA-256 is {actual}, expected {expected}"
+            ));
+        }
+    }
+    key.verify(bytes, &artifact.signature)
+        .map_err(|e| format!("failed to verify update: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::{download, verify_artifact, Artifact, DownloadEvent};
+    use crate::{
+        signature::UpdaterKey,
+        test_server::{Response, TestServer},
+    };
+
+    const PUBLIC_KEY: &str = include_str!("../testdata/public.key");
+    const LATEST_JSON: &str = include_str!("../testdata/latest.json");
+    const APK: &str = include_str!("../testdata/app.apk");
+
+    fn android_artifact() -> Artifact {
+        let latest_json: serde_json::Value = serde_json::from_str(LATEST_JSON).unwrap();
+        let entry = &latest_json["platforms"]["android-aarch64"];
+        Artifact {
+            url: entry["url"].as_str().unwrap().to_string(),
+            signature: entry["signature"].as_str().unwrap().to_string(),
+            sha256: entry["sha256"].as_str().map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_download_reports_progress() {
+        let server = TestServer::start(|_| Response::ok(APK));
+        let client = reqwest::Client::new();
+        let events = Mutex::new(Vec::new());
+        let bytes =
+            tauri::async_runtime::block_on(download(&client, &server.url("/app.apk"), |event| {
+                events.lock().unwrap().push(event)
+            }))
+            .unwrap();
+        assert_eq!(bytes, APK.as_bytes());
+        let events = events.into_inner().unwrap();
+        assert_eq!(
+            events.first(),
+            Some(&DownloadEvent::Started {
+                content_length: Some(APK.len() as u64)
+            })
+        );
+        assert_eq!(events.last(), Some(&DownloadEvent::Finished));
+        let received: usize = events
+            .iter()
+            .map(|event| match event {
+                DownloadEvent::Progress { chunk_length } => *chunk_length,
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(received, APK.len());
+    }
+
+    #[test]
+    fn test_download_fails_on_error_status() {
+        let server = TestServer::start(|_| Response::status(404));
+        let client = reqwest::Client::new();
+        let result =
+            tauri::async_runtime::block_on(download(&client, &server.url("/app.apk"), |_| {}));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_download_event_format() {
+        assert_eq!(
+            serde_json::to_value(DownloadEvent::Started {
+                content_length: Some(42)
+            })
+            .unwrap(),
+            serde_json::json!({ "event": "Started", "data": { "contentLength": 42 } })
+        );
+        assert_eq!(
+            serde_json::to_value(DownloadEvent::Finished).unwrap(),
+            serde_json::json!({ "event": "Finished" })
+        );
+    }
+
+    #[test]
+    fn test_verify_artifact() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        let artifact = android_artifact();
+        assert_eq!(verify_artifact(&key, &artifact, APK.as_bytes()), Ok(()));
+        assert!(verify_artifact(&key, &artifact, b"tampered artifact").is_err());
+    }
+
+    #[test]
+    fn test_verify_artifact_checks_sha256() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        let mut artifact = android_artifact();
+        artifact.sha256 = Some("00".repeat(32));
+        let err = verify_artifact(&key, &artifact, APK.as_bytes()).unwrap_err();
+        assert!(err.contains("SHA-256"), "{err}");
+        artifact.sha256 = None;
+        assert_eq!(verify_artifact(&key, &artifact, APK.as_bytes()), Ok(()));
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+//! Conditional requests, so unchanged manifests and release listings are not
+//! downloaded on every check.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use reqwest::{
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    StatusCode,
+};
+
+/// A response kept to revalidate with `If-None-Match` and
+/// `If-Modified-Since`.
+#[derive(Clone)]
+struct CachedResponse {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+/// Responses by URL, for servers that send an `ETag` or `Last-Modified`
+/// header.
+#[derive(Default)]
+pub(crate) struct HttpCache(Mutex<HashMap<String, CachedResponse>>);
+
+impl HttpCache {
+    /// Fetches `url` as text, reusing the cached body when the server answers
+    /// with `304 Not Modified`. `what` names the resource in errors.
+    pub(crate) async fn fetch_text(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        what: &str,
+    ) -> Result<String, String> {
+        let cached = self.get(url);
+        let mut request = client.get(url);
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let response = request.send().await.map_err(|e| {
+            log::error!("failed to fetch {what}: {e}");
+            format!("failed to fetch {what}: {e}")
+        })?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                log::debug!("{what} not modified");
+                return Ok(cached.body);
+            }
+        }
+        let response = response.error_for_status().map_err(|e| {
+            log::error!("failed to fetch {what}: {e}");
+            format!("failed to fetch {what}: {e}")
+        })?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let body = response.text().await.map_err(|e| {
+            log::error!("failed to read {what}: {e}");
+            format!("failed to read {what}: {e}")
+        })?;
+        if etag.is_some() || last_modified.is_some() {
+            self.insert(
+                url,
+                CachedResponse {
+                    etag,
+                    last_modified,
+                    body: body.clone(),
+                },
+            );
+        }
+        Ok(body)
+    }
+
+    fn get(&self, url: &str) -> Option<CachedResponse> {
+        self.0
+            .lock()
+            .map_err(|e| log::error!("failed to lock http cache: {e}"))
+            .ok()?
+            .get(url)
+            .cloned()
+    }
+
+    fn insert(&self, url: &str, response: CachedResponse) {
+        match self.0.lock() {
+            Ok(mut responses) => {
+                responses.insert(url.to_string(), response);
+            }
+            Err(e) => log::error!("failed to lock http cache: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HttpCache;
+    use crate::test_server::{Response, TestServer};
+
+    #[test]
+    fn test_fetch_text_revalidates_with_etag() {
+        let server = TestServer::start(|request| {
+            if request.header("if-none-match") == Some("\"v1\"") {
+                Response::not_modified()
+            } else {
+                Response::ok("first").with_header("ETag", "\"v1\"")
+            }
+        });
+        let cache = HttpCache::default();
+        let client = reqwest::Client::new();
+        let url = server.url("/latest.json");
+        tauri::async_runtime::block_on(async {
+            assert_eq!(
+                cache.fetch_text(&client, &url, "manifest").await,
+                Ok("first".into())
+            );
+            assert_eq!(
+                cache.fetch_text(&client, &url, "manifest").await,
+                Ok("first".into())
+            );
+        });
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].header("if-none-match"), None);
+        assert_eq!(requests[1].header("if-none-match"), Some("\"v1\""));
+    }
+
+    #[test]
+    fn test_fetch_text_revalidates_with_last_modified() {
+        const LAST_MODIFIED: &str = "Wed, 01 Jan 2026 00:00:00 GMT";
+        let server = TestServer::start(|request| {
+            if request.header("if-modified-since") == Some(LAST_MODIFIED) {
+                Response::not_modified()
+            } else {
+                Response::ok("body").with_header("Last-Modified", LAST_MODIFIED)
+            }
+        });
+        let cache = HttpCache::default();
+        let client = reqwest::Client::new();
+        let url = server.url("/latest.json");
+        tauri::async_runtime::block_on(async {
+            assert_eq!(
+                cache.fetch_text(&client, &url, "manifest").await,
+                Ok("body".into())
+            );
+            assert_eq!(
+                cache.fetch_text(&client, &url, "manifest").await,
+                Ok("body".into())
+            );
+        });
+        assert_eq!(
+            server.requests()[1].header("if-modified-since"),
+            Some(LAST_MODIFIED)
+        );
+    }
+
+    #[test]
+    fn test_fetch_text_replaces_changed_response() {
+        let server = TestServer::start(|request| {
+            if request.header("if-none-match") == Some("\"v1\"") {
+                Response::ok("second").with_header("ETag", "\"v2\"")
+            } else {
+                Response::ok("first").with_header("ETag", "\"v1\"")
+            }
+        });
+        let cache = HttpCache::default();
+        let client = reqwest::Client::new();
+        let url = server.url("/latest.json");
+        tauri::async_runtime::block_on(async {
+            assert_eq!(
+                cache.fetch_text(&client, &url, "manifest").await,
+                Ok("first".into())
+            );
+            assert_eq!(
+                cache.fetch_text(&client, &url, "manifest").await,
+                Ok("second".into())
+            );
+        });
+        assert_eq!(server.requests()[1].header("if-none-match"), Some("\"v1\""));
+    }
+
+    #[test]
+    fn test_fetch_text_fails_on_error_status() {
+        let server = TestServer::start(|_| Response::status(404));
+        let cache = HttpCache::default();
+        let client = reqwest::Client::new();
+        let result = tauri::async_runtime::block_on(cache.fetch_text(
+            &client,
+            &server.url("/latest.json"),
+            "manifest",
+        ));
+        assert!(result.is_err());
+    }
+}
@@ -3,7 +3,7 @@
 
 //! # tauri-plugin-android-update
 //!
-//! A Tauri plugin that surfaces new GitHub releases for manual download.
+//! A Tauri plugin that surfaces new releases for manual download.
 //!
 //! [`tauri-plugin-updater`] cannot be used on Android, where apps must not
 //! self-install (Google Play Store regulations). This plugin fills that gap
@@ -14,7 +14,10 @@
 //! `tauri-apps/tauri-action` attaches it (signed with the updater signing key
 //! configured in the workflow) to each GitHub release. Instead of downloading
 //! and installing, `download_and_install` opens the release page in the
-//! default browser so the user can install manually.
+//! default browser so the user can install manually. Apps distributed outside
+//! of Google Play can opt into [`InstallMode::DownloadApk`] instead, which
+//! downloads and verifies the APK and hands it to the system package
+//! installer.
 //!
 //! The plugin registers the [`check`] and [`download_and_install`] commands
 //! under the `plugin:android-update|` namespace and manages the state they
@@ -23,9 +26,39 @@
 //! self-install support (e.g. under `#[cfg(mobile)]`), where desktop apps keep
 //! using [`tauri-plugin-updater`].
 //!
+//! Before an update is offered, the manifest is verified against its minisign
+//! signature, published as `latest.json.sig` next to it, with the public key
+//! configured via [`Builder::pubkey`].
+//!
+//! Releases are looked up on GitHub by default. [`Gitea`] and Forgejo,
+//! [`GitLab`] and manifests at [`StaticUrls`] are supported as well, and any
+//! other release server can be added by implementing [`UpdateSource`].
+//! Responses are revalidated with `ETag` and `Last-Modified`, and
+//! [`Builder::check_interval`] limits how often the server is asked at all.
+//!
 //! [`tauri-plugin-updater`]: https://docs.rs/tauri-plugin-updater
 
-use std::sync::Mutex;
+#[cfg(target_os = "android")]
+mod android;
+mod download;
+mod http;
+mod signature;
+mod source;
+#[cfg(test)]
+mod test_server;
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+pub use download::DownloadEvent;
+use download::{current_target, download, verify_artifact, Artifact};
+use http::HttpCache;
+use signature::UpdaterKey;
+pub use source::{GitHub, GitLab, Gitea, ReleaseLookup, ReleaseUrls, StaticUrls, UpdateSource};
 
 use tauri::{
     plugin::{Builder as PluginBuilder, TauriPlugin},
@@ -40,20 +73,71 @@ pub struct UpdateMetadata {
     pub version: String,
     /// The currently installed version.
     pub current_version: String,
+    /// The release notes of the available update, if the manifest has any.
+    pub notes: Option<String>,
+    /// The publication date of the available update, if the manifest has one.
+    pub pub_date: Option<String>,
+}
+
+/// The release channel updates are checked on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Channel {
+    /// Only releases without a semver pre-release tag, like `1.2.0`.
+    #[default]
+    Stable,
+    /// Pre-releases like `1.2.0-beta.1` as well as stable releases.
+    Beta,
+}
+
+impl Channel {
+    /// Whether a release of `version` is offered on this channel.
+    fn accepts(&self, version: &semver::Version) -> bool {
+        match self {
+            Channel::Stable => version.pre.is_empty(),
+            Channel::Beta => true,
+        }
+    }
+}
+
+/// What [`download_and_install`] does with the pending update.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InstallMode {
+    /// Opens the release page in the default browser, where the user can
+    /// download the update manually.
+    #[default]
+    OpenReleasePage,
+    /// Downloads the APK the manifest lists for the running platform,
+    /// verifies it and hands it to the system package installer. Falls back
+    /// to opening the release page when the manifest lists no APK for the
+    /// platform or the installer cannot be started.
+    ///
+    /// The app must declare the `REQUEST_INSTALL_PACKAGES` permission in its
+    /// Android manifest, which Google Play only grants to few apps.
+    DownloadApk,
 }
 
-/// Configures the plugin with the GitHub repository whose releases are
-/// checked for updates.
+/// Configures the plugin with the release server that is checked for updates.
 ///
-/// Either set [`Builder::owner`] and [`Builder::repo`] to derive the URLs
-/// from the repository, or provide the URLs explicitly via
-/// [`Builder::latest_json_url`] and [`Builder::releases_url`]. Missing
-/// configuration is reported when the plugin is set up.
+/// Set [`Builder::owner`] and [`Builder::repo`] to check a GitHub repository,
+/// provide the URLs explicitly via [`Builder::latest_json_url`] and
+/// [`Builder::releases_url`], or pass any other [`UpdateSource`], like
+/// [`Gitea`] or [`GitLab`], to [`Builder::source`]. Missing configuration is
+/// reported when the plugin is set up.
+///
+/// The public key the manifest is signed with must be set via
+/// [`Builder::pubkey`].
 pub struct Builder {
     owner: String,
     repo: String,
     latest_json_url: Option<String>,
+    latest_json_signature_url: Option<String>,
     releases_url: Option<String>,
+    source: Option<Box<dyn UpdateSource>>,
+    pubkey: Option<String>,
+    channel: Channel,
+    check_interval: Option<Duration>,
+    install_mode: InstallMode,
 }
 
 impl Default for Builder {
@@ -63,17 +147,24 @@ impl Default for Builder {
 }
 
 impl Builder {
-    /// Creates a builder with no URLs configured.
+    /// Creates a builder with no release server configured.
     ///
     /// Configure the repository with [`Builder::owner`] and [`Builder::repo`],
-    /// or provide the URLs explicitly with [`Builder::latest_json_url`] and
-    /// [`Builder::releases_url`].
+    /// provide the URLs explicitly with [`Builder::latest_json_url`] and
+    /// [`Builder::releases_url`], or set an [`UpdateSource`] with
+    /// [`Builder::source`].
     pub fn new() -> Self {
         Self {
             owner: String::new(),
             repo: String::new(),
             latest_json_url: None,
+            latest_json_signature_url: None,
             releases_url: None,
+            source: None,
+            pubkey: None,
+            channel: Channel::Stable,
+            check_interval: None,
+            install_mode: InstallMode::OpenReleasePage,
         }
     }
 
@@ -103,6 +194,34 @@ impl Builder {
         self
     }
 
+    /// Overrides the URL of the minisign signature of the `latest.json`
+    /// manifest. Defaults to the manifest URL with `.sig` appended.
+    pub fn latest_json_signature_url(mut self, url: impl Into<String>) -> Self {
+        self.latest_json_signature_url = Some(url.into());
+        self
+    }
+
+    /// Sets the public key the `latest.json` manifest and the artifacts it
+    /// lists are signed with, in the format of the `plugins.updater.pubkey`
+    /// entry of `tauri.conf.json`.
+    ///
+    /// [`check`] refuses updates whose manifest does not verify against it.
+    pub fn pubkey(mut self, pubkey: impl Into<String>) -> Self {
+        self.pubkey = Some(pubkey.into());
+        self
+    }
+
+    /// Sets the release channel, [`Channel::Stable`] by default.
+    ///
+    /// On [`Channel::Beta`], releases with a semver pre-release tag are
+    /// offered too. GitHub's latest release is never a pre-release, so with
+    /// `owner`/`repo` configured the beta channel reads the manifest of the
+    /// newest release instead, unless [`Builder::latest_json_url`] is set.
+    pub fn channel(mut self, channel: Channel) -> Self {
+        self.channel = channel;
+        self
+    }
+
     /// Overrides the release page that [`download_and_install`] opens.
     /// Defaults to the latest release of the configured repository.
     pub fn releases_url(mut self, url: impl Into<String>) -> Self {
@@ -110,6 +229,31 @@ impl Builder {
         self
     }
 
+    /// Sets the release server that is checked for updates, taking
+    /// precedence over `owner`/`repo` and the explicitly configured URLs.
+    pub fn source(mut self, source: impl UpdateSource + 'static) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// Sets the minimum time between two checks against the release server.
+    ///
+    /// Within the interval, [`check`] answers with the result of the last
+    /// successful check without any request. Unset by default, so every
+    /// check asks the server, which still only sends unchanged responses
+    /// again when it supports neither `ETag` nor `Last-Modified`.
+    pub fn check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = Some(interval);
+        self
+    }
+
+    /// Sets what [`download_and_install`] does with the pending update,
+    /// [`InstallMode::OpenReleasePage`] by default.
+    pub fn install_mode(mut self, install_mode: InstallMode) -> Self {
+        self.install_mode = install_mode;
+        self
+    }
+
     /// Builds the plugin.
     ///
     /// Registers the [`check`] and [`download_and_install`] commands and sets
@@ -119,71 +263,160 @@ impl Builder {
     /// frontend can invoke the commands under their `plugin:android-update|`
     /// names.
     ///
-    /// The URLs are resolved when the plugin is set up: an explicitly
-    /// configured URL wins, otherwise it is derived from `owner`/`repo`.
-    /// Plugin setup fails with an error if neither source is configured.
-    pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
-        let latest_json_url = self
-            .latest_json_url
-            .or_else(|| github_latest_json_url(&self.owner, &self.repo));
-        let releases_url = self
-            .releases_url
-            .or_else(|| github_releases_url(&self.owner, &self.repo));
+    /// The release server is resolved when the plugin is set up: a
+    /// [`Builder::source`] wins, then explicitly configured URLs, with
+    /// missing ones derived from `owner`/`repo`, then the GitHub repository.
+    /// Plugin setup fails with an error if none is configured, or if the
+    /// public key is missing or malformed.
+    pub fn build<R: Runtime>(mut self) -> TauriPlugin<R> {
+        let channel = self.channel;
+        let check_interval = self.check_interval;
+        let install_mode = self.install_mode;
+        let pubkey = self.pubkey.take();
+        let source = self.into_source();
 
         PluginBuilder::<R>::new("android-update")
-            .setup(move |app, _api| {
-                let (Some(latest_json_url), Some(releases_url)) = (latest_json_url, releases_url)
-                else {
+            .setup(move |app, api| {
+                let Some(source) = source else {
                     let message =
-                        "owner and repo, or latest_json_url and releases_url, must be configured"
-                            .to_string();
+                        "owner and repo, the URLs or a source must be configured".to_string();
                     log::error!("failed to set up tauri-plugin-android-update: {message}");
                     return Err(message.into());
                 };
-                app.manage(Config {
-                    latest_json_url,
-                    releases_url,
-                });
+                let key = pubkey
+                    .ok_or_else(|| "pubkey must be configured".to_string())
+                    .and_then(|pubkey| UpdaterKey::decode(&pubkey))
+                    .map_err(|message| {
+                        log::error!("failed to set up tauri-plugin-android-update: {message}");
+                        message
+                    })?;
+                app.manage(Config::new(
+                    source,
+                    key,
+                    channel,
+                    check_interval,
+                    install_mode,
+                ));
                 app.manage(PendingUpdateInfo(Mutex::new(None)));
+                #[cfg(target_os = "android")]
+                app.manage(android::AndroidPlugin::register(api)?);
+                #[cfg(not(target_os = "android"))]
+                let _ = api;
                 Ok(())
             })
             .invoke_handler(tauri::generate_handler![check, download_and_install])
             .build()
     }
-}
-
-/// The URL of the `latest.json` update manifest attached to the latest
-/// release of the GitHub repository `owner`/`repo`, if the repository is
-/// configured.
-fn github_latest_json_url(owner: &str, repo: &str) -> Option<String> {
-    repository_configured(owner, repo)
-        .then(|| format!("https://github.com/{owner}/{repo}/releases/latest/download/latest.json"))
-}
 
-/// The latest release page of the GitHub repository `owner`/`repo`, if the
-/// repository is configured.
-fn github_releases_url(owner: &str, repo: &str) -> Option<String> {
-    repository_configured(owner, repo)
-        .then(|| format!("https://github.com/{owner}/{repo}/releases/latest"))
+    /// The configured release server, if any.
+    ///
+    /// Explicitly configured URLs pin the manifest to a fixed location on
+    /// every channel.
+    fn into_source(self) -> Option<Box<dyn UpdateSource>> {
+        if let Some(source) = self.source {
+            return Some(source);
+        }
+        let github = repository_configured(&self.owner, &self.repo)
+            .then(|| GitHub::new(self.owner, self.repo));
+        if self.latest_json_url.is_none()
+            && self.latest_json_signature_url.is_none()
+            && self.releases_url.is_none()
+        {
+            return github.map(|github| Box::new(github) as Box<dyn UpdateSource>);
+        }
+        let latest_json_url = self
+            .latest_json_url
+            .or_else(|| github.as_ref().map(GitHub::latest_json_url))?;
+        let releases_url = self
+            .releases_url
+            .or_else(|| github.as_ref().map(GitHub::latest_release_url))?;
+        let mut urls = ReleaseUrls::new(latest_json_url, releases_url);
+        if let Some(url) = self.latest_json_signature_url {
+            urls.latest_json_signature_url = url;
+        }
+        Some(Box::new(StaticUrls(urls)))
+    }
 }
 
 fn repository_configured(owner: &str, repo: &str) -> bool {
     !owner.is_empty() && !repo.is_empty()
 }
 
-/// The release endpoints the plugin talks to.
+/// The release server the plugin talks to and what it remembers about it.
 ///
 /// Managed as app state by [`Builder::build`]; the [`check`] and
 /// [`download_and_install`] commands take it via `tauri::State`.
 pub struct Config {
-    latest_json_url: String,
-    releases_url: String,
+    source: Box<dyn UpdateSource>,
+    key: UpdaterKey,
+    channel: Channel,
+    check_interval: Option<Duration>,
+    install_mode: InstallMode,
+    cache: HttpCache,
+    last_check: Mutex<Option<LastCheck>>,
+}
+
+/// The result of the last successful check.
+struct LastCheck {
+    at: Instant,
+    update: Option<FoundUpdate>,
+}
+
+impl Config {
+    fn new(
+        source: Box<dyn UpdateSource>,
+        key: UpdaterKey,
+        channel: Channel,
+        check_interval: Option<Duration>,
+        install_mode: InstallMode,
+    ) -> Self {
+        Self {
+            source,
+            key,
+            channel,
+            check_interval,
+            install_mode,
+            cache: HttpCache::default(),
+            last_check: Mutex::new(None),
+        }
+    }
+
+    /// The result of the last check, if it happened within the check
+    /// interval.
+    fn recent_check(&self) -> Option<Option<FoundUpdate>> {
+        let interval = self.check_interval?;
+        let last_check = self
+            .last_check
+            .lock()
+            .map_err(|e| log::error!("failed to lock last check: {e}"))
+            .ok()?;
+        last_check
+            .as_ref()
+            .filter(|last_check| last_check.at.elapsed() < interval)
+            .map(|last_check| last_check.update.clone())
+    }
+
+    fn remember_check(&self, update: &Option<FoundUpdate>) {
+        match self.last_check.lock() {
+            Ok(mut last_check) => {
+                *last_check = Some(LastCheck {
+                    at: Instant::now(),
+                    update: update.clone(),
+                });
+            }
+            Err(e) => log::error!("failed to lock last check: {e}"),
+        }
+    }
 }
 
 /// The pending update stored between [`check`] and [`download_and_install`].
 #[derive(Clone)]
 struct PendingUpdate {
     version: String,
+    releases_url: String,
+    /// The artifact of the update for the running platform, if the manifest
+    /// lists one.
+    artifact: Option<Artifact>,
 }
 
 /// App-managed state holding the pending update, if any.
@@ -196,6 +429,51 @@ pub struct PendingUpdateInfo(Mutex<Option<PendingUpdate>>);
 #[derive(serde::Deserialize)]
 struct LatestJson {
     version: String,
+    notes: Option<String>,
+    pub_date: Option<String>,
+    #[serde(default)]
+    platforms: HashMap<String, PlatformEntry>,
+}
+
+/// An artifact listed in the `latest.json` update manifest.
+#[derive(serde::Deserialize)]
+struct PlatformEntry {
+    url: String,
+    /// The minisign signature of the artifact.
+    signature: String,
+    /// The hex encoded SHA-256 digest of the artifact, optionally added to
+    /// the manifest next to the signature.
+    sha256: Option<String>,
+}
+
+impl LatestJson {
+    /// The artifact for the platform `target`, like `android-aarch64`.
+    fn artifact(&self, target: &str) -> Option<Artifact> {
+        self.platforms.get(target).map(|entry| Artifact {
+            url: entry.url.clone(),
+            signature: entry.signature.clone(),
+            sha256: entry.sha256.clone(),
+        })
+    }
+}
+
+/// Verifies the `latest.json` manifest against its signature and parses it.
+///
+/// The manifest signature covers the artifact entries, whose signatures must
+/// additionally be made with the same key, so the artifacts verify once
+/// downloaded.
+fn verify_manifest(key: &UpdaterKey, body: &str, signature: &str) -> Result<LatestJson, String> {
+    key.verify(body.as_bytes(), signature)
+        .map_err(|e| format!("failed to verify latest release info: {e}"))?;
+    let latest_json: LatestJson = serde_json::from_str(body)
+        .map_err(|e| format!("failed to parse latest release info: {e}"))?;
+    let mut platforms = latest_json.platforms.iter().collect::<Vec<_>>();
+    platforms.sort_by_key(|(platform, _)| *platform);
+    for (platform, entry) in platforms {
+        key.check_signed_by(&entry.signature)
+            .map_err(|e| format!("failed to verify {platform} artifact entry: {e}"))?;
+    }
+    Ok(latest_json)
 }
 
 /// Compares a fetched release version against the installed version.
@@ -215,20 +493,160 @@ fn compare_versions(fetched: &str, current: &str) -> Result<std::cmp::Ordering,
     Ok(fetched.cmp(&current))
 }
 
+/// Whether a fetched release version is an update on `channel`: newer than
+/// the installed version and, on the stable channel, not a pre-release.
+fn is_update(fetched: &str, current: &str, channel: Channel) -> Result<bool, String> {
+    let ordering = compare_versions(fetched, current)?;
+    let fetched = semver::Version::parse(fetched.strip_prefix('v').unwrap_or(fetched))
+        .map_err(|e| format!("failed to parse latest release version: {e}"))?;
+    Ok(ordering == std::cmp::Ordering::Greater && channel.accepts(&fetched))
+}
+
+/// An update found by [`find_update`]: the metadata [`check`] returns and the
+/// pending update it stores for [`download_and_install`].
+type FoundUpdate = (UpdateMetadata, PendingUpdate);
+
+/// Looks up the newest release on the configured source and channel, and
+/// returns it if its verified manifest is an update of `current_version`.
+async fn find_update(
+    config: &Config,
+    client: &reqwest::Client,
+    current_version: &str,
+) -> Result<Option<FoundUpdate>, String> {
+    let urls = match config.source.lookup(config.channel) {
+        ReleaseLookup::Fixed(urls) => urls,
+        ReleaseLookup::Listing(url) => {
+            let releases = config.cache.fetch_text(client, &url, "releases").await?;
+            let urls = config
+                .source
+                .newest_release(&releases, config.channel)
+                .map_err(|e| {
+                    log::error!("{e}");
+                    e
+                })?;
+            let Some(urls) = urls else {
+                log::info!("no release with an update manifest found");
+                return Ok(None);
+            };
+            urls
+        }
+    };
+    let body = config
+        .cache
+        .fetch_text(client, &urls.latest_json_url, "latest release info")
+        .await?;
+    let signature = config
+        .cache
+        .fetch_text(
+            client,
+            &urls.latest_json_signature_url,
+            "latest release signature",
+        )
+        .await?;
+    let latest_json = verify_manifest(&config.key, &body, &signature).map_err(|e| {
+        log::error!("{e}");
+        e
+    })?;
+
+    if !is_update(&latest_json.version, current_version, config.channel)? {
+        log::info!("app is up to date ({current_version})");
+        return Ok(None);
+    }
+    let version = latest_json.version.trim_start_matches('v').to_string();
+    log::info!("update {version} found");
+    let artifact = latest_json.artifact(&current_target());
+    Ok(Some((
+        UpdateMetadata {
+            version: version.clone(),
+            current_version: current_version.to_string(),
+            notes: latest_json.notes,
+            pub_date: latest_json.pub_date,
+        },
+        PendingUpdate {
+            version,
+            releases_url: urls.releases_url,
+            artifact,
+        },
+    )))
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        // No overall timeout, so large downloads do not time out while they
+        // make progress
+        .connect_timeout(Duration::from_secs(15))
+        .read_timeout(Duration::from_secs(15))
+        // The GitHub API rejects requests without a user agent
+        .user_agent(concat!(
+            "tauri-plugin-android-update/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build()
+        .map_err(|e| {
+            log::error!("failed to build http client: {e}");
+            format!("failed to build http client: {e}")
+        })
+}
+
+/// Downloads and verifies the APK of update `version` into the `updates`
+/// directory of the app cache, which the package installer is granted
+/// access to.
+async fn download_apk<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    key: &UpdaterKey,
+    version: &str,
+    artifact: &Artifact,
+    on_event: &tauri::ipc::Channel<DownloadEvent>,
+) -> Result<PathBuf, String> {
+    let client = http_client()?;
+    let bytes = download(&client, &artifact.url, |event| {
+        if let Err(e) = on_event.send(event) {
+            log::warn!("failed to report download progress: {e}");
+        }
+    })
+    .await?;
+    verify_artifact(key, artifact, &bytes)?;
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("failed to resolve app cache dir: {e}"))?
+        .join("updates");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+    let path = dir.join(format!("update-{version}.apk"));
+    std::fs::write(&path, bytes).map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Opens the system package installer for the APK at `path`.
+#[cfg(target_os = "android")]
+fn install_apk<R: Runtime>(app: &tauri::AppHandle<R>, path: &Path) -> Result<(), String> {
+    app.state::<android::AndroidPlugin<R>>().install(path)
+}
+
+/// Opens the system package installer for the APK at `path`.
+#[cfg(not(target_os = "android"))]
+fn install_apk<R: Runtime>(_app: &tauri::AppHandle<R>, _path: &Path) -> Result<(), String> {
+    Err("installing an APK is only supported on Android".to_string())
+}
+
 mod commands {
     use super::{
-        compare_versions, Config, LatestJson, PendingUpdate, PendingUpdateInfo, UpdateMetadata,
+        current_target, download_apk, find_update, http_client, install_apk, Config, DownloadEvent,
+        InstallMode, PendingUpdateInfo, UpdateMetadata,
     };
     use tauri::Runtime;
     use tauri_plugin_opener::OpenerExt;
 
-    /// Checks the GitHub releases of the configured repository for a version
-    /// newer than the installed one, mirroring the `check` command of
+    /// Checks the configured release server for a version newer than the
+    /// installed one, mirroring the `check` command of
     /// [`tauri-plugin-updater`](https://docs.rs/tauri-plugin-updater).
     ///
     /// Returns the update metadata when a newer release exists and stores it as
     /// the pending update for [`download_and_install`], or `None` when the app is
-    /// up to date.
+    /// up to date. Fails without offering an update when the manifest does not
+    /// verify against the configured public key. Within the check interval,
+    /// the result of the last check is returned again.
     ///
     /// Registered by the plugin under the `plugin:android-update|check` name;
     /// the app must grant the plugin's `default` permission.
@@ -238,64 +656,45 @@ mod commands {
         config: tauri::State<'_, Config>,
         pending_update: tauri::State<'_, PendingUpdateInfo>,
     ) -> Result<Option<UpdateMetadata>, String> {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(15))
-            .build()
-            .map_err(|e| {
-                log::error!("failed to build http client: {e}");
-                format!("failed to build http client: {e}")
-            })?;
-        let body = client
-            .get(&config.latest_json_url)
-            .send()
-            .await
-            .map_err(|e| {
-                log::error!("failed to fetch latest release info: {e}");
-                format!("failed to fetch latest release info: {e}")
-            })?
-            .text()
-            .await
-            .map_err(|e| {
-                log::error!("failed to read latest release info: {e}");
-                format!("failed to read latest release info: {e}")
-            })?;
-        let latest_json: LatestJson = serde_json::from_str(&body).map_err(|e| {
-            log::error!("failed to parse latest release info: {e}");
-            format!("failed to parse latest release info: {e}")
-        })?;
-        let latest_version = latest_json.version.trim_start_matches('v').to_string();
-        let current_version = app.package_info().version.to_string();
-
-        let ordering = compare_versions(&latest_json.version, &current_version)?;
+        let update = match config.recent_check() {
+            Some(update) => {
+                log::debug!("reusing the result of the last check");
+                update
+            }
+            None => {
+                let client = http_client()?;
+                let current_version = app.package_info().version.to_string();
+                let update = find_update(&config, &client, &current_version).await?;
+                config.remember_check(&update);
+                update
+            }
+        };
 
         let mut pending = pending_update.0.lock().map_err(|e| {
             log::error!("failed to lock pending update state: {e}");
             format!("failed to lock pending update state: {e}")
         })?;
-
-        match ordering {
-            std::cmp::Ordering::Greater => {
-                log::info!("update {latest_version} found");
-                *pending = Some(PendingUpdate {
-                    version: latest_version.clone(),
-                });
-                Ok(Some(UpdateMetadata {
-                    version: latest_version,
-                    current_version,
-                }))
+        match update {
+            Some((metadata, update)) => {
+                *pending = Some(update);
+                Ok(Some(metadata))
             }
-            _ => {
-                log::info!("app is up to date ({current_version})");
+            None => {
                 *pending = None;
                 Ok(None)
             }
         }
     }
 
-    /// Opens the release page of the configured repository for the pending
-    /// update, where the user can download the new version manually. Named after
-    /// the `download_and_install` command of
-    /// [`tauri-plugin-updater`](https://docs.rs/tauri-plugin-updater).
+    /// Installs the pending update. Named after the `download_and_install`
+    /// command of [`tauri-plugin-updater`](https://docs.rs/tauri-plugin-updater),
+    /// whose download events it reports on `on_event`.
+    ///
+    /// With [`InstallMode::DownloadApk`](crate::InstallMode::DownloadApk), the
+    /// APK of the pending update is downloaded, verified against the manifest
+    /// and handed to the system package installer. Otherwise, or when that is
+    /// not possible, the release page of the pending update is opened, where
+    /// the user can download the new version manually.
     ///
     /// Registered by the plugin under the `plugin:android-update|`
     /// `download_and_install` name; the app must grant the plugin's `default`
@@ -305,6 +704,7 @@ mod commands {
         app: tauri::AppHandle<R>,
         config: tauri::State<'_, Config>,
         pending_update: tauri::State<'_, PendingUpdateInfo>,
+        on_event: tauri::ipc::Channel<DownloadEvent>,
     ) -> Result<(), String> {
         let pending = pending_update
             .0
@@ -320,13 +720,39 @@ mod commands {
                 "there is no pending update".to_string()
             })?;
 
+        if config.install_mode == InstallMode::DownloadApk {
+            match &pending.artifact {
+                Some(artifact) => {
+                    let path =
+                        download_apk(&app, &config.key, &pending.version, artifact, &on_event)
+                            .await
+                            .map_err(|e| {
+                                log::error!("{e}");
+                                e
+                            })?;
+                    match install_apk(&app, &path) {
+                        Ok(()) => {
+                            log::info!("package installer opened for update {}", pending.version);
+                            return Ok(());
+                        }
+                        Err(e) => log::warn!("{e}, opening releases page instead"),
+                    }
+                }
+                None => log::warn!(
+                    "update {} has no artifact for {}, opening releases page instead",
+                    pending.version,
+                    current_target()
+                ),
+            }
+        }
+
         log::info!(
             "opening releases page for update {}: {}",
             pending.version,
-            config.releases_url
+            pending.releases_url
         );
         app.opener()
-            .open_url(config.releases_url.clone(), None::<String>)
+            .open_url(pending.releases_url.clone(), None::<String>)
             .map_err(|e| {
                 log::error!("failed to open releases page: {e:?}");
                 format!("failed to open releases page: {e:?}")
@@ -339,6 +765,32 @@ mod commands {
 
 pub use commands::{check, download_and_install};
 
+/// Extends the app with the platform APIs of the plugin.
+pub trait AndroidUpdateExt<R: Runtime> {
+    /// Whether the active network is metered, like mobile data, so
+    /// background downloads can be avoided. `false` where that is unknown,
+    /// including all platforms but Android.
+    fn is_network_metered(&self) -> bool;
+}
+
+impl<R: Runtime, T: Manager<R>> AndroidUpdateExt<R> for T {
+    #[cfg(target_os = "android")]
+    fn is_network_metered(&self) -> bool {
+        let Some(plugin) = self.try_state::<android::AndroidPlugin<R>>() else {
+            return false;
+        };
+        plugin.is_network_metered().unwrap_or_else(|e| {
+            log::warn!("{e}, assuming an unmetered network");
+            false
+        })
+    }
+
+    #[cfg(not(target_os = "android"))]
+    fn is_network_metered(&self) -> bool {
+        false
+    }
+}
+
 #[cfg(test)]
 mod compare_versions_tests {
     use super::compare_versions;
@@ -371,30 +823,330 @@ mod compare_versions_tests {
     }
 }
 
+#[cfg(test)]
+mod is_update_tests {
+    use super::{is_update, Channel};
+
+    #[test]
+    fn test_is_update_stable_ignores_pre_releases() {
+        assert_eq!(
+            is_update("2.1.0-beta.1", "2.0.0", Channel::Stable),
+            Ok(false)
+        );
+        assert_eq!(is_update("2.1.0", "2.0.0", Channel::Stable), Ok(true));
+    }
+
+    #[test]
+    fn test_is_update_beta_offers_pre_releases() {
+        assert_eq!(is_update("v2.1.0-beta.1", "2.0.0", Channel::Beta), Ok(true));
+        assert_eq!(is_update("2.1.0", "2.0.0", Channel::Beta), Ok(true));
+    }
+
+    #[test]
+    fn test_is_update_honors_pre_release_precedence() {
+        assert_eq!(
+            is_update("2.1.0-beta.2", "2.1.0-beta.1", Channel::Beta),
+            Ok(true)
+        );
+        assert_eq!(
+            is_update("2.1.0", "2.1.0-beta.2", Channel::Stable),
+            Ok(true)
+        );
+        assert_eq!(is_update("2.1.0-beta.1", "2.1.0", Channel::Beta), Ok(false));
+    }
+
+    #[test]
+    fn test_is_update_malformed_is_rejected() {
+        assert!(is_update("not-a-version", "2.0.0", Channel::Beta).is_err());
+    }
+}
+
+#[cfg(test)]
+mod verify_manifest_tests {
+    use super::{verify_manifest, UpdaterKey};
+
+    const PUBLIC_KEY: &str = include_str!("../testdata/public.key");
+    const OTHER_PUBLIC_KEY: &str = include_str!("../testdata/other_public.key");
+    const LATEST_JSON: &str = include_str!("../testdata/latest.json");
+    const LATEST_JSON_SIG: &str = include_str!("../testdata/latest.json.sig");
+    const FOREIGN_ENTRIES_JSON: &str = include_str!("../testdata/foreign_entries.json");
+    const FOREIGN_ENTRIES_JSON_SIG: &str = include_str!("../testdata/foreign_entries.json.sig");
+
+    #[test]
+    fn test_verify_manifest_accepts_signed_manifest() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        let latest_json = verify_manifest(&key, LATEST_JSON, LATEST_JSON_SIG).unwrap();
+        assert_eq!(latest_json.version, "2.0.0");
+        assert_eq!(latest_json.notes.as_deref(), Some("Test release"));
+        assert_eq!(
+            latest_json.pub_date.as_deref(),
+            Some("2026-01-01T00:00:00Z")
+        );
+        assert_eq!(latest_json.platforms.len(), 2);
+    }
+
+    #[test]
+    fn test_artifact_for_platform() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        let latest_json = verify_manifest(&key, LATEST_JSON, LATEST_JSON_SIG).unwrap();
+        let artifact = latest_json.artifact("android-aarch64").unwrap();
+        assert_eq!(artifact.url, "https://example.com/app.apk");
+        assert!(artifact.sha256.is_some());
+        assert_eq!(latest_json.artifact("linux-x86_64").unwrap().sha256, None);
+        assert_eq!(latest_json.artifact("windows-x86_64"), None);
+    }
+
+    #[test]
+    fn test_verify_manifest_rejects_tampered_manifest() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        let tampered =
+            LATEST_JSON.replace("https://example.com/app.apk", "https://evil.test/app.apk");
+        assert!(verify_manifest(&key, &tampered, LATEST_JSON_SIG).is_err());
+    }
+
+    #[test]
+    fn test_verify_manifest_rejects_other_key() {
+        let key = UpdaterKey::decode(OTHER_PUBLIC_KEY).unwrap();
+        assert!(verify_manifest(&key, LATEST_JSON, LATEST_JSON_SIG).is_err());
+    }
+
+    #[test]
+    fn test_verify_manifest_rejects_entries_signed_with_other_key() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        let err = verify_manifest(&key, FOREIGN_ENTRIES_JSON, FOREIGN_ENTRIES_JSON_SIG)
+            .err()
+            .unwrap();
+        assert!(err.contains("artifact entry"), "{err}");
+    }
+
+    #[test]
+    fn test_verify_manifest_rejects_missing_signature() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        assert!(verify_manifest(&key, LATEST_JSON, "").is_err());
+    }
+}
+
 #[cfg(test)]
 mod builder_tests {
-    use super::{github_latest_json_url, github_releases_url};
+    use super::{Builder, Channel, GitHub, ReleaseLookup, ReleaseUrls, StaticUrls};
 
     #[test]
-    fn test_github_urls_derived_from_owner_and_repo() {
+    fn test_owner_and_repo_check_github() {
+        let source = Builder::new()
+            .owner("hrzlgnm")
+            .repo("mdns-browser")
+            .into_source()
+            .unwrap();
         assert_eq!(
-            github_latest_json_url("hrzlgnm", "mdns-browser"),
-            Some(
-                "https://github.com/hrzlgnm/mdns-browser/releases/latest/download/latest.json"
-                    .to_string()
-            )
+            source.lookup(Channel::Stable),
+            GitHub::new("hrzlgnm", "mdns-browser").lookup(Channel::Stable)
         );
         assert_eq!(
-            github_releases_url("hrzlgnm", "mdns-browser"),
-            Some("https://github.com/hrzlgnm/mdns-browser/releases/latest".to_string())
+            source.lookup(Channel::Beta),
+            GitHub::new("hrzlgnm", "mdns-browser").lookup(Channel::Beta)
         );
     }
 
     #[test]
-    fn test_github_urls_require_owner_and_repo() {
-        assert_eq!(github_latest_json_url("", "mdns-browser"), None);
-        assert_eq!(github_latest_json_url("hrzlgnm", ""), None);
-        assert_eq!(github_latest_json_url("", ""), None);
-        assert_eq!(github_releases_url("", "mdns-browser"), None);
+    fn test_explicit_urls_are_completed_from_owner_and_repo() {
+        let source = Builder::new()
+            .owner("hrzlgnm")
+            .repo("mdns-browser")
+            .latest_json_url("https://example.com/app/latest.json")
+            .into_source()
+            .unwrap();
+        let expected = ReleaseLookup::Fixed(ReleaseUrls::new(
+            "https://example.com/app/latest.json",
+            "https://github.com/hrzlgnm/mdns-browser/releases/latest",
+        ));
+        assert_eq!(source.lookup(Channel::Stable), expected);
+        assert_eq!(source.lookup(Channel::Beta), expected);
+    }
+
+    #[test]
+    fn test_signature_url_override() {
+        let source = Builder::new()
+            .latest_json_url("https://example.com/app/latest.json")
+            .latest_json_signature_url("https://example.com/app/signature")
+            .releases_url("https://example.com/app")
+            .into_source()
+            .unwrap();
+        assert_eq!(
+            source.lookup(Channel::Stable),
+            ReleaseLookup::Fixed(ReleaseUrls {
+                latest_json_url: "https://example.com/app/latest.json".to_string(),
+                latest_json_signature_url: "https://example.com/app/signature".to_string(),
+                releases_url: "https://example.com/app".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_source_takes_precedence() {
+        let urls = ReleaseUrls::new(
+            "https://example.com/app/latest.json",
+            "https://example.com/app",
+        );
+        let source = Builder::new()
+            .owner("hrzlgnm")
+            .repo("mdns-browser")
+            .source(StaticUrls(urls.clone()))
+            .into_source()
+            .unwrap();
+        assert_eq!(source.lookup(Channel::Beta), ReleaseLookup::Fixed(urls));
+    }
+
+    #[test]
+    fn test_missing_configuration() {
+        assert!(Builder::new().into_source().is_none());
+        assert!(Builder::new().owner("hrzlgnm").into_source().is_none());
+        assert!(Builder::new()
+            .latest_json_url("https://example.com/app/latest.json")
+            .into_source()
+            .is_none());
+    }
+}
+
+#[cfg(test)]
+mod find_update_tests {
+    use std::time::Duration;
+
+    use super::{
+        find_update, Channel, Config, Gitea, InstallMode, ReleaseUrls, StaticUrls, UpdaterKey,
+    };
+    use crate::test_server::{Request, Response, TestServer};
+
+    const PUBLIC_KEY: &str = include_str!("../testdata/public.key");
+    const LATEST_JSON: &str = include_str!("../testdata/latest.json");
+    const LATEST_JSON_SIG: &str = include_str!("../testdata/latest.json.sig");
+    const LATEST_JSON_OTHER_SIG: &str = include_str!("../testdata/latest.json.other.sig");
+
+    fn serve_release(request: &Request) -> Response {
+        match request.path.rsplit('/').next() {
+            Some("latest.json") => Response::ok(LATEST_JSON).with_header("ETag", "\"manifest\""),
+            Some("latest.json.sig") => Response::ok(LATEST_JSON_SIG),
+            _ => Response::status(404),
+        }
+    }
+
+    fn config(source: impl super::UpdateSource + 'static) -> Config {
+        Config::new(
+            Box::new(source),
+            UpdaterKey::decode(PUBLIC_KEY).unwrap(),
+            Channel::Stable,
+            None,
+            InstallMode::OpenReleasePage,
+        )
+    }
+
+    fn static_urls(server: &TestServer) -> StaticUrls {
+        StaticUrls(ReleaseUrls::new(
+            server.url("/latest.json"),
+            server.url("/releases"),
+        ))
+    }
+
+    #[test]
+    fn test_find_update_from_static_urls() {
+        let server = TestServer::start(serve_release);
+        let config = config(static_urls(&server));
+        let client = reqwest::Client::new();
+        let (metadata, pending) =
+            tauri::async_runtime::block_on(find_update(&config, &client, "1.0.0"))
+                .unwrap()
+                .unwrap();
+        assert_eq!(metadata.version, "2.0.0");
+        assert_eq!(metadata.current_version, "1.0.0");
+        assert_eq!(metadata.notes.as_deref(), Some("Test release"));
+        assert_eq!(pending.releases_url, server.url("/releases"));
+    }
+
+    #[test]
+    fn test_find_update_up_to_date_revalidates_manifest() {
+        let server = TestServer::start(|request| {
+            if request.header("if-none-match") == Some("\"manifest\"") {
+                Response::not_modified()
+            } else {
+                serve_release(request)
+            }
+        });
+        let config = config(static_urls(&server));
+        let client = reqwest::Client::new();
+        tauri::async_runtime::block_on(async {
+            assert!(find_update(&config, &client, "2.0.0")
+                .await
+                .unwrap()
+                .is_none());
+            assert!(find_update(&config, &client, "1.0.0")
+                .await
+                .unwrap()
+                .is_some());
+        });
+        let revalidated = server
+            .requests()
+            .iter()
+            .filter(|request| request.header("if-none-match").is_some())
+            .count();
+        assert_eq!(revalidated, 1);
+    }
+
+    #[test]
+    fn test_find_update_from_release_listing() {
+        let server = TestServer::start(|request| {
+            if request.path == "/api/v1/repos/o/r/releases?limit=10" {
+                let host = request.header("host").unwrap_or_default();
+                let asset = |name: &str| {
+                    serde_json::json!({
+                        "name": name,
+                        "browser_download_url": format!("http://{host}/v2.0.0/{name}"),
+                    })
+                };
+                let releases = serde_json::json!([{
+                    "html_url": format!("http://{host}/o/r/releases/tag/v2.0.0"),
+                    "draft": false,
+                    "prerelease": false,
+                    "assets": [asset("latest.json"), asset("latest.json.sig")],
+                }]);
+                Response::ok(releases.to_string())
+            } else {
+                serve_release(request)
+            }
+        });
+        let config = config(Gitea::new(server.url(""), "o", "r"));
+        let client = reqwest::Client::new();
+        let (metadata, pending) =
+            tauri::async_runtime::block_on(find_update(&config, &client, "1.0.0"))
+                .unwrap()
+                .unwrap();
+        assert_eq!(metadata.version, "2.0.0");
+        assert_eq!(pending.releases_url, server.url("/o/r/releases/tag/v2.0.0"));
+    }
+
+    #[test]
+    fn test_find_update_rejects_manifest_signed_with_other_key() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/latest.json.sig" => Response::ok(LATEST_JSON_OTHER_SIG),
+            _ => serve_release(request),
+        });
+        let config = config(static_urls(&server));
+        let client = reqwest::Client::new();
+        assert!(tauri::async_runtime::block_on(find_update(&config, &client, "1.0.0")).is_err());
+    }
+
+    #[test]
+    fn test_recent_check_within_interval() {
+        let mut config = config(StaticUrls(ReleaseUrls::new(
+            "http://127.0.0.1/latest.json",
+            "http://127.0.0.1/releases",
+        )));
+        config.remember_check(&None);
+        assert!(config.recent_check().is_none());
+        config.check_interval = Some(Duration::from_secs(3600));
+        assert_eq!(
+            config.recent_check().map(|update| update.is_none()),
+            Some(true)
+        );
+        config.check_interval = Some(Duration::ZERO);
+        assert!(config.recent_check().is_none());
     }
 }
@@ -0,0 +1,147 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+//! Verification of the minisign signatures the Tauri updater tooling produces.
+//!
+//! Public keys and signatures are stored the way `tauri signer` writes them
+//! and `tauri-plugin-updater` reads them: the minisign key or signature file,
+//! base64 encoded once more so it fits on a single line.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use minisign_verify::{PublicKey, Signature};
+
+/// Length of a minisign key id in bytes.
+const KEY_ID_LEN: usize = 8;
+
+/// A public key configured with [`crate::Builder::pubkey`].
+pub(crate) struct UpdaterKey {
+    public_key: PublicKey,
+    key_id: [u8; KEY_ID_LEN],
+}
+
+impl UpdaterKey {
+    /// Decodes a public key as found in the `plugins.updater.pubkey` entry
+    /// of `tauri.conf.json`.
+    pub(crate) fn decode(pubkey: &str) -> Result<Self, String> {
+        let text = decode_base64_text(pubkey, "public key")?;
+        let public_key =
+            PublicKey::decode(&text).map_err(|e| format!("failed to decode public key: {e}"))?;
+        // Public key: 2 bytes algorithm, 8 bytes key id, 32 bytes key
+        let key_id = key_id_of(&text, 2 + KEY_ID_LEN + 32, "public key")?;
+        Ok(Self { public_key, key_id })
+    }
+
+    /// Verifies that `signature` was made over `data` with this key.
+    pub(crate) fn verify(&self, data: &[u8], signature: &str) -> Result<(), String> {
+        let text = decode_base64_text(signature, "signature")?;
+        let signature =
+            Signature::decode(&text).map_err(|e| format!("failed to decode signature: {e}"))?;
+        self.public_key
+            .verify(data, &signature, false)
+            .map_err(|e| format!("signature verification failed: {e}"))
+    }
+
+    /// Checks that `signature` is a well-formed signature made with this key,
+    /// without access to the data it signs.
+    ///
+    /// Used for the artifact entries of the update manifest: the manifest
+    /// signature covers them, and the artifacts themselves are only verified
+    /// once downloaded.
+    pub(crate) fn check_signed_by(&self, signature: &str) -> Result<(), String> {
+        let text = decode_base64_text(signature, "signature")?;
+        Signature::decode(&text).map_err(|e| format!("failed to decode signature: {e}"))?;
+        // Signature: 2 bytes algorithm, 8 bytes key id, 64 bytes signature
+        let key_id = key_id_of(&text, 2 + KEY_ID_LEN + 64, "signature")?;
+        if key_id != self.key_id {
+            return Err("signature was made with a different key".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn decode_base64_text(encoded: &str, what: &str) -> Result<String, String> {
+    let decoded = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("failed to decode {what}: {e}"))?;
+    String::from_utf8(decoded).map_err(|e| format!("failed to decode {what}: {e}"))
+}
+
+/// Extracts the key id from the second line of a minisign key or signature
+/// file, which holds the base64 encoded binary form of `expected_len` bytes.
+fn key_id_of(text: &str, expected_len: usize, what: &str) -> Result<[u8; KEY_ID_LEN], String> {
+    let line = text
+        .lines()
+        .nth(1)
+        .ok_or_else(|| format!("failed to decode {what}: missing data"))?;
+    let bin = STANDARD
+        .decode(line.trim())
+        .map_err(|e| format!("failed to decode {what}: {e}"))?;
+    if bin.len() != expected_len {
+        return Err(format!("failed to decode {what}: unexpected length"));
+    }
+    let mut key_id = [0; KEY_ID_LEN];
+    key_id.copy_from_slice(&bin[2..2 + KEY_ID_LEN]);
+    Ok(key_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UpdaterKey;
+
+    // Keys and signatures generated locally for these tests, in the format of
+    // `tauri signer generate` and `tauri signer sign`.
+    const PUBLIC_KEY: &str = include_str!("../testdata/public.key");
+    const OTHER_PUBLIC_KEY: &str = include_str!("../testdata/other_public.key");
+    const LATEST_JSON: &str = include_str!("../testdata/latest.json");
+    const LATEST_JSON_SIG: &str = include_str!("../testdata/latest.json.sig");
+    const LATEST_JSON_OTHER_SIG: &str = include_str!("../testdata/latest.json.other.sig");
+
+    #[test]
+    fn test_verify_accepts_valid_signature() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        assert_eq!(key.verify(LATEST_JSON.as_bytes(), LATEST_JSON_SIG), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_data() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        let tampered = LATEST_JSON.replace("2.0.0", "9.0.0");
+        assert!(key.verify(tampered.as_bytes(), LATEST_JSON_SIG).is_err());
+    }
+
+    #[test]
+    fn test_verify_rejects_signature_of_other_key() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        assert!(key
+            .verify(LATEST_JSON.as_bytes(), LATEST_JSON_OTHER_SIG)
+            .is_err());
+        let other = UpdaterKey::decode(OTHER_PUBLIC_KEY).unwrap();
+        assert_eq!(
+            other.verify(LATEST_JSON.as_bytes(), LATEST_JSON_OTHER_SIG),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_signature() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        assert!(key.verify(LATEST_JSON.as_bytes(), "").is_err());
+        assert!(key.verify(LATEST_JSON.as_bytes(), "not base64!").is_err());
+        assert!(key.verify(LATEST_JSON.as_bytes(), PUBLIC_KEY).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed_public_key() {
+        assert!(UpdaterKey::decode("").is_err());
+        assert!(UpdaterKey::decode("not base64!").is_err());
+        assert!(UpdaterKey::decode(LATEST_JSON_SIG).is_err());
+    }
+
+    #[test]
+    fn test_check_signed_by() {
+        let key = UpdaterKey::decode(PUBLIC_KEY).unwrap();
+        assert_eq!(key.check_signed_by(LATEST_JSON_SIG), Ok(()));
+        assert!(key.check_signed_by(LATEST_JSON_OTHER_SIG).is_err());
+        assert!(key.check_signed_by("").is_err());
+    }
+}
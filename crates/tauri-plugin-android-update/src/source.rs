@@ -0,0 +1,484 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+//! Release servers the `latest.json` update manifest is read from.
+
+use crate::Channel;
+
+/// Where the manifest of a release, its signature and its release page are
+/// found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReleaseUrls {
+    /// The `latest.json` update manifest.
+    pub latest_json_url: String,
+    /// The minisign signature of the manifest.
+    pub latest_json_signature_url: String,
+    /// The page [`crate::download_and_install`] opens.
+    pub releases_url: String,
+}
+
+impl ReleaseUrls {
+    /// Creates the URLs of a manifest whose signature is published next to
+    /// it, with `.sig` appended.
+    pub fn new(latest_json_url: impl Into<String>, releases_url: impl Into<String>) -> Self {
+        let latest_json_url = latest_json_url.into();
+        Self {
+            latest_json_signature_url: format!("{latest_json_url}.sig"),
+            latest_json_url,
+            releases_url: releases_url.into(),
+        }
+    }
+}
+
+/// How an [`UpdateSource`] finds the manifest of the newest release.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReleaseLookup {
+    /// The manifest of the newest release is always at the same location.
+    Fixed(ReleaseUrls),
+    /// The releases listed at this URL are searched with
+    /// [`UpdateSource::newest_release`].
+    Listing(String),
+}
+
+/// A release server publishing the signed `latest.json` update manifest.
+///
+/// Implemented for [`GitHub`], [`Gitea`] (and Forgejo), [`GitLab`] and
+/// [`StaticUrls`]. Implement it to check a release server with another API.
+pub trait UpdateSource: Send + Sync {
+    /// How the manifest of the newest release on `channel` is found.
+    fn lookup(&self, channel: Channel) -> ReleaseLookup;
+
+    /// Finds the newest release on `channel` in the response of a
+    /// [`ReleaseLookup::Listing`], `None` if no release has a manifest.
+    fn newest_release(
+        &self,
+        releases: &str,
+        channel: Channel,
+    ) -> Result<Option<ReleaseUrls>, String> {
+        let _ = (releases, channel);
+        Ok(None)
+    }
+}
+
+/// Manifest and release page at fixed URLs, e.g. on a plain web server.
+pub struct StaticUrls(pub ReleaseUrls);
+
+impl UpdateSource for StaticUrls {
+    fn lookup(&self, _channel: Channel) -> ReleaseLookup {
+        ReleaseLookup::Fixed(self.0.clone())
+    }
+}
+
+/// Releases of a repository on GitHub.
+///
+/// The stable channel reads the manifest of the latest release directly.
+/// GitHub's latest release is never a pre-release, so the beta channel
+/// searches the releases listed by the GitHub API instead.
+pub struct GitHub {
+    owner: String,
+    repo: String,
+}
+
+impl GitHub {
+    /// Checks the releases of `https://github.com/{owner}/{repo}`.
+    pub fn new(owner: impl Into<String>, repo: impl Into<String>) -> Self {
+        Self {
+            owner: owner.into(),
+            repo: repo.into(),
+        }
+    }
+
+    /// The `latest.json` manifest attached to the latest release.
+    pub(crate) fn latest_json_url(&self) -> String {
+        format!(
+            "https://github.com/{}/{}/releases/latest/download/latest.json",
+            self.owner, self.repo
+        )
+    }
+
+    /// The page of the latest release.
+    pub(crate) fn latest_release_url(&self) -> String {
+        format!(
+            "https://github.com/{}/{}/releases/latest",
+            self.owner, self.repo
+        )
+    }
+}
+
+impl UpdateSource for GitHub {
+    fn lookup(&self, channel: Channel) -> ReleaseLookup {
+        match channel {
+            Channel::Stable => ReleaseLookup::Fixed(ReleaseUrls::new(
+                self.latest_json_url(),
+                self.latest_release_url(),
+            )),
+            Channel::Beta => ReleaseLookup::Listing(format!(
+                "https://api.github.com/repos/{}/{}/releases?per_page=10",
+                self.owner, self.repo
+            )),
+        }
+    }
+
+    fn newest_release(
+        &self,
+        releases: &str,
+        channel: Channel,
+    ) -> Result<Option<ReleaseUrls>, String> {
+        newest_github_style_release(releases, channel)
+    }
+}
+
+/// Releases of a repository on a Gitea or Forgejo instance, whose release
+/// API matches GitHub's.
+pub struct Gitea {
+    base_url: String,
+    owner: String,
+    repo: String,
+}
+
+impl Gitea {
+    /// `base_url` is the address of the instance, like `https://codeberg.org`.
+    pub fn new(
+        base_url: impl Into<String>,
+        owner: impl Into<String>,
+        repo: impl Into<String>,
+    ) -> Self {
+        Self {
+            base_url: base_url.into(),
+            owner: owner.into(),
+            repo: repo.into(),
+        }
+    }
+}
+
+impl UpdateSource for Gitea {
+    fn lookup(&self, _channel: Channel) -> ReleaseLookup {
+        ReleaseLookup::Listing(format!(
+            "{}/api/v1/repos/{}/{}/releases?limit=10",
+            self.base_url.trim_end_matches('/'),
+            self.owner,
+            self.repo
+        ))
+    }
+
+    fn newest_release(
+        &self,
+        releases: &str,
+        channel: Channel,
+    ) -> Result<Option<ReleaseUrls>, String> {
+        newest_github_style_release(releases, channel)
+    }
+}
+
+/// Releases of a project on a GitLab instance, with the manifest and its
+/// signature attached as release links.
+///
+/// GitLab has no pre-release flag, so releases are told apart by the semver
+/// pre-release tag of their tag name.
+pub struct GitLab {
+    base_url: String,
+    project: String,
+}
+
+impl GitLab {
+    /// `base_url` is the address of the instance, like `https://gitlab.com`,
+    /// and `project` the path of the project, like `group/app`, or its id.
+    pub fn new(base_url: impl Into<String>, project: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            project: project.into(),
+        }
+    }
+}
+
+impl UpdateSource for GitLab {
+    fn lookup(&self, _channel: Channel) -> ReleaseLookup {
+        ReleaseLookup::Listing(format!(
+            "{}/api/v4/projects/{}/releases?per_page=10",
+            self.base_url.trim_end_matches('/'),
+            self.project.replace('/', "%2F")
+        ))
+    }
+
+    fn newest_release(
+        &self,
+        releases: &str,
+        channel: Channel,
+    ) -> Result<Option<ReleaseUrls>, String> {
+        let releases: Vec<GitLabRelease> =
+            serde_json::from_str(releases).map_err(|e| format!("failed to parse releases: {e}"))?;
+        Ok(releases
+            .into_iter()
+            .filter(|release| !release.upcoming_release)
+            .filter(|release| {
+                let tag = release
+                    .tag_name
+                    .strip_prefix('v')
+                    .unwrap_or(&release.tag_name);
+                semver::Version::parse(tag)
+                    .ok()
+                    .is_none_or(|version| channel.accepts(&version))
+            })
+            .find_map(|release| {
+                let link_url = |name: &str| {
+                    release
+                        .assets
+                        .links
+                        .iter()
+                        .find(|link| link.name == name)
+                        .map(|link| {
+                            link.direct_asset_url
+                                .clone()
+                                .unwrap_or_else(|| link.url.clone())
+                        })
+                };
+                Some(ReleaseUrls {
+                    latest_json_url: link_url("latest.json")?,
+                    latest_json_signature_url: link_url("latest.json.sig")?,
+                    releases_url: release.links.self_url.clone(),
+                })
+            }))
+    }
+}
+
+/// A release as listed by the GitHub, Gitea and Forgejo release APIs.
+#[derive(serde::Deserialize)]
+struct GitHubRelease {
+    html_url: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<GitHubAsset>,
+}
+
+#[derive(serde::Deserialize)]
+struct GitHubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Finds the newest published release on `channel` with a signed
+/// `latest.json` manifest in a GitHub style release listing.
+fn newest_github_style_release(
+    releases: &str,
+    channel: Channel,
+) -> Result<Option<ReleaseUrls>, String> {
+    let releases: Vec<GitHubRelease> =
+        serde_json::from_str(releases).map_err(|e| format!("failed to parse releases: {e}"))?;
+    Ok(releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .filter(|release| channel == Channel::Beta || !release.prerelease)
+        .find_map(|release| {
+            let asset_url = |name: &str| {
+                release
+                    .assets
+                    .iter()
+                    .find(|asset| asset.name == name)
+                    .map(|asset| asset.browser_download_url.clone())
+            };
+            Some(ReleaseUrls {
+                latest_json_url: asset_url("latest.json")?,
+                latest_json_signature_url: asset_url("latest.json.sig")?,
+                releases_url: release.html_url.clone(),
+            })
+        }))
+}
+
+/// A release as listed by the GitLab release API.
+#[derive(serde::Deserialize)]
+struct GitLabRelease {
+    tag_name: String,
+    #[serde(default)]
+    upcoming_release: bool,
+    #[serde(rename = "_links")]
+    links: GitLabReleaseLinks,
+    assets: GitLabAssets,
+}
+
+#[derive(serde::Deserialize)]
+struct GitLabReleaseLinks {
+    #[serde(rename = "self")]
+    self_url: String,
+}
+
+#[derive(serde::Deserialize)]
+struct GitLabAssets {
+    #[serde(default)]
+    links: Vec<GitLabLink>,
+}
+
+#[derive(serde::Deserialize)]
+struct GitLabLink {
+    name: String,
+    url: String,
+    direct_asset_url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn github_release(tag: &str, draft: bool, assets: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "html_url": format!("https://github.com/o/r/releases/tag/{tag}"),
+            "draft": draft,
+            "prerelease": tag.contains('-'),
+            "assets": assets.iter().map(|name| serde_json::json!({
+                "name": name,
+                "browser_download_url": format!("https://github.com/o/r/releases/download/{tag}/{name}"),
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    fn github_release_urls(tag: &str) -> ReleaseUrls {
+        ReleaseUrls::new(
+            format!("https://github.com/o/r/releases/download/{tag}/latest.json"),
+            format!("https://github.com/o/r/releases/tag/{tag}"),
+        )
+    }
+
+    #[test]
+    fn test_github_stable_reads_latest_release() {
+        assert_eq!(
+            GitHub::new("hrzlgnm", "mdns-browser").lookup(Channel::Stable),
+            ReleaseLookup::Fixed(ReleaseUrls {
+                latest_json_url:
+                    "https://github.com/hrzlgnm/mdns-browser/releases/latest/download/latest.json"
+                        .to_string(),
+                latest_json_signature_url:
+                    "https://github.com/hrzlgnm/mdns-browser/releases/latest/download/latest.json.sig"
+                        .to_string(),
+                releases_url: "https://github.com/hrzlgnm/mdns-browser/releases/latest".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_github_beta_lists_releases() {
+        assert_eq!(
+            GitHub::new("hrzlgnm", "mdns-browser").lookup(Channel::Beta),
+            ReleaseLookup::Listing(
+                "https://api.github.com/repos/hrzlgnm/mdns-browser/releases?per_page=10"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_github_newest_release_skips_drafts_and_releases_without_manifest() {
+        let releases = serde_json::json!([
+            github_release("v2.1.0", true, &["latest.json", "latest.json.sig"]),
+            github_release("v2.1.0-beta.2", false, &["latest.json"]),
+            github_release(
+                "v2.1.0-beta.1",
+                false,
+                &["app.apk", "latest.json", "latest.json.sig"]
+            ),
+            github_release("v2.0.0", false, &["latest.json", "latest.json.sig"]),
+        ])
+        .to_string();
+        let github = GitHub::new("o", "r");
+        assert_eq!(
+            github.newest_release(&releases, Channel::Beta),
+            Ok(Some(github_release_urls("v2.1.0-beta.1")))
+        );
+        assert_eq!(
+            github.newest_release(&releases, Channel::Stable),
+            Ok(Some(github_release_urls("v2.0.0")))
+        );
+    }
+
+    #[test]
+    fn test_github_newest_release_without_releases() {
+        let github = GitHub::new("o", "r");
+        assert_eq!(github.newest_release("[]", Channel::Beta), Ok(None));
+        assert!(github.newest_release("{}", Channel::Beta).is_err());
+    }
+
+    #[test]
+    fn test_gitea_lists_releases() {
+        assert_eq!(
+            Gitea::new("https://codeberg.org/", "o", "r").lookup(Channel::Stable),
+            ReleaseLookup::Listing(
+                "https://codeberg.org/api/v1/repos/o/r/releases?limit=10".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_gitlab_lists_releases_of_encoded_project() {
+        assert_eq!(
+            GitLab::new("https://gitlab.example.com", "group/app").lookup(Channel::Beta),
+            ReleaseLookup::Listing(
+                "https://gitlab.example.com/api/v4/projects/group%2Fapp/releases?per_page=10"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_gitlab_newest_release_honors_channel() {
+        let release = |tag: &str, upcoming: bool| {
+            serde_json::json!({
+                "tag_name": tag,
+                "upcoming_release": upcoming,
+                "_links": { "self": format!("https://gitlab.example.com/g/a/-/releases/{tag}") },
+                "assets": { "links": [
+                    { "name": "latest.json", "url": format!("https://files.example.com/{tag}/latest.json") },
+                    {
+                        "name": "latest.json.sig",
+                        "url": format!("https://files.example.com/{tag}/latest.json.sig"),
+                        "direct_asset_url": format!("https://gitlab.example.com/g/a/-/releases/{tag}/downloads/latest.json.sig"),
+                    },
+                ] },
+            })
+        };
+        let releases = serde_json::json!([
+            release("v3.0.0", true),
+            release("v2.1.0-rc.1", false),
+            release("v2.0.0", false),
+        ])
+        .to_string();
+        let gitlab = GitLab::new("https://gitlab.example.com", "g/a");
+        assert_eq!(
+            gitlab.newest_release(&releases, Channel::Stable),
+            Ok(Some(ReleaseUrls {
+                latest_json_url: "https://files.example.com/v2.0.0/latest.json".to_string(),
+                latest_json_signature_url:
+                    "https://gitlab.example.com/g/a/-/releases/v2.0.0/downloads/latest.json.sig"
+                        .to_string(),
+                releases_url: "https://gitlab.example.com/g/a/-/releases/v2.0.0".to_string(),
+            }))
+        );
+        assert_eq!(
+            gitlab
+                .newest_release(&releases, Channel::Beta)
+                .map(|urls| urls.map(|urls| urls.releases_url)),
+            Ok(Some(
+                "https://gitlab.example.com/g/a/-/releases/v2.1.0-rc.1".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_static_urls_on_every_channel() {
+        let urls = ReleaseUrls::new(
+            "https://example.com/app/latest.json",
+            "https://example.com/app/download",
+        );
+        assert_eq!(
+            urls.latest_json_signature_url,
+            "https://example.com/app/latest.json.sig"
+        );
+        let source = StaticUrls(urls.clone());
+        assert_eq!(
+            source.lookup(Channel::Stable),
+            ReleaseLookup::Fixed(urls.clone())
+        );
+        assert_eq!(source.lookup(Channel::Beta), ReleaseLookup::Fixed(urls));
+        assert_eq!(source.newest_release("[]", Channel::Beta), Ok(None));
+    }
+}
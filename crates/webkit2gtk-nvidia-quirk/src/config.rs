@@ -0,0 +1,404 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+//! Config files that force or suppress workarounds, so packagers and users do
+//! not need to pass command line flags.
+//!
+//! The files consist of `key = value` lines, `#` starts a comment:
+//!
+//! ```text
+//! # auto (default), force or suppress
+//! disable-dmabuf-renderer = force
+//! disable-nv-explicit-sync = suppress
+//! verbose = true
+//! # set by the application when a workaround fixed a crash
+//! remembered-workaround = disable-dmabuf-renderer
+//! ```
+//!
+//! The system file `$XDG_CONFIG_DIRS/<app>/gpu-workarounds.conf` (by default in
+//! `/etc/xdg`) is read first, then the user file
+//! `$XDG_CONFIG_HOME/<app>/gpu-workarounds.conf` (by default in `~/.config`),
+//! whose settings take precedence.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::WorkaroundKind;
+
+const CONFIG_FILE_NAME: &str = "gpu-workarounds.conf";
+const REMEMBERED_KEY: &str = "remembered-workaround";
+
+/// Whether a workaround is applied as detected, always or never.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WorkaroundSetting {
+    /// Applied when detected.
+    #[default]
+    Auto,
+    /// Always applied.
+    Force,
+    /// Never applied automatically.
+    Suppress,
+}
+
+/// The workaround overrides read from the config files.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkaroundConfig {
+    pub disable_dmabuf_renderer: WorkaroundSetting,
+    pub disable_nv_explicit_sync: WorkaroundSetting,
+    /// Print diagnostic notes when applying a workaround.
+    pub verbose: Option<bool>,
+    /// A workaround that fixed a crash, applied as if it was forced.
+    pub remembered_workaround: Option<WorkaroundKind>,
+}
+
+/// An invalid line in a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// The name of a workaround in the config files.
+fn workaround_name(kind: WorkaroundKind) -> &'static str {
+    match kind {
+        WorkaroundKind::None => "none",
+        WorkaroundKind::DisableWebkitDmabufRenderer => "disable-dmabuf-renderer",
+        WorkaroundKind::DisableNvExplicitSync => "disable-nv-explicit-sync",
+    }
+}
+
+fn parse_workaround(value: &str) -> Option<WorkaroundKind> {
+    [
+        WorkaroundKind::None,
+        WorkaroundKind::DisableWebkitDmabufRenderer,
+        WorkaroundKind::DisableNvExplicitSync,
+    ]
+    .into_iter()
+    .find(|kind| workaround_name(*kind) == value)
+}
+
+fn parse_setting(value: &str) -> Option<WorkaroundSetting> {
+    match value {
+        "auto" => Some(WorkaroundSetting::Auto),
+        "force" => Some(WorkaroundSetting::Force),
+        "suppress" => Some(WorkaroundSetting::Suppress),
+        _ => None,
+    }
+}
+
+/// Splits a config line into its key and value, `None` for blank lines and
+/// comments.
+fn key_value(line: &str) -> Option<Result<(&str, &str), String>> {
+    let line = line.split('#').next().unwrap_or_default().trim();
+    if line.is_empty() {
+        return None;
+    }
+    Some(
+        line.split_once('=')
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .ok_or_else(|| format!("expected `key = value`, got `{line}`")),
+    )
+}
+
+impl WorkaroundConfig {
+    /// Parses the content of a config file, on top of the settings in `self`.
+    pub fn parse(mut self, content: &str) -> Result<Self, ConfigError> {
+        for (index, line) in content.lines().enumerate() {
+            let error = |message: String| ConfigError {
+                line: index + 1,
+                message,
+            };
+            let Some(key_value) = key_value(line) else {
+                continue;
+            };
+            let (key, value) = key_value.map_err(error)?;
+            let invalid = || error(format!("invalid value `{value}` for `{key}`"));
+            match key {
+                "disable-dmabuf-renderer" => {
+                    self.disable_dmabuf_renderer = parse_setting(value).ok_or_else(invalid)?
+                }
+                "disable-nv-explicit-sync" => {
+                    self.disable_nv_explicit_sync = parse_setting(value).ok_or_else(invalid)?
+                }
+                "verbose" => self.verbose = Some(value.parse().map_err(|_| invalid())?),
+                REMEMBERED_KEY => {
+                    self.remembered_workaround =
+                        match parse_workaround(value).ok_or_else(invalid)? {
+                            WorkaroundKind::None => None,
+                            kind => Some(kind),
+                        }
+                }
+                _ => return Err(error(format!("unknown key `{key}`"))),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Loads the system and user config files of the application `app_name`.
+    ///
+    /// Missing files are skipped, invalid ones are reported on stderr and
+    /// skipped as well, so a broken file never prevents the application from
+    /// starting.
+    pub fn load(app_name: &str) -> Self {
+        Self::load_files(&config_paths(app_name))
+    }
+
+    /// Loads the config files at `paths`, later files taking precedence.
+    fn load_files(paths: &[PathBuf]) -> Self {
+        paths.iter().fold(Self::default(), |config, path| {
+            let Ok(content) = std::fs::read_to_string(path) else {
+                return config;
+            };
+            config.clone().parse(&content).unwrap_or_else(|err| {
+                eprintln!(
+                    "Ignoring invalid GPU workaround config {}: {err}",
+                    path.display()
+                );
+                config
+            })
+        })
+    }
+
+    /// Returns the setting of `kind`, with a remembered workaround counting as
+    /// forced.
+    pub fn setting(&self, kind: WorkaroundKind) -> WorkaroundSetting {
+        if self.remembered_workaround == Some(kind) {
+            return WorkaroundSetting::Force;
+        }
+        match kind {
+            WorkaroundKind::None => WorkaroundSetting::Auto,
+            WorkaroundKind::DisableWebkitDmabufRenderer => self.disable_dmabuf_renderer,
+            WorkaroundKind::DisableNvExplicitSync => self.disable_nv_explicit_sync,
+        }
+    }
+}
+
+/// Returns the value of the environment variable `name`, unless it is empty.
+pub(crate) fn non_empty_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// The directory of the user config file, following the XDG base directory
+/// specification.
+fn user_config_dir() -> Option<PathBuf> {
+    non_empty_var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty_var("HOME").map(|home| Path::new(&home).join(".config")))
+}
+
+/// The config files in the order they are read: the system files from the
+/// least to the most important `$XDG_CONFIG_DIRS` entry, then the user file.
+fn config_paths(app_name: &str) -> Vec<PathBuf> {
+    let system_dirs = non_empty_var("XDG_CONFIG_DIRS").unwrap_or_else(|| "/etc/xdg".to_string());
+    config_paths_in(&system_dirs, user_config_dir().as_deref(), app_name)
+}
+
+/// The config files of `app_name` in the colon separated `system_dirs` and
+/// in `user_dir`, in the order they are read.
+fn config_paths_in(system_dirs: &str, user_dir: Option<&Path>, app_name: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = system_dirs
+        .split(':')
+        .rev()
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| Path::new(dir).join(app_name).join(CONFIG_FILE_NAME))
+        .collect();
+    paths.extend(user_dir.map(|dir| dir.join(app_name).join(CONFIG_FILE_NAME)));
+    paths
+}
+
+/// Returns the path of the user config file of the application `app_name`,
+/// where remembered workarounds are stored, or `None` if neither
+/// `XDG_CONFIG_HOME` nor `HOME` is set.
+pub fn user_config_path(app_name: &str) -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join(app_name).join(CONFIG_FILE_NAME))
+}
+
+/// Returns `content` with the remembered workaround replaced by `kind`, keeping
+/// all other lines.
+fn with_remembered_workaround(content: &str, kind: Option<WorkaroundKind>) -> String {
+    let mut lines: Vec<String> = content
+        .lines()
+        .filter(|line| !matches!(key_value(line), Some(Ok((key, _))) if key == REMEMBERED_KEY))
+        .map(str::to_string)
+        .collect();
+    if let Some(kind) = kind {
+        lines.push(format!("{REMEMBERED_KEY} = {}", workaround_name(kind)));
+    }
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Remembers the workaround that fixed a crash of the application `app_name`
+/// in its user config file, or forgets it if `kind` is `None`.
+///
+/// The remembered workaround is applied on every start until it is forgotten.
+pub fn remember_workaround(app_name: &str, kind: Option<WorkaroundKind>) -> std::io::Result<()> {
+    let path = user_config_path(app_name)
+        .ok_or_else(|| std::io::Error::other("neither XDG_CONFIG_HOME nor HOME is set"))?;
+    remember_workaround_in(&path, kind)
+}
+
+/// Remembers or forgets a workaround in the config file at `path`.
+fn remember_workaround_in(path: &Path, kind: Option<WorkaroundKind>) -> std::io::Result<()> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, with_remembered_workaround(&content, kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = WorkaroundConfig::default()
+            .parse(
+                "# overrides\n\
+                 disable-dmabuf-renderer = force\n\
+                 disable-nv-explicit-sync = suppress # not needed here\n\
+                 \n\
+                 verbose = true\n\
+                 remembered-workaround = disable-nv-explicit-sync\n",
+            )
+            .unwrap();
+        assert_eq!(
+            config,
+            WorkaroundConfig {
+                disable_dmabuf_renderer: WorkaroundSetting::Force,
+                disable_nv_explicit_sync: WorkaroundSetting::Suppress,
+                verbose: Some(true),
+                remembered_workaround: Some(WorkaroundKind::DisableNvExplicitSync),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_config_overrides_previous_settings() {
+        let system = WorkaroundConfig::default()
+            .parse("disable-dmabuf-renderer = force\nverbose = true\n")
+            .unwrap();
+        let user = system
+            .parse("disable-dmabuf-renderer = suppress\n")
+            .unwrap();
+        assert_eq!(user.disable_dmabuf_renderer, WorkaroundSetting::Suppress);
+        assert_eq!(user.verbose, Some(true));
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        let parse = |content| WorkaroundConfig::default().parse(content).unwrap_err();
+        assert_eq!(parse("verbose = true\nforce").line, 2);
+        assert!(parse("disable-dmabuf-renderer = always")
+            .message
+            .contains("invalid value"));
+        assert!(parse("disable-everything = force")
+            .message
+            .contains("unknown key"));
+    }
+
+    #[test]
+    fn test_remembered_workaround_counts_as_forced() {
+        let config = WorkaroundConfig {
+            disable_dmabuf_renderer: WorkaroundSetting::Suppress,
+            remembered_workaround: Some(WorkaroundKind::DisableWebkitDmabufRenderer),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.setting(WorkaroundKind::DisableWebkitDmabufRenderer),
+            WorkaroundSetting::Force
+        );
+        assert_eq!(
+            config.setting(WorkaroundKind::DisableNvExplicitSync),
+            WorkaroundSetting::Auto
+        );
+    }
+
+    #[test]
+    fn test_config_paths_in() {
+        assert_eq!(
+            config_paths_in(
+                "/etc/xdg:/opt/xdg::",
+                Some(Path::new("/home/me/.config")),
+                "app"
+            ),
+            vec![
+                PathBuf::from("/opt/xdg/app").join(CONFIG_FILE_NAME),
+                PathBuf::from("/etc/xdg/app").join(CONFIG_FILE_NAME),
+                PathBuf::from("/home/me/.config/app").join(CONFIG_FILE_NAME),
+            ]
+        );
+        assert_eq!(
+            config_paths_in("/etc/xdg", None, "app"),
+            vec![PathBuf::from("/etc/xdg/app").join(CONFIG_FILE_NAME)]
+        );
+    }
+
+    #[test]
+    fn test_load_and_remember_workaround() {
+        let dir = std::env::temp_dir().join(format!(
+            "webkit2gtk-nvidia-quirk-test-config-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let system_dir = dir.join("system").join("app");
+        std::fs::create_dir_all(&system_dir).unwrap();
+        std::fs::write(
+            system_dir.join(CONFIG_FILE_NAME),
+            "disable-nv-explicit-sync = suppress\n",
+        )
+        .unwrap();
+        let user_dir = dir.join("user");
+        let paths = config_paths_in(dir.join("system").to_str().unwrap(), Some(&user_dir), "app");
+        let user_path = user_dir.join("app").join(CONFIG_FILE_NAME);
+        assert_eq!(paths.last(), Some(&user_path));
+
+        remember_workaround_in(
+            &user_path,
+            Some(WorkaroundKind::DisableWebkitDmabufRenderer),
+        )
+        .unwrap();
+        let config = WorkaroundConfig::load_files(&paths);
+        assert_eq!(config.disable_nv_explicit_sync, WorkaroundSetting::Suppress);
+        assert_eq!(
+            config.remembered_workaround,
+            Some(WorkaroundKind::DisableWebkitDmabufRenderer)
+        );
+
+        remember_workaround_in(&user_path, None).unwrap();
+        assert_eq!(
+            WorkaroundConfig::load_files(&paths).remembered_workaround,
+            None
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_with_remembered_workaround_keeps_other_lines() {
+        let content =
+            "# comment\nremembered-workaround = disable-nv-explicit-sync\nverbose = true\n";
+        let remembered =
+            with_remembered_workaround(content, Some(WorkaroundKind::DisableWebkitDmabufRenderer));
+        assert_eq!(
+            remembered,
+            "# comment\nverbose = true\nremembered-workaround = disable-dmabuf-renderer\n"
+        );
+        assert_eq!(
+            with_remembered_workaround(&remembered, None),
+            "# comment\nverbose = true\n"
+        );
+    }
+}
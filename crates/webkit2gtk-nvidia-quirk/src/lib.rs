@@ -4,7 +4,8 @@
 //! # webkit2gtk-nvidia-quirk
 //!
 //! A crate that provides session-aware workarounds for WebKitGTK rendering issues
-//! on Linux systems with the proprietary NVIDIA driver.
+//! on Linux systems with the proprietary NVIDIA driver, hybrid (PRIME) laptops,
+//! virtual GPUs and old Mesa releases.
 //!
 //! ## Problem
 //!
@@ -25,15 +26,21 @@
 //!
 //! ## Solution
 //!
-//! This crate detects the proprietary NVIDIA driver and the session type (X11/Wayland),
-//! then applies the appropriate workaround:
+//! This crate detects the GPUs, their drivers and the session type (X11/Wayland),
+//! then checks a table of known issues in order and applies the workaround of the
+//! first rule that matches:
 //!
-//! | Session Type | Workaround | Environment Variable |
-//! |-------------|------------|---------------------|
-//! | X11 | Disable DMABUF renderer | `WEBKIT_DISABLE_DMABUF_RENDERER=1` |
-//! | Wayland (Hyprland) | Disable DMABUF renderer | `WEBKIT_DISABLE_DMABUF_RENDERER=1` |
-//! | Wayland (other, `egl-wayland2`) | none | - |
-//! | Wayland (other) | Disable NVIDIA explicit sync | `__NV_DISABLE_EXPLICIT_SYNC=1` |
+//! | Rule | System | Workaround | Environment Variable |
+//! |------|--------|------------|---------------------|
+//! | `nvidia-hyprland` | NVIDIA, Wayland (Hyprland) | Disable DMABUF renderer | `WEBKIT_DISABLE_DMABUF_RENDERER=1` |
+//! | `nvidia-wayland-egl-wayland2` | NVIDIA, Wayland (other, `egl-wayland2`) | none | - |
+//! | `nvidia-wayland` | NVIDIA, Wayland (other) | Disable NVIDIA explicit sync | `__NV_DISABLE_EXPLICIT_SYNC=1` |
+//! | `nvidia-x11` | NVIDIA, X11 | Disable DMABUF renderer | `WEBKIT_DISABLE_DMABUF_RENDERER=1` |
+//! | `nvidia-prime-offload` | Render offload to a secondary NVIDIA GPU | Disable DMABUF renderer | `WEBKIT_DISABLE_DMABUF_RENDERER=1` |
+//! | `virtual-gpu` | `virtio_gpu`, `qxl` or `vmwgfx` primary GPU | Disable DMABUF renderer | `WEBKIT_DISABLE_DMABUF_RENDERER=1` |
+//! | `old-mesa` | AMD/Intel primary GPU, Mesa older than 23.1 | Disable DMABUF renderer | `WEBKIT_DISABLE_DMABUF_RENDERER=1` |
+//!
+//! No workaround is applied when the session type is unknown.
 //!
 //! The session type is taken from `GDK_BACKEND` first (the backend GDK/WebKitGTK
 //! actually selects, a comma-separated list where the first recognized entry of
@@ -77,6 +84,15 @@
 //!
 //! This specifically targets the proprietary NVIDIA driver, not the open-source nouveau driver.
 //!
+//! The same enumeration also identifies the other affected systems:
+//! - Hybrid (PRIME) laptops: a secondary GPU bound to the `nvidia` driver while
+//!   `DRI_PRIME` or `__NV_PRIME_RENDER_OFFLOAD` requests render offload
+//! - Virtual machines: a primary GPU bound to `virtio_gpu`, `qxl` or `vmwgfx`
+//! - Old Mesa: a primary AMD (0x1002) or Intel (0x8086) GPU with a Mesa release
+//!   older than 23.1. Mesa's version is not exposed in sysfs, it is taken from
+//!   the versioned `libgallium-<version>.so` of Mesa 24.1+ or Mesa's
+//!   `pkgconfig/dri.pc` in the system library directories
+//!
 //! ### Sandboxed environments (Flatpak)
 //!
 //! GPU and NVIDIA-driver detection reads are scoped to `/sys/class/drm`, which is one of the
@@ -114,10 +130,17 @@
 //!
 //! ### `needs_workaround() -> WorkaroundKind`
 //!
-//! Determines which workaround should be applied based on NVIDIA detection and session type.
+//! Determines which workaround should be applied based on the detected GPUs and session type.
+//!
+//! Returns `WorkaroundKind::None` if no workaround is needed, otherwise the workaround of the
+//! first matching rule of the table above.
+//!
+//! ### `diagnose() -> GpuDiagnostics`
 //!
-//! Returns `WorkaroundKind::None` if no workaround is needed, `WorkaroundKind::DisableWebkitDmabufRenderer`
-//! for X11 sessions, or `WorkaroundKind::DisableNvExplicitSync` for Wayland sessions.
+//! Returns every input [`needs_workaround`] considers: the enumerated GPUs, the session
+//! environment and type, the compositor, the EGL external platform manifests, the NVIDIA driver
+//! major version, the Mesa version, and the matching rule with its `WorkaroundKind`. Enable the `serde` feature to serialize the
+//! report, e.g. for bug reports.
 //!
 //! ### `set_webkit_disable_dmabuf_renderer(verbose: bool)`
 //!
@@ -139,12 +162,34 @@
 //!
 //! This is the recommended way to apply workarounds from CLI arguments.
 //!
+//! ### `WorkaroundConfig`
+//!
+//! Overrides read from config files by [`WorkaroundConfig::load`]: each workaround can be forced
+//! or suppressed, and [`remember_workaround`] stores a workaround that fixed a crash, which is
+//! then applied on every start. The system file `$XDG_CONFIG_DIRS/<app>/gpu-workarounds.conf`
+//! is read before the user file `$XDG_CONFIG_HOME/<app>/gpu-workarounds.conf`:
+//!
+//! ```text
+//! disable-dmabuf-renderer = force
+//! disable-nv-explicit-sync = suppress
+//! verbose = true
+//! ```
+//!
+//! Pass it to [`ApplyWorkaroundOptions::config`]; the force options take precedence over it.
+//!
+//! ### `StartupMarker`
+//!
+//! A marker file in `$XDG_STATE_HOME/<app>` that is set before the webview is created and
+//! cleared once it rendered. A marker left behind means the previous startup crashed, e.g. due to
+//! the Hyprland protocol error or the EGL SIGSEGV; the application can then apply
+//! [`set_webkit_disable_dmabuf_renderer`] and [`remember_workaround`] it.
+//!
 //! ### `WorkaroundKind`
 //!
 //! Enum representing the type of workaround to apply:
 //! - `None`: No workaround needed
-//! - `DisableWebkitDmabufRenderer`: Disable the DMABUF renderer (for X11)
-//! - `DisableNvExplicitSync`: Disable NVIDIA explicit sync (for Wayland)
+//! - `DisableWebkitDmabufRenderer`: Disable the DMABUF renderer
+//! - `DisableNvExplicitSync`: Disable NVIDIA explicit sync (for NVIDIA on Wayland)
 //!
 //! ### `ApplyWorkaroundOptions`
 //!
@@ -167,11 +212,55 @@
 
 use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
-struct GpuDevice {
-    is_primary: bool,
-    is_nvidia: bool,
-    uses_nvidia_driver: bool,
+mod config;
+mod startup;
+
+pub use config::{
+    remember_workaround, user_config_path, ConfigError, WorkaroundConfig, WorkaroundSetting,
+};
+pub use startup::StartupMarker;
+
+/// PCI vendor ID of NVIDIA.
+const VENDOR_NVIDIA: u16 = 0x10de;
+/// PCI vendor ID of AMD.
+const VENDOR_AMD: u16 = 0x1002;
+/// PCI vendor ID of Intel.
+const VENDOR_INTEL: u16 = 0x8086;
+
+/// Kernel drivers of the virtual GPUs of common hypervisors: QEMU/KVM
+/// (`virtio_gpu`, `qxl`) and VMware (`vmwgfx`).
+const VIRTUAL_GPU_DRIVERS: &[&str] = &["virtio_gpu", "qxl", "vmwgfx"];
+
+/// A GPU found under `/sys/class/drm`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GpuDevice {
+    /// The DRM card name, e.g. `card0`.
+    pub name: String,
+    /// The PCI vendor ID, `0` if it could not be read.
+    pub vendor_id: u16,
+    /// The name of the bound kernel driver, e.g. `nvidia` or `nouveau`.
+    pub driver: Option<String>,
+    /// Whether this is the primary/boot GPU (`boot_display` or `boot_vga`).
+    pub is_primary: bool,
+    /// Whether the vendor ID is NVIDIA's (0x10de).
+    pub is_nvidia: bool,
+    /// Whether the proprietary `nvidia` driver is bound.
+    pub uses_nvidia_driver: bool,
+}
+
+impl GpuDevice {
+    /// Whether the GPU is the virtual GPU of a hypervisor.
+    fn is_virtual(&self) -> bool {
+        self.driver
+            .as_deref()
+            .is_some_and(|driver| VIRTUAL_GPU_DRIVERS.contains(&driver))
+    }
+
+    /// Whether the GPU is an AMD or Intel GPU, which are driven by Mesa.
+    fn is_mesa(&self) -> bool {
+        self.vendor_id == VENDOR_AMD || self.vendor_id == VENDOR_INTEL
+    }
 }
 
 fn read_sysfs_file(path: &Path) -> Option<String> {
@@ -232,10 +321,14 @@ fn enumerate_gpus_at(drm_path: &Path) -> Vec<GpuDevice> {
             || is_sysfs_attr_one(&card_path.join("device"), "boot_display")
             || is_sysfs_attr_one(&card_path.join("device"), "boot_vga");
 
-        let is_nvidia = vendor_id == 0x10de;
-        let uses_nvidia_driver = driver_name(&card_path).as_deref() == Some("nvidia");
+        let is_nvidia = vendor_id == VENDOR_NVIDIA;
+        let driver = driver_name(&card_path);
+        let uses_nvidia_driver = driver.as_deref() == Some("nvidia");
 
         devices.push(GpuDevice {
+            name: sysname.to_string(),
+            vendor_id,
+            driver,
             is_primary,
             is_nvidia,
             uses_nvidia_driver,
@@ -262,7 +355,9 @@ fn nvidia_driver_loaded(devices: &[GpuDevice]) -> bool {
 
 /// The NVIDIA Wayland EGL platform library referenced by an external platform manifest.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum WaylandLib {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum WaylandLib {
     /// The EGLStreams based `egl-wayland` library.
     EglWayland,
     /// The dma-buf based `egl-wayland2` library.
@@ -382,15 +477,87 @@ fn is_egl_wayland2_selected(configs: &[PathBuf], driver_major: Option<u64>) -> b
         && driver_major.is_some_and(|major| major >= 560)
 }
 
-/// Reads the current system state and returns whether `egl-wayland2` is in use.
-fn egl_wayland2_active() -> bool {
-    let configs = egl_external_platform_config_files();
-    let driver_major = nvidia_driver_major();
-    is_egl_wayland2_selected(&configs, driver_major)
+/// The version of the installed Mesa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MesaVersion {
+    pub major: u64,
+    pub minor: u64,
+}
+
+/// Mesa releases older than this are known to render blank or corrupted
+/// windows with WebKitGTK's DMA-BUF renderer.
+const MIN_MESA_VERSION: MesaVersion = MesaVersion {
+    major: 23,
+    minor: 1,
+};
+
+/// Parses the major and minor version of a dotted version string, e.g.
+/// `24.2` from `24.2.8`.
+fn parse_mesa_version(version: &str) -> Option<MesaVersion> {
+    let mut parts = version.trim().split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.split(|c: char| !c.is_ascii_digit()).next()?;
+    Some(MesaVersion {
+        major,
+        minor: minor.parse().ok()?,
+    })
+}
+
+/// Extracts the Mesa version from the name of the versioned gallium library
+/// Mesa 24.1 and newer install, e.g. `libgallium-24.2.8.so`.
+fn mesa_version_from_gallium_lib(file_name: &str) -> Option<MesaVersion> {
+    let version = file_name.strip_prefix("libgallium-")?.strip_suffix(".so")?;
+    parse_mesa_version(version)
+}
+
+/// Extracts the Mesa version from the `Version:` field of Mesa's `dri.pc`
+/// pkg-config file, which older Mesa releases can only be identified by.
+fn mesa_version_from_pkg_config(content: &str) -> Option<MesaVersion> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("Version:"))
+        .and_then(parse_mesa_version)
+}
+
+/// Detects the Mesa version from the libraries and pkg-config files in
+/// `lib_dirs`.
+///
+/// Mesa does not expose its version anywhere in sysfs, so this looks for the
+/// versioned `libgallium-<version>.so` first and Mesa's `pkgconfig/dri.pc`
+/// second. Inside a Flatpak sandbox the runtime's Mesa is found, which is the
+/// one the application actually uses.
+fn mesa_version_in(lib_dirs: &[PathBuf]) -> Option<MesaVersion> {
+    let gallium = lib_dirs.iter().find_map(|dir| {
+        std::fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .find_map(|entry| mesa_version_from_gallium_lib(entry.file_name().to_str()?))
+    });
+    gallium.or_else(|| {
+        lib_dirs.iter().find_map(|dir| {
+            let content = read_sysfs_file(&dir.join("pkgconfig/dri.pc"))?;
+            mesa_version_from_pkg_config(&content)
+        })
+    })
+}
+
+/// Reads the Mesa version from the system library directories.
+fn mesa_version() -> Option<MesaVersion> {
+    let arch = std::env::consts::ARCH;
+    mesa_version_in(&[
+        PathBuf::from(format!("/usr/lib/{arch}-linux-gnu")),
+        PathBuf::from("/usr/lib64"),
+        PathBuf::from("/usr/lib"),
+        PathBuf::from("/usr/share"),
+    ])
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum SessionType {
+/// The windowing system session the application runs in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum SessionType {
     Wayland,
     X11,
     Unknown,
@@ -600,18 +767,20 @@ fn is_hyprland(compositor: Option<&str>) -> bool {
         .unwrap_or(false)
 }
 
-/// Represents the type of workaround to apply for NVIDIA WebKitGTK issues.
+/// Represents the type of workaround to apply for WebKitGTK rendering issues.
 ///
 /// Use this enum to determine which workaround is needed based on the session type
-/// and whether NVIDIA is detected.
+/// and the detected GPUs.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum WorkaroundKind {
     /// No workaround needed.
     None,
     /// Disable the WebKit DMABUF renderer.
     ///
     /// This workaround is needed for X11 sessions and for Hyprland Wayland
-    /// sessions with NVIDIA drivers.
+    /// sessions with NVIDIA drivers, for PRIME render offload to a secondary
+    /// NVIDIA GPU, for virtual GPUs and for old Mesa releases.
     DisableWebkitDmabufRenderer,
     /// Disable NVIDIA explicit sync.
     ///
@@ -620,8 +789,17 @@ pub enum WorkaroundKind {
     DisableNvExplicitSync,
 }
 
-/// Selects the workaround for a detected session, GPU, compositor, and EGL
-/// library state.
+/// A known WebKitGTK rendering issue and the workaround for it.
+struct QuirkRule {
+    /// Identifies the rule in the [`GpuDiagnostics`].
+    name: &'static str,
+    /// Whether the issue affects the detected system.
+    applies: fn(&GpuDiagnostics) -> bool,
+    workaround: WorkaroundKind,
+}
+
+/// The known issues, in the order they are checked; the first rule that
+/// applies selects the workaround.
 ///
 /// On Hyprland the DMA-BUF renderer is disabled entirely, since it both
 /// violates the compositor's acquire-point rule (a protocol error that kills
@@ -630,28 +808,73 @@ pub enum WorkaroundKind {
 /// based `egl-wayland2` library is in use (NVIDIA driver 560+), since disabling
 /// explicit sync would degrade rendering performance; otherwise explicit sync is
 /// disabled.
-fn workaround_for(
-    session: SessionType,
-    nvidia_detected: bool,
-    hyprland: bool,
-    egl_wayland2: bool,
-) -> WorkaroundKind {
-    if !nvidia_detected {
-        return WorkaroundKind::None;
-    }
-    match session {
-        SessionType::Wayland if hyprland => WorkaroundKind::DisableWebkitDmabufRenderer,
-        SessionType::Wayland if egl_wayland2 => WorkaroundKind::None,
-        SessionType::Wayland => WorkaroundKind::DisableNvExplicitSync,
-        SessionType::X11 => WorkaroundKind::DisableWebkitDmabufRenderer,
-        SessionType::Unknown => WorkaroundKind::None,
-    }
+///
+/// When rendering is offloaded to a secondary NVIDIA GPU on a hybrid (PRIME)
+/// laptop, the DMA-BUF buffers are shared between two GPUs, which leaves the
+/// window blank. Virtual GPUs and Mesa releases older than
+/// [`MIN_MESA_VERSION`] lack the buffer sharing the DMA-BUF renderer relies on.
+/// In sessions of unknown type no workaround is applied.
+const RULES: &[QuirkRule] = &[
+    QuirkRule {
+        name: "nvidia-hyprland",
+        applies: |system| {
+            system.nvidia_detected && system.session_type == SessionType::Wayland && system.hyprland
+        },
+        workaround: WorkaroundKind::DisableWebkitDmabufRenderer,
+    },
+    QuirkRule {
+        name: "nvidia-wayland-egl-wayland2",
+        applies: |system| {
+            system.nvidia_detected
+                && system.session_type == SessionType::Wayland
+                && system.egl_wayland2_active
+        },
+        workaround: WorkaroundKind::None,
+    },
+    QuirkRule {
+        name: "nvidia-wayland",
+        applies: |system| system.nvidia_detected && system.session_type == SessionType::Wayland,
+        workaround: WorkaroundKind::DisableNvExplicitSync,
+    },
+    QuirkRule {
+        name: "nvidia-x11",
+        applies: |system| system.nvidia_detected && system.session_type == SessionType::X11,
+        workaround: WorkaroundKind::DisableWebkitDmabufRenderer,
+    },
+    QuirkRule {
+        name: "nvidia-prime-offload",
+        applies: |system| system.has_session() && system.prime_offload_to_nvidia(),
+        workaround: WorkaroundKind::DisableWebkitDmabufRenderer,
+    },
+    QuirkRule {
+        name: "virtual-gpu",
+        applies: |system| {
+            system.has_session() && system.primary_gpu().is_some_and(GpuDevice::is_virtual)
+        },
+        workaround: WorkaroundKind::DisableWebkitDmabufRenderer,
+    },
+    QuirkRule {
+        name: "old-mesa",
+        applies: |system| {
+            system.has_session()
+                && system.primary_gpu().is_some_and(GpuDevice::is_mesa)
+                && system
+                    .mesa_version
+                    .is_some_and(|version| version < MIN_MESA_VERSION)
+        },
+        workaround: WorkaroundKind::DisableWebkitDmabufRenderer,
+    },
+];
+
+/// Returns the first rule of [`RULES`] that applies to `system`.
+fn select_rule(system: &GpuDiagnostics) -> Option<&'static QuirkRule> {
+    RULES.iter().find(|rule| (rule.applies)(system))
 }
 
 /// Checks if a workaround should be applied.
 ///
-/// This function checks if the proprietary NVIDIA driver is loaded and the primary GPU is NVIDIA.
-/// If so, it detects the session type (X11 or Wayland) and returns which workaround should be applied.
+/// This function detects the GPUs, their drivers and the session type (X11 or Wayland), and
+/// returns the workaround of the first rule in the table of known issues that applies.
 ///
 /// # Returns
 ///
@@ -664,15 +887,187 @@ fn workaround_for(
 /// This function only performs detection. Use [`set_webkit_disable_dmabuf_renderer`] or
 /// [`nv_disable_explicit_sync`] to apply the respective workaround.
 /// Call this first, then call the workaround if needed - ideally before spawning any threads.
+///
+/// Use [`diagnose`] to get the findings the decision is based on.
 pub fn needs_workaround() -> WorkaroundKind {
-    let devices = enumerate_gpus();
-    let nvidia_detected =
-        devices.iter().any(|d| d.is_primary && d.is_nvidia) && nvidia_driver_loaded(&devices);
-    workaround_for(
-        get_session_type(),
-        nvidia_detected,
-        is_hyprland(get_compositor().as_deref()),
-        egl_wayland2_active(),
+    diagnose().workaround
+}
+
+/// The environment variables the session type, the compositor and PRIME render
+/// offload are detected from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SessionEnvironment {
+    pub gdk_backend: Option<String>,
+    pub xdg_session_type: Option<String>,
+    pub wayland_display: Option<String>,
+    pub display: Option<String>,
+    pub xdg_current_desktop: Option<String>,
+    pub xdg_session_desktop: Option<String>,
+    /// Mesa's GPU selection for PRIME render offload.
+    pub dri_prime: Option<String>,
+    /// NVIDIA's switch for PRIME render offload.
+    pub nv_prime_render_offload: Option<String>,
+}
+
+impl SessionEnvironment {
+    fn from_env() -> Self {
+        let var = |name| std::env::var(name).ok();
+        Self {
+            gdk_backend: var("GDK_BACKEND"),
+            xdg_session_type: var("XDG_SESSION_TYPE"),
+            wayland_display: var("WAYLAND_DISPLAY"),
+            display: var("DISPLAY"),
+            xdg_current_desktop: var("XDG_CURRENT_DESKTOP"),
+            xdg_session_desktop: var("XDG_SESSION_DESKTOP"),
+            dri_prime: var("DRI_PRIME"),
+            nv_prime_render_offload: var("__NV_PRIME_RENDER_OFFLOAD"),
+        }
+    }
+
+    /// Whether rendering is offloaded to a secondary GPU, via `DRI_PRIME` or
+    /// `__NV_PRIME_RENDER_OFFLOAD`.
+    fn prime_offload_requested(&self) -> bool {
+        let enabled = |value: &Option<String>| {
+            value
+                .as_deref()
+                .is_some_and(|value| !value.is_empty() && value != "0")
+        };
+        enabled(&self.dri_prime) || enabled(&self.nv_prime_render_offload)
+    }
+
+    fn session_type(&self) -> SessionType {
+        session_type_from_env(
+            self.gdk_backend.as_deref(),
+            self.xdg_session_type.as_deref(),
+            self.wayland_display.as_deref(),
+            self.display.as_deref(),
+        )
+    }
+}
+
+/// An EGL external platform JSON manifest, in the order the EGL loader tries them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EglPlatformManifest {
+    pub path: PathBuf,
+    /// The `library_path` of the manifest, `None` if it could not be read.
+    pub library_path: Option<String>,
+    /// The NVIDIA Wayland library the manifest references, if any.
+    pub wayland_lib: Option<WaylandLib>,
+}
+
+impl EglPlatformManifest {
+    fn read(path: &Path) -> Self {
+        let library_path = read_sysfs_file(path)
+            .and_then(|content| library_path_from_manifest(&content).map(str::to_string));
+        let wayland_lib = library_path.as_deref().and_then(classify_wayland_lib);
+        Self {
+            path: path.to_path_buf(),
+            library_path,
+            wayland_lib,
+        }
+    }
+}
+
+/// Every input [`needs_workaround`] considers, along with the workaround it selects.
+///
+/// Returned by [`diagnose`]. With the `serde` feature enabled the report can be serialized, e.g.
+/// to be pasted into a bug report.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GpuDiagnostics {
+    /// The GPUs found under `/sys/class/drm`.
+    pub gpus: Vec<GpuDevice>,
+    /// Whether the primary GPU is NVIDIA and the proprietary driver is loaded.
+    pub nvidia_detected: bool,
+    pub environment: SessionEnvironment,
+    pub session_type: SessionType,
+    /// The compositor, from the environment or Hyprland's socket.
+    pub compositor: Option<String>,
+    pub hyprland: bool,
+    pub egl_platform_manifests: Vec<EglPlatformManifest>,
+    /// The major version from `/proc/driver/nvidia/version`.
+    pub nvidia_driver_major: Option<u64>,
+    pub egl_wayland2_active: bool,
+    pub mesa_version: Option<MesaVersion>,
+    /// The name of the rule that selected the workaround, if any applies.
+    pub rule: Option<&'static str>,
+    pub workaround: WorkaroundKind,
+}
+
+impl GpuDiagnostics {
+    fn new(
+        gpus: Vec<GpuDevice>,
+        environment: SessionEnvironment,
+        compositor: Option<String>,
+        egl_configs: &[PathBuf],
+        nvidia_driver_major: Option<u64>,
+        mesa_version: Option<MesaVersion>,
+    ) -> Self {
+        let nvidia_detected =
+            gpus.iter().any(|d| d.is_primary && d.is_nvidia) && nvidia_driver_loaded(&gpus);
+        let session_type = environment.session_type();
+        let hyprland = is_hyprland(compositor.as_deref());
+        let egl_wayland2_active = is_egl_wayland2_selected(egl_configs, nvidia_driver_major);
+        Self {
+            gpus,
+            nvidia_detected,
+            environment,
+            session_type,
+            compositor,
+            hyprland,
+            egl_platform_manifests: egl_configs
+                .iter()
+                .map(|path| EglPlatformManifest::read(path))
+                .collect(),
+            nvidia_driver_major,
+            egl_wayland2_active,
+            mesa_version,
+            rule: None,
+            workaround: WorkaroundKind::None,
+        }
+        .with_selected_rule()
+    }
+
+    /// Records the rule that applies to the system and its workaround.
+    fn with_selected_rule(mut self) -> Self {
+        let rule = select_rule(&self);
+        self.rule = rule.map(|rule| rule.name);
+        self.workaround = rule.map_or(WorkaroundKind::None, |rule| rule.workaround);
+        self
+    }
+
+    fn has_session(&self) -> bool {
+        self.session_type != SessionType::Unknown
+    }
+
+    fn primary_gpu(&self) -> Option<&GpuDevice> {
+        self.gpus.iter().find(|gpu| gpu.is_primary)
+    }
+
+    /// Whether rendering is offloaded to a secondary GPU driven by the
+    /// proprietary NVIDIA driver, as on hybrid (PRIME) laptops.
+    fn prime_offload_to_nvidia(&self) -> bool {
+        self.environment.prime_offload_requested()
+            && self
+                .gpus
+                .iter()
+                .any(|gpu| !gpu.is_primary && gpu.uses_nvidia_driver)
+    }
+}
+
+/// Collects the findings [`needs_workaround`] bases its decision on.
+///
+/// Only performs detection, nothing is applied.
+pub fn diagnose() -> GpuDiagnostics {
+    GpuDiagnostics::new(
+        enumerate_gpus(),
+        SessionEnvironment::from_env(),
+        get_compositor(),
+        &egl_external_platform_config_files(),
+        nvidia_driver_major(),
+        mesa_version(),
     )
 }
 
@@ -767,6 +1162,10 @@ pub struct ApplyWorkaroundOptions {
     /// Off by default. Can also be enabled with the
     /// `WEBKIT2GTK_NVIDIA_QUIRK_VERBOSE` environment variable.
     pub verbose: bool,
+    /// Overrides from the config files, see [`WorkaroundConfig::load`].
+    ///
+    /// The force options take precedence over the config.
+    pub config: WorkaroundConfig,
 }
 
 impl ApplyWorkaroundOptions {
@@ -796,12 +1195,60 @@ impl ApplyWorkaroundOptions {
         self.verbose = value;
         self
     }
+
+    /// Sets the `config` option.
+    ///
+    /// Workarounds the config forces or remembers are applied like the force
+    /// options, detected workarounds it suppresses are not applied.
+    pub fn config(mut self, value: WorkaroundConfig) -> Self {
+        self.config = value;
+        self
+    }
+
+    fn forces(&self, kind: WorkaroundKind) -> bool {
+        let forced_by_option = match kind {
+            WorkaroundKind::None => false,
+            WorkaroundKind::DisableWebkitDmabufRenderer => self.force_disable_dmabuf,
+            WorkaroundKind::DisableNvExplicitSync => self.force_disable_nv_explicit_sync,
+        };
+        forced_by_option || self.config.setting(kind) == WorkaroundSetting::Force
+    }
+
+    /// Returns the workarounds to apply: the forced ones, or otherwise the one
+    /// `detect` returns, unless the config suppresses it.
+    fn workarounds(&self, detect: impl FnOnce() -> WorkaroundKind) -> Vec<WorkaroundKind> {
+        let forced: Vec<WorkaroundKind> = [
+            WorkaroundKind::DisableWebkitDmabufRenderer,
+            WorkaroundKind::DisableNvExplicitSync,
+        ]
+        .into_iter()
+        .filter(|kind| self.forces(*kind))
+        .collect();
+        if !forced.is_empty() {
+            return forced;
+        }
+        match detect() {
+            WorkaroundKind::None => Vec::new(),
+            kind if self.config.setting(kind) == WorkaroundSetting::Suppress => {
+                if should_print(self.is_verbose()) {
+                    eprintln!("Note: {kind:?} is suppressed by the GPU workaround config.");
+                }
+                Vec::new()
+            }
+            kind => vec![kind],
+        }
+    }
+
+    fn is_verbose(&self) -> bool {
+        self.verbose || self.config.verbose.unwrap_or(false)
+    }
 }
 
 /// Applies workarounds based on the provided options.
 ///
-/// If any force options are set in `options`, those workarounds are applied directly.
-/// Otherwise, it calls [`needs_workaround`] to detect which workaround is needed.
+/// If any force options are set in `options`, or its config forces or remembers a workaround,
+/// those workarounds are applied directly. Otherwise, it calls [`needs_workaround`] to detect
+/// which workaround is needed, and applies it unless the config suppresses it.
 ///
 /// # Arguments
 ///
@@ -812,19 +1259,14 @@ impl ApplyWorkaroundOptions {
 /// This function modifies the process environment. Call it early in your
 /// application's startup, before any threading has begun.
 pub fn apply_workaround_with_options(options: ApplyWorkaroundOptions) {
-    if options.force_disable_dmabuf {
-        set_webkit_disable_dmabuf_renderer(options.verbose);
-    }
-    if options.force_disable_nv_explicit_sync {
-        nv_disable_explicit_sync(options.verbose);
-    }
-    if !options.force_disable_dmabuf && !options.force_disable_nv_explicit_sync {
-        match needs_workaround() {
+    let verbose = options.is_verbose();
+    for kind in options.workarounds(needs_workaround) {
+        match kind {
             WorkaroundKind::None => {}
             WorkaroundKind::DisableWebkitDmabufRenderer => {
-                set_webkit_disable_dmabuf_renderer(options.verbose)
+                set_webkit_disable_dmabuf_renderer(verbose)
             }
-            WorkaroundKind::DisableNvExplicitSync => nv_disable_explicit_sync(options.verbose),
+            WorkaroundKind::DisableNvExplicitSync => nv_disable_explicit_sync(verbose),
         }
     }
 }
@@ -886,6 +1328,51 @@ mod tests {
         Ok(card_path)
     }
 
+    /// A detected system with the given session and GPUs, and nothing else.
+    fn system(session_type: SessionType, gpus: Vec<GpuDevice>) -> GpuDiagnostics {
+        GpuDiagnostics {
+            nvidia_detected: gpus.iter().any(|d| d.is_primary && d.is_nvidia)
+                && nvidia_driver_loaded(&gpus),
+            gpus,
+            environment: SessionEnvironment::default(),
+            session_type,
+            compositor: None,
+            hyprland: false,
+            egl_platform_manifests: Vec::new(),
+            nvidia_driver_major: None,
+            egl_wayland2_active: false,
+            mesa_version: None,
+            rule: None,
+            workaround: WorkaroundKind::None,
+        }
+    }
+
+    /// Selects the workaround for a session whose primary GPU is driven by the
+    /// proprietary NVIDIA driver if `nvidia_detected`, without any other GPU.
+    fn workaround_for(
+        session: SessionType,
+        nvidia_detected: bool,
+        hyprland: bool,
+        egl_wayland2: bool,
+    ) -> WorkaroundKind {
+        let gpus = if nvidia_detected {
+            vec![GpuDevice {
+                name: "card0".to_string(),
+                vendor_id: VENDOR_NVIDIA,
+                driver: Some("nvidia".to_string()),
+                is_primary: true,
+                is_nvidia: true,
+                uses_nvidia_driver: true,
+            }]
+        } else {
+            Vec::new()
+        };
+        let mut system = system(session, gpus);
+        system.hyprland = hyprland;
+        system.egl_wayland2_active = egl_wayland2;
+        system.with_selected_rule().workaround
+    }
+
     mod egl_external_platform {
         use super::*;
 
@@ -1317,6 +1804,341 @@ mod tests {
         }
     }
 
+    mod diagnostics {
+        use super::*;
+
+        fn wayland_environment(desktop: &str) -> SessionEnvironment {
+            SessionEnvironment {
+                xdg_session_type: Some("wayland".to_string()),
+                wayland_display: Some("wayland-0".to_string()),
+                xdg_current_desktop: Some(desktop.to_string()),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn test_diagnostics_report_inputs_and_workaround() -> std::io::Result<()> {
+            let drm = temp_dir("diagnostics_drm");
+            write_fake_card(&drm, "card0", Some("0x10de"), Some("nvidia"), true)?;
+            let egl = temp_dir("diagnostics_egl");
+            let configs = vec![write_manifest(
+                &egl,
+                "09_nvidia_wayland2.json",
+                "libnvidia-egl-wayland2.so.1",
+            )?];
+
+            let report = GpuDiagnostics::new(
+                enumerate_gpus_at(&drm),
+                wayland_environment("niri"),
+                Some("niri".to_string()),
+                &configs,
+                Some(610),
+                None,
+            );
+            assert_eq!(
+                report.gpus,
+                vec![GpuDevice {
+                    name: "card0".to_string(),
+                    vendor_id: 0x10de,
+                    driver: Some("nvidia".to_string()),
+                    is_primary: true,
+                    is_nvidia: true,
+                    uses_nvidia_driver: true,
+                }]
+            );
+            assert!(report.nvidia_detected);
+            assert_eq!(report.session_type, SessionType::Wayland);
+            assert!(!report.hyprland);
+            assert_eq!(
+                report.egl_platform_manifests,
+                vec![EglPlatformManifest {
+                    path: configs[0].clone(),
+                    library_path: Some("libnvidia-egl-wayland2.so.1".to_string()),
+                    wayland_lib: Some(WaylandLib::EglWayland2),
+                }]
+            );
+            assert!(report.egl_wayland2_active);
+            assert_eq!(report.rule, Some("nvidia-wayland-egl-wayland2"));
+            assert_eq!(report.workaround, WorkaroundKind::None);
+            Ok(())
+        }
+
+        #[test]
+        fn test_diagnostics_match_workaround_selection() -> std::io::Result<()> {
+            let drm = temp_dir("diagnostics_hyprland");
+            write_fake_card(&drm, "card0", Some("0x10de"), Some("nvidia"), true)?;
+
+            let report = GpuDiagnostics::new(
+                enumerate_gpus_at(&drm),
+                wayland_environment("Hyprland"),
+                Some("Hyprland".to_string()),
+                &[],
+                None,
+                None,
+            );
+            assert!(report.hyprland);
+            assert_eq!(report.rule, Some("nvidia-hyprland"));
+            assert!(report.egl_platform_manifests.is_empty());
+            assert_eq!(
+                report.workaround,
+                workaround_for(SessionType::Wayland, true, true, false)
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_diagnostics_unreadable_manifest() {
+            let manifest = EglPlatformManifest::read(Path::new("/nonexistent/10_nvidia.json"));
+            assert_eq!(manifest.library_path, None);
+            assert_eq!(manifest.wayland_lib, None);
+        }
+    }
+
+    mod quirk_rules {
+        use super::*;
+
+        /// A hybrid laptop with an Intel primary GPU and a secondary NVIDIA GPU
+        /// bound to `driver`.
+        fn hybrid_gpus(name: &str, driver: &str) -> std::io::Result<Vec<GpuDevice>> {
+            let dir = temp_dir(name);
+            write_fake_card(&dir, "card0", Some("0x8086"), Some("i915"), true)?;
+            write_fake_card(&dir, "card1", Some("0x10de"), Some(driver), false)?;
+            Ok(enumerate_gpus_at(&dir))
+        }
+
+        #[test]
+        fn test_prime_offload_to_nvidia_disables_dmabuf_renderer() -> std::io::Result<()> {
+            let mut system = system(SessionType::Wayland, hybrid_gpus("prime", "nvidia")?);
+            assert!(!system.nvidia_detected);
+            system.environment.dri_prime = Some("1".to_string());
+            let system = system.with_selected_rule();
+            assert_eq!(system.rule, Some("nvidia-prime-offload"));
+            assert_eq!(
+                system.workaround,
+                WorkaroundKind::DisableWebkitDmabufRenderer
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_nv_prime_render_offload_disables_dmabuf_renderer() -> std::io::Result<()> {
+            let mut system = system(SessionType::X11, hybrid_gpus("nv_prime", "nvidia")?);
+            system.environment.nv_prime_render_offload = Some("1".to_string());
+            assert_eq!(
+                system.with_selected_rule().workaround,
+                WorkaroundKind::DisableWebkitDmabufRenderer
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_hybrid_without_offload_is_noop() -> std::io::Result<()> {
+            let mut system = system(SessionType::Wayland, hybrid_gpus("no_prime", "nvidia")?);
+            system.environment.dri_prime = Some("0".to_string());
+            let system = system.with_selected_rule();
+            assert_eq!(system.rule, None);
+            assert_eq!(system.workaround, WorkaroundKind::None);
+            Ok(())
+        }
+
+        #[test]
+        fn test_prime_offload_to_nouveau_is_noop() -> std::io::Result<()> {
+            let mut system = system(
+                SessionType::Wayland,
+                hybrid_gpus("nouveau_prime", "nouveau")?,
+            );
+            system.environment.dri_prime = Some("1".to_string());
+            assert_eq!(system.with_selected_rule().workaround, WorkaroundKind::None);
+            Ok(())
+        }
+
+        #[test]
+        fn test_virtual_gpus_disable_dmabuf_renderer() -> std::io::Result<()> {
+            for (vendor, driver) in [
+                ("0x1af4", "virtio_gpu"),
+                ("0x1b36", "qxl"),
+                ("0x15ad", "vmwgfx"),
+            ] {
+                let dir = temp_dir(&format!("virtual_{driver}"));
+                write_fake_card(&dir, "card0", Some(vendor), Some(driver), true)?;
+                let system = system(SessionType::Wayland, enumerate_gpus_at(&dir));
+                let system = system.with_selected_rule();
+                assert_eq!(system.rule, Some("virtual-gpu"), "{driver}");
+                assert_eq!(
+                    system.workaround,
+                    WorkaroundKind::DisableWebkitDmabufRenderer
+                );
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn test_virtual_gpu_in_unknown_session_is_noop() -> std::io::Result<()> {
+            let dir = temp_dir("virtual_unknown_session");
+            write_fake_card(&dir, "card0", Some("0x1af4"), Some("virtio_gpu"), true)?;
+            let system = system(SessionType::Unknown, enumerate_gpus_at(&dir));
+            assert_eq!(system.with_selected_rule().workaround, WorkaroundKind::None);
+            Ok(())
+        }
+
+        #[test]
+        fn test_old_mesa_disables_dmabuf_renderer() -> std::io::Result<()> {
+            let dir = temp_dir("old_mesa");
+            write_fake_card(&dir, "card0", Some("0x1002"), Some("amdgpu"), true)?;
+            let mut system = system(SessionType::X11, enumerate_gpus_at(&dir));
+
+            system.mesa_version = Some(MesaVersion {
+                major: 22,
+                minor: 3,
+            });
+            let old = system.clone().with_selected_rule();
+            assert_eq!(old.rule, Some("old-mesa"));
+            assert_eq!(old.workaround, WorkaroundKind::DisableWebkitDmabufRenderer);
+
+            system.mesa_version = Some(MesaVersion {
+                major: 24,
+                minor: 2,
+            });
+            assert_eq!(
+                system.clone().with_selected_rule().workaround,
+                WorkaroundKind::None
+            );
+
+            system.mesa_version = None;
+            assert_eq!(system.with_selected_rule().workaround, WorkaroundKind::None);
+            Ok(())
+        }
+
+        #[test]
+        fn test_nvidia_rules_take_precedence() -> std::io::Result<()> {
+            let dir = temp_dir("nvidia_precedence");
+            write_fake_card(&dir, "card0", Some("0x10de"), Some("nvidia"), true)?;
+            let mut system = system(SessionType::Wayland, enumerate_gpus_at(&dir));
+            system.mesa_version = Some(MesaVersion {
+                major: 22,
+                minor: 3,
+            });
+            let system = system.with_selected_rule();
+            assert_eq!(system.rule, Some("nvidia-wayland"));
+            assert_eq!(system.workaround, WorkaroundKind::DisableNvExplicitSync);
+            Ok(())
+        }
+
+        #[test]
+        fn test_parses_mesa_version() {
+            assert_eq!(
+                mesa_version_from_gallium_lib("libgallium-24.2.8.so"),
+                Some(MesaVersion {
+                    major: 24,
+                    minor: 2
+                })
+            );
+            assert_eq!(
+                mesa_version_from_gallium_lib("libgallium-25.0.0-devel.so"),
+                Some(MesaVersion {
+                    major: 25,
+                    minor: 0
+                })
+            );
+            assert_eq!(mesa_version_from_gallium_lib("libgallium_dri.so"), None);
+            assert_eq!(
+                mesa_version_from_pkg_config("prefix=/usr\nName: dri\nVersion: 22.3.6\n"),
+                Some(MesaVersion {
+                    major: 22,
+                    minor: 3
+                })
+            );
+            assert_eq!(mesa_version_from_pkg_config("Name: dri\n"), None);
+        }
+
+        #[test]
+        fn test_mesa_version_in_prefers_gallium_lib() -> std::io::Result<()> {
+            let pkg_config_only = temp_dir("mesa_pkg_config");
+            std::fs::create_dir_all(pkg_config_only.join("pkgconfig"))?;
+            std::fs::write(
+                pkg_config_only.join("pkgconfig/dri.pc"),
+                "Name: dri\nVersion: 22.3.6\n",
+            )?;
+            let gallium = temp_dir("mesa_gallium");
+            std::fs::write(gallium.join("libgallium-24.2.8.so"), "")?;
+
+            assert_eq!(
+                mesa_version_in(&[pkg_config_only.clone(), gallium]),
+                Some(MesaVersion {
+                    major: 24,
+                    minor: 2
+                })
+            );
+            assert_eq!(
+                mesa_version_in(&[pkg_config_only]),
+                Some(MesaVersion {
+                    major: 22,
+                    minor: 3
+                })
+            );
+            assert_eq!(mesa_version_in(&[temp_dir("mesa_none")]), None);
+            Ok(())
+        }
+    }
+
+    mod apply_options {
+        use super::*;
+
+        const DMABUF: WorkaroundKind = WorkaroundKind::DisableWebkitDmabufRenderer;
+        const NV_SYNC: WorkaroundKind = WorkaroundKind::DisableNvExplicitSync;
+
+        #[test]
+        fn test_detected_workaround_is_applied() {
+            let options = ApplyWorkaroundOptions::default();
+            assert_eq!(options.workarounds(|| NV_SYNC), vec![NV_SYNC]);
+            assert!(options.workarounds(|| WorkaroundKind::None).is_empty());
+        }
+
+        #[test]
+        fn test_forced_workarounds_skip_detection() {
+            let options = ApplyWorkaroundOptions::default()
+                .force_disable_dmabuf(true)
+                .config(WorkaroundConfig {
+                    disable_nv_explicit_sync: WorkaroundSetting::Force,
+                    ..Default::default()
+                });
+            assert_eq!(
+                options.workarounds(|| panic!("detection must be skipped")),
+                vec![DMABUF, NV_SYNC]
+            );
+        }
+
+        #[test]
+        fn test_remembered_workaround_is_applied() {
+            let options = ApplyWorkaroundOptions::default().config(WorkaroundConfig {
+                remembered_workaround: Some(DMABUF),
+                ..Default::default()
+            });
+            assert_eq!(options.workarounds(|| NV_SYNC), vec![DMABUF]);
+        }
+
+        #[test]
+        fn test_suppressed_workaround_is_not_applied() {
+            let options = ApplyWorkaroundOptions::default().config(WorkaroundConfig {
+                disable_nv_explicit_sync: WorkaroundSetting::Suppress,
+                ..Default::default()
+            });
+            assert!(options.workarounds(|| NV_SYNC).is_empty());
+            assert_eq!(options.workarounds(|| DMABUF), vec![DMABUF]);
+        }
+
+        #[test]
+        fn test_force_option_overrides_suppressing_config() {
+            let options = ApplyWorkaroundOptions::default()
+                .force_disable_dmabuf(true)
+                .config(WorkaroundConfig {
+                    disable_dmabuf_renderer: WorkaroundSetting::Suppress,
+                    ..Default::default()
+                });
+            assert_eq!(options.workarounds(|| NV_SYNC), vec![DMABUF]);
+        }
+    }
+
     /// Integration test: exercises the full GPU-detection pipeline
     /// (`enumerate_gpus_at` + `nvidia_driver_loaded`) against a fake sysfs
     /// tree that mimics what is actually visible inside a Flatpak sandbox,
@@ -0,0 +1,82 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+//! Detection of startups that did not complete, which is how the webview
+//! crashes of the DMA-BUF renderer show: the application just vanishes.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::non_empty_var;
+
+const MARKER_FILE_NAME: &str = "startup-in-progress";
+
+/// A file that exists while the application starts, so a startup that crashed
+/// is noticed on the next launch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartupMarker {
+    path: PathBuf,
+}
+
+impl StartupMarker {
+    /// The marker of the application `app_name`, in `$XDG_STATE_HOME/<app>`
+    /// (by default in `~/.local/state`).
+    ///
+    /// Returns `None` if neither `XDG_STATE_HOME` nor `HOME` is set.
+    pub fn new(app_name: &str) -> Option<Self> {
+        let state_dir = non_empty_var("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| non_empty_var("HOME").map(|home| Path::new(&home).join(".local/state")))?;
+        Some(Self::at(state_dir.join(app_name).join(MARKER_FILE_NAME)))
+    }
+
+    fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Whether the marker of a previous startup was left behind, i.e. that
+    /// startup did not complete.
+    pub fn left_behind(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Records that a startup is in progress; call it before creating the
+    /// webview.
+    pub fn set(&self) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, b"")
+    }
+
+    /// Records that the startup completed; call it once the webview rendered.
+    pub fn clear(&self) -> std::io::Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_startup_marker_lifecycle() {
+        let dir = std::env::temp_dir().join(format!(
+            "webkit2gtk-nvidia-quirk-test-startup-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let marker = StartupMarker::at(dir.join("app").join(MARKER_FILE_NAME));
+
+        assert!(!marker.left_behind());
+        marker.set().unwrap();
+        assert!(marker.left_behind());
+        marker.clear().unwrap();
+        assert!(!marker.left_behind());
+        // Clearing twice, e.g. when the splashscreen closes again, is fine.
+        marker.clear().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -4,7 +4,7 @@
 #[cfg(desktop)]
 use clap::builder::TypedValueParser as _;
 #[cfg(desktop)]
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 #[cfg(windows)]
 use ipconfig::IfType;
 use mdns_sd::{Error, IfKind, ServiceDaemon, ServiceEvent};
@@ -12,43 +12,47 @@ use models::check_service_type_fully_qualified;
 use models::*;
 #[cfg(not(windows))]
 use pnet::datalink;
-use shared_constants::{
-    INTERFACES_LIST_CHECK_INTERVAL, MDNS_SD_IP_CHECK_INTERVAL, MDNS_SD_META_SERVICE,
-    METRICS_CHECK_INTERVAL, VERIFY_TIMEOUT,
-};
+use settings::{Settings, TimingOverrides};
+use shared_constants::{MDNS_SD_META_SERVICE, SERVICES_BATCH_INTERVAL};
 use std::{
     collections::{BTreeSet, HashMap, HashSet},
     net::IpAddr,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
+        Arc, Mutex, MutexGuard,
     },
 };
 use tauri::{AppHandle, Emitter, Manager, State, Theme, Window};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_opener::OpenerExt;
 #[cfg(all(target_os = "linux", desktop))]
-use webkit2gtk_nvidia_quirk::{apply_workaround_with_options, ApplyWorkaroundOptions};
+use webkit2gtk_nvidia_quirk::{
+    apply_workaround_with_options, remember_workaround, set_webkit_disable_dmabuf_renderer,
+    user_config_path, ApplyWorkaroundOptions, StartupMarker, WorkaroundConfig, WorkaroundKind,
+    WorkaroundSetting,
+};
 
-type SharedServiceDaemon = Arc<Mutex<ServiceDaemon>>;
+mod settings;
 
-const BROWSE_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(20);
-const BROWSE_RETRY_ATTEMPTS: usize = 100;
+type SharedServiceDaemon = Arc<Mutex<ServiceDaemon>>;
+type SharedSettings = Arc<Mutex<Settings>>;
 
 async fn browse_with_retry(
     daemon: &ServiceDaemon,
     service_type: &str,
+    timing: TimingConfig,
 ) -> Result<mdns_sd::Receiver<ServiceEvent>, Error> {
+    let max_attempts = timing.browse_retry_attempts;
     let mut attempts = 0;
     loop {
         match daemon.browse(service_type) {
             Ok(rx) => return Ok(rx),
-            Err(Error::Again) if attempts < BROWSE_RETRY_ATTEMPTS => {
+            Err(Error::Again) if attempts < max_attempts => {
                 attempts += 1;
                 log::warn!(
-                    "Failed to start browsing for {service_type}, retrying ({attempts}/{BROWSE_RETRY_ATTEMPTS})"
+                    "Failed to start browsing for {service_type}, retrying ({attempts}/{max_attempts})"
                 );
-                tokio::time::sleep(BROWSE_RETRY_DELAY).await;
+                tokio::time::sleep(timing.browse_retry_delay()).await;
             }
             Err(e) => {
                 log::error!(
@@ -68,13 +72,26 @@ struct ManagedState {
     ipv4_enabled: AtomicBool,
     ipv6_enabled: AtomicBool,
     disabled_interfaces: Arc<Mutex<HashSet<String>>>,
+    services: Arc<Mutex<ServiceCache>>,
+    coalescer: SharedEventCoalescer,
+    settings: SharedSettings,
+    timing_overrides: TimingOverrides,
+    /// Profile auto-activated while browsing, activated once browsing stops.
+    deferred_profile: Mutex<Option<String>>,
     #[cfg(desktop)]
     dev_tools_enabled: bool,
+    /// Why the app started differently than usual, shown to the user once.
+    #[cfg(desktop)]
+    startup_notice: Mutex<Option<String>>,
 }
 
 impl ManagedState {
     #[cfg(desktop)]
-    fn new(dev_tools_requested: bool) -> Self {
+    fn new(
+        dev_tools_requested: bool,
+        timing_overrides: TimingOverrides,
+        startup_notice: Option<String>,
+    ) -> Self {
         Self {
             daemon: initialize_shared_daemon(),
             queriers: Arc::new(Mutex::new(HashSet::new())),
@@ -83,7 +100,13 @@ impl ManagedState {
             ipv4_enabled: AtomicBool::new(true),
             ipv6_enabled: AtomicBool::new(true),
             disabled_interfaces: Arc::new(Mutex::new(HashSet::new())),
+            services: Arc::new(Mutex::new(ServiceCache::default())),
+            coalescer: SharedEventCoalescer::default(),
+            settings: Arc::new(Mutex::new(Settings::default())),
+            deferred_profile: Mutex::new(None),
+            timing_overrides,
             dev_tools_enabled: dev_tools_requested,
+            startup_notice: Mutex::new(startup_notice),
         }
     }
 
@@ -97,15 +120,64 @@ impl ManagedState {
             ipv4_enabled: AtomicBool::new(true),
             ipv6_enabled: AtomicBool::new(true),
             disabled_interfaces: Arc::new(Mutex::new(HashSet::new())),
+            services: Arc::new(Mutex::new(ServiceCache::default())),
+            coalescer: SharedEventCoalescer::default(),
+            settings: Arc::new(Mutex::new(Settings::default())),
+            deferred_profile: Mutex::new(None),
+            timing_overrides: TimingOverrides::default(),
+        }
+    }
+
+    fn timing(&self) -> TimingConfig {
+        effective_timing(&self.settings, &self.timing_overrides)
+    }
+
+    fn protocol_flags(&self) -> ProtocolFlags {
+        ProtocolFlags {
+            ipv4: self.ipv4_enabled.load(Ordering::SeqCst),
+            ipv6: self.ipv6_enabled.load(Ordering::SeqCst),
         }
     }
 }
 
-fn initialize_shared_daemon() -> SharedServiceDaemon {
-    let daemon = ServiceDaemon::new().expect("Failed to create daemon");
-    if let Err(err) = daemon.set_ip_check_interval(MDNS_SD_IP_CHECK_INTERVAL.as_secs() as u32) {
+/// Returns the timings from the settings with the command line overrides applied.
+fn effective_timing(settings: &Mutex<Settings>, overrides: &TimingOverrides) -> TimingConfig {
+    let timing = match settings.lock() {
+        Ok(settings) => settings.timing,
+        Err(err) => {
+            log::error!("Failed to lock settings: {err:?}, using default timings");
+            TimingConfig::default()
+        }
+    };
+    overrides.apply(timing)
+}
+
+fn apply_ip_check_interval(daemon: &ServiceDaemon, timing: &TimingConfig) {
+    if let Err(err) = daemon.set_ip_check_interval(timing.ip_check_interval_secs as u32) {
         log::warn!("Failed to set ip check interval: {err:?}, continuing anyway");
     }
+}
+
+/// Loads the persisted settings into the managed state and applies them to the daemon.
+fn load_settings(app: &AppHandle) {
+    let state = app.state::<ManagedState>();
+    let loaded = settings::load(app);
+    match state.settings.lock() {
+        Ok(mut settings) => *settings = loaded,
+        Err(err) => {
+            log::error!("Failed to lock settings: {err:?}");
+            return;
+        }
+    }
+    match state.daemon.lock() {
+        Ok(daemon) => apply_ip_check_interval(&daemon, &state.timing()),
+        Err(err) => log::error!("Failed to lock daemon: {err:?}"),
+    }
+}
+
+fn initialize_shared_daemon() -> SharedServiceDaemon {
+    let daemon = ServiceDaemon::new().expect("Failed to create daemon");
+    apply_ip_check_interval(&daemon, &TimingConfig::default());
     if let Err(err) = daemon.disable_interface(enumerate_mdns_incapable_interfaces()) {
         log::warn!("Failed to disable interface: {err:?}, continuing anyway");
     }
@@ -205,11 +277,36 @@ mod interface_selection_tests {
                 name: "en0".to_string(),
                 addresses: vec!["192.168.1.1".to_string()],
                 enabled: true,
+                index: 1,
+                mac: Some("00:11:22:33:44:55".to_string()),
+                flags: InterfaceFlags {
+                    up: true,
+                    multicast: true,
+                    broadcast: Some(true),
+                    ..Default::default()
+                },
+                mtu: Some(1500),
+                incapable_reason: None,
             },
             NetworkInterface {
                 name: "en1".to_string(),
-                addresses: vec![],
+                addresses: vec!["192.168.2.1".to_string()],
                 enabled: true,
+                index: 2,
+                mac: None,
+                flags: InterfaceFlags::default(),
+                mtu: None,
+                incapable_reason: None,
+            },
+            NetworkInterface {
+                name: "en2".to_string(),
+                addresses: vec![],
+                enabled: false,
+                index: 3,
+                mac: None,
+                flags: InterfaceFlags::default(),
+                mtu: None,
+                incapable_reason: Some("No IP addresses".to_string()),
             },
         ]
     }
@@ -217,7 +314,22 @@ mod interface_selection_tests {
     #[test]
     fn test_set_interface_enabled_flags_keeps_all_enabled_when_nothing_disabled() {
         let result = set_interface_enabled_flags(sample_interfaces(), &HashSet::new());
-        assert!(result.iter().all(|interface| interface.enabled));
+        assert!(result
+            .iter()
+            .filter(|interface| interface.is_mdns_capable())
+            .all(|interface| interface.enabled));
+    }
+
+    #[test]
+    fn test_set_interface_enabled_flags_never_enables_incapable_interfaces() {
+        let result = set_interface_enabled_flags(sample_interfaces(), &HashSet::new());
+        assert!(
+            !result
+                .iter()
+                .find(|interface| interface.name == "en2")
+                .expect("To contain en2")
+                .enabled
+        );
     }
 
     #[test]
@@ -264,12 +376,11 @@ fn from_resolved_service(resolved: &mdns_sd::ResolvedService) -> ResolvedService
     let mut sorted_txt: Vec<TxtRecord> = resolved
         .txt_properties
         .iter()
-        .map(|r| TxtRecord {
-            key: r.key().into(),
-            val: bytes_option_to_string_option_with_escaping(r.val()),
-        })
+        .map(|r| TxtRecord::new(r.key(), r.val()))
         .collect();
     sorted_txt.sort_by(|a, b| a.key.cmp(&b.key));
+    let now = timestamp_micros();
+    let sightings = interface_sightings(&consolidated, now);
     ResolvedService {
         instance_fullname: resolved.fullname.clone(),
         service_type: resolved.ty_domain.clone(),
@@ -278,8 +389,9 @@ fn from_resolved_service(resolved: &mdns_sd::ResolvedService) -> ResolvedService
         addresses: consolidated,
         subtype: resolved.sub_ty_domain.clone(),
         txt: sorted_txt,
-        updated_at_micros: timestamp_micros(),
+        updated_at_micros: now,
         dead: false,
+        sightings,
     }
 }
 
@@ -298,6 +410,20 @@ where
     }
 }
 
+/// Logs the findings of the service type linter for a service type found on the network.
+fn log_lint_diagnostics(service_type: &str, level: log::Level) {
+    let lint = lint_service_type(service_type);
+    for diagnostic in &lint.diagnostics {
+        log::log!(level, "Service type `{service_type}`: {diagnostic}");
+    }
+    if let Some(suggestion) = lint.suggestion {
+        log::log!(
+            level,
+            "Service type `{service_type}` was probably meant to be `{suggestion}`"
+        );
+    }
+}
+
 #[tauri::command]
 fn browse_types(window: Window, state: State<ManagedState>) -> Result<(), String> {
     let daemon = state
@@ -310,8 +436,9 @@ fn browse_types(window: Window, state: State<ManagedState>) -> Result<(), String
         .map_err(|e| format!("Failed to stop browsing for {MDNS_SD_META_SERVICE}: {e:?}"))?;
 
     let daemon = daemon.clone();
+    let timing = state.timing();
     tauri::async_runtime::spawn(async move {
-        let receiver = match browse_with_retry(&daemon, MDNS_SD_META_SERVICE).await {
+        let receiver = match browse_with_retry(&daemon, MDNS_SD_META_SERVICE, timing).await {
             Ok(receiver) => receiver,
             Err(_) => return,
         };
@@ -320,6 +447,7 @@ fn browse_types(window: Window, state: State<ManagedState>) -> Result<(), String
                 ServiceEvent::ServiceFound(_service_type, full_name) => {
                     match check_service_type_fully_qualified(full_name.as_str()) {
                         Ok(MdnsLabelType::ServiceType) => {
+                            log_lint_diagnostics(&full_name, log::Level::Debug);
                             emit_event(
                                 &window,
                                 "service-type-found",
@@ -329,12 +457,15 @@ fn browse_types(window: Window, state: State<ManagedState>) -> Result<(), String
                             );
                         }
                         Ok(MdnsLabelType::SubType) => {
-                            log::debug!(
-                                "Ignoring subtype `{full_name}` found during service type browsing"
+                            emit_event(
+                                &window,
+                                "service-subtype-found",
+                                &ServiceSubtypeFoundEvent { subtype: full_name },
                             );
                         }
                         Err(e) => {
-                            log::debug!("Ignoring invalid service type `{full_name}`: {e}")
+                            log::warn!("Ignoring invalid service type `{full_name}`: {e}");
+                            log_lint_diagnostics(&full_name, log::Level::Warn);
                         }
                     }
                 }
@@ -351,25 +482,124 @@ fn browse_types(window: Window, state: State<ManagedState>) -> Result<(), String
 }
 
 #[tauri::command]
-fn stop_browse(state: State<ManagedState>) -> Result<(), String> {
-    let daemon = state
-        .daemon
+fn stop_browse(window: Window, state: State<ManagedState>) -> Result<(), String> {
+    {
+        let daemon = state
+            .daemon
+            .lock()
+            .map_err(|e| format!("Failed to lock daemon: {e:?}"))?;
+        let mut queriers = state
+            .queriers
+            .lock()
+            .map_err(|e| format!("Failed to lock running queriers: {e:?}"))?;
+        for ty_domain in queriers.iter() {
+            if let Err(e) = daemon.stop_browse(ty_domain) {
+                log::error!("Failed to stop browsing for {ty_domain}: {e:?}");
+            }
+        }
+
+        queriers.clear();
+    }
+
+    let deferred = state
+        .deferred_profile
         .lock()
-        .map_err(|e| format!("Failed to lock daemon: {e:?}"))?;
-    let mut queriers = state
-        .queriers
+        .map_err(|e| format!("Failed to lock deferred profile: {e:?}"))?
+        .take();
+    if let Some(name) = deferred {
+        log::info!("Activating profile {name} deferred while browsing");
+        apply_auto_activated_profile(&window, &state, &name);
+        emit_interfaces(&window, &state.disabled_interfaces)?;
+    }
+    Ok(())
+}
+
+/// Locks the service cache after dropping the services expired by the dead service policy.
+fn lock_services(state: &ManagedState) -> Result<MutexGuard<'_, ServiceCache>, String> {
+    let policy = state
+        .settings
         .lock()
-        .map_err(|e| format!("Failed to lock running queriers: {e:?}"))?;
-    for ty_domain in queriers.iter() {
-        if let Err(e) = daemon.stop_browse(ty_domain) {
-            log::error!("Failed to stop browsing for {ty_domain}: {e:?}");
+        .map_err(|e| format!("Failed to lock settings: {e:?}"))?
+        .dead_service_policy;
+    let mut services = state
+        .services
+        .lock()
+        .map_err(|e| format!("Failed to lock services: {e:?}"))?;
+    services.purge(&policy, timestamp_micros());
+    Ok(services)
+}
+
+/// Drops the dead services that expired according to the dead service policy.
+fn purge_dead_services(services: &Mutex<ServiceCache>, settings: &Mutex<Settings>) {
+    let policy = match settings.lock() {
+        Ok(settings) => settings.dead_service_policy,
+        Err(err) => {
+            log::error!("Failed to lock settings: {err:?}");
+            return;
+        }
+    };
+    match services.lock() {
+        Ok(mut services) => {
+            let purged = services.purge(&policy, timestamp_micros());
+            if purged > 0 {
+                log::debug!("Purged {purged} dead services");
+            }
         }
+        Err(err) => log::error!("Failed to lock services: {err:?}"),
     }
+}
+
+/// Returns all services resolved since browsing started, sorted by instance fullname.
+#[tauri::command]
+fn list_services(state: State<ManagedState>) -> Result<Vec<ResolvedService>, String> {
+    Ok(lock_services(&state)?.list())
+}
+
+#[tauri::command]
+fn get_service(
+    instance_fullname: String,
+    state: State<ManagedState>,
+) -> Result<Option<ResolvedService>, String> {
+    Ok(lock_services(&state)?.get(&instance_fullname).cloned())
+}
+
+/// Returns the services updated at or after `since_micros`, allowing incremental snapshots.
+#[tauri::command]
+fn services_since(
+    since_micros: u64,
+    state: State<ManagedState>,
+) -> Result<Vec<ResolvedService>, String> {
+    Ok(lock_services(&state)?.since(since_micros))
+}
+
+#[tauri::command]
+fn clear_services(state: State<ManagedState>) -> Result<(), String> {
+    lock_services(&state)?.clear();
+    state
+        .coalescer
+        .events
+        .lock()
+        .map_err(|e| format!("Failed to lock event coalescer: {e:?}"))?
+        .reset();
+    Ok(())
+}
 
-    queriers.clear();
+#[tauri::command]
+fn clear_dead_services(state: State<ManagedState>) -> Result<(), String> {
+    lock_services(&state)?.clear_dead();
     Ok(())
 }
 
+/// Returns whether any browse is currently running, so a reloaded frontend can restore its state.
+#[tauri::command]
+fn is_browsing(state: State<ManagedState>) -> Result<bool, String> {
+    let queriers = state
+        .queriers
+        .lock()
+        .map_err(|e| format!("Failed to lock running queriers: {e:?}"))?;
+    Ok(!queriers.is_empty())
+}
+
 #[tauri::command]
 fn verify(instance_fullname: String, state: State<ManagedState>) -> Result<(), String> {
     let daemon = state
@@ -377,13 +607,54 @@ fn verify(instance_fullname: String, state: State<ManagedState>) -> Result<(), S
         .lock()
         .map_err(|e| format!("Failed to lock daemon: {e:?}"))?;
     daemon
-        .verify(instance_fullname.clone(), VERIFY_TIMEOUT)
+        .verify(instance_fullname.clone(), state.timing().verify_timeout())
         .map_err(|e| format!("Failed to verify {instance_fullname}: {e:?}"))?;
     Ok(())
 }
 
+/// Batches service events, so they reach the webview at most once per [`SERVICES_BATCH_INTERVAL`].
+#[derive(Clone, Default)]
+struct SharedEventCoalescer {
+    events: Arc<Mutex<EventCoalescer>>,
+    flush_scheduled: Arc<AtomicBool>,
+}
+
+impl SharedEventCoalescer {
+    /// Records an event and schedules a flush of the batch, unless one is already scheduled.
+    fn push(&self, window: &Window, record: impl FnOnce(&mut EventCoalescer)) {
+        match self.events.lock() {
+            Ok(mut events) => record(&mut events),
+            Err(err) => {
+                log::error!("Failed to lock event coalescer: {err:?}");
+                return;
+            }
+        }
+        if self.flush_scheduled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let coalescer = self.clone();
+        let window = window.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(SERVICES_BATCH_INTERVAL).await;
+            // Cleared before taking the batch, so events recorded from now on schedule a new flush
+            coalescer.flush_scheduled.store(false, Ordering::SeqCst);
+            let batch = match coalescer.events.lock() {
+                Ok(mut events) => events.take_batch(),
+                Err(err) => {
+                    log::error!("Failed to lock event coalescer: {err:?}");
+                    return;
+                }
+            };
+            if let Some(batch) = batch {
+                emit_event(&window, "services-batch", &batch);
+            }
+        });
+    }
+}
+
 #[tauri::command]
 fn browse_many(service_types: Vec<String>, window: Window, state: State<ManagedState>) {
+    let timing = state.timing();
     for service_type in service_types {
         let daemon = match state.daemon.lock() {
             Ok(daemon) => daemon.clone(),
@@ -405,9 +676,11 @@ fn browse_many(service_types: Vec<String>, window: Window, state: State<ManagedS
         drop(queriers);
 
         let queriers = state.queriers.clone();
+        let services = state.services.clone();
+        let coalescer = state.coalescer.clone();
         let window = window.clone();
         tauri::async_runtime::spawn(async move {
-            let receiver = match browse_with_retry(&daemon, &service_type).await {
+            let receiver = match browse_with_retry(&daemon, &service_type, timing).await {
                 Ok(receiver) => receiver,
                 Err(_) => {
                     if let Ok(mut queriers) = queriers.lock() {
@@ -419,23 +692,41 @@ fn browse_many(service_types: Vec<String>, window: Window, state: State<ManagedS
 
             while let Ok(event) = receiver.recv_async().await {
                 match event {
-                    ServiceEvent::ServiceResolved(resolved) => emit_event(
-                        &window,
-                        "service-resolved",
-                        &ServiceResolvedEvent {
-                            service: from_resolved_service(&resolved),
-                        },
-                    ),
+                    ServiceEvent::ServiceResolved(resolved) => {
+                        let service = from_resolved_service(&resolved);
+                        for diagnostic in lint_instance_name(&service.get_instance_name()) {
+                            log::debug!("Instance `{}`: {diagnostic}", service.instance_fullname);
+                        }
+                        for diagnostic in lint_hostname(&service.hostname) {
+                            log::debug!("Host of `{}`: {diagnostic}", service.instance_fullname);
+                        }
+                        for warning in service.txt_warnings() {
+                            log::debug!("TXT of `{}`: {warning}", service.instance_fullname);
+                        }
+                        let service = match services.lock() {
+                            Ok(mut services) => services.upsert(service),
+                            Err(err) => {
+                                log::error!("Failed to lock services: {err:?}");
+                                service
+                            }
+                        };
+                        coalescer.push(&window, |events| events.push_resolved(service));
+                    }
 
                     ServiceEvent::ServiceRemoved(_service_type, instance_name) => {
-                        emit_event(
-                            &window,
-                            "service-removed",
-                            &ServiceRemovedEvent {
+                        let at_micros = timestamp_micros();
+                        match services.lock() {
+                            Ok(mut services) => {
+                                services.mark_dead(&instance_name, at_micros);
+                            }
+                            Err(err) => log::error!("Failed to lock services: {err:?}"),
+                        }
+                        coalescer.push(&window, |events| {
+                            events.push_removed(ServiceRemovedEvent {
                                 instance_name,
-                                at_micros: timestamp_micros(),
-                            },
-                        );
+                                at_micros,
+                            })
+                        });
                     }
                     ServiceEvent::SearchStopped(_service_type) => {
                         break;
@@ -448,20 +739,53 @@ fn browse_many(service_types: Vec<String>, window: Window, state: State<ManagedS
 }
 
 #[cfg(not(windows))]
-/// Checks whether a network interface is capable of mDNS discovery.
+/// Determines why a network interface is not capable of mDNS discovery.
 ///
 /// mDNS capable interfaces must have at least one IP address, must not be loopback or point to
 /// point, must be running and support multicast and broadcast. On Android there are some `rmnet`
 /// (remote network virtual) interfaces for cellular modems without a broadcast capability like
 /// ethernet or wifi interfaces, and sometimes a `dummy0` interface without multicast capability,
 /// both of which are therefore considered incapable.
+///
+/// Returns `None` if the interface is capable.
+fn mdns_incapable_reason_pnet(
+    interface: &pnet::datalink::NetworkInterface,
+) -> Option<&'static str> {
+    if interface.ips.is_empty() {
+        Some("No IP addresses")
+    } else if interface.is_loopback() {
+        Some("Loopback interface")
+    } else if interface.is_point_to_point() {
+        Some("Point to point interface")
+    } else if !interface.is_multicast() {
+        Some("No multicast support")
+    } else if !interface.is_broadcast() {
+        Some("No broadcast support")
+    } else if !interface.is_running() {
+        Some("Not running")
+    } else {
+        None
+    }
+}
+
+#[cfg(not(windows))]
+/// Checks whether a network interface is capable of mDNS discovery.
 fn is_mdns_capable_pnet(interface: &pnet::datalink::NetworkInterface) -> bool {
-    !interface.ips.is_empty()
-        && !interface.is_loopback()
-        && !interface.is_point_to_point()
-        && interface.is_multicast()
-        && interface.is_broadcast()
-        && interface.is_running()
+    mdns_incapable_reason_pnet(interface).is_none()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+/// Reads the MTU of a network interface from sysfs.
+fn interface_mtu(name: &str) -> Option<u32> {
+    std::fs::read_to_string(format!("/sys/class/net/{name}/mtu"))
+        .ok()
+        .and_then(|mtu| mtu.trim().parse().ok())
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "android")))]
+/// The MTU is not available on this platform.
+fn interface_mtu(_name: &str) -> Option<u32> {
+    None
 }
 
 #[cfg(not(windows))]
@@ -481,19 +805,39 @@ fn enumerate_mdns_incapable_interfaces() -> Vec<IfKind> {
 }
 
 #[cfg(not(windows))]
-fn enumerate_mdns_capable_interfaces() -> Vec<NetworkInterface> {
+/// Enumerates all network interfaces of the host with their details.
+///
+/// Interfaces that cannot be used for mDNS carry the reason in `incapable_reason`. The loopback
+/// interface is offered as a selection even though it is not multicast capable, as it allows
+/// browsing mDNS services that are only advertised on loopback.
+fn enumerate_interfaces() -> Vec<NetworkInterface> {
     let mut interfaces: Vec<NetworkInterface> = datalink::interfaces()
         .iter()
-        .filter(|interface| {
-            // The loopback interface is offered as a selection even though it is not multicast
-            // capable, as it allows browsing mDNS services that are only advertised on loopback.
-            is_mdns_capable_pnet(interface)
-                || (interface.is_loopback() && !interface.ips.is_empty())
-        })
-        .map(|interface| NetworkInterface {
-            name: interface.name.clone(),
-            addresses: interface.ips.iter().map(|ip| ip.ip().to_string()).collect(),
-            enabled: true,
+        .map(|interface| {
+            let incapable_reason = if interface.is_loopback() && !interface.ips.is_empty() {
+                None
+            } else {
+                mdns_incapable_reason_pnet(interface).map(str::to_string)
+            };
+            NetworkInterface {
+                name: interface.name.clone(),
+                addresses: interface.ips.iter().map(|ip| ip.ip().to_string()).collect(),
+                enabled: incapable_reason.is_none(),
+                index: interface.index,
+                mac: interface
+                    .mac
+                    .filter(|mac| !mac.is_zero())
+                    .map(|mac| mac.to_string()),
+                flags: InterfaceFlags {
+                    up: interface.is_up(),
+                    multicast: interface.is_multicast(),
+                    broadcast: Some(interface.is_broadcast()),
+                    point_to_point: interface.is_point_to_point(),
+                    loopback: interface.is_loopback(),
+                },
+                mtu: interface_mtu(&interface.name),
+                incapable_reason,
+            }
         })
         .collect();
     interfaces.sort_by(|a, b| a.name.cmp(&b.name));
@@ -564,10 +908,7 @@ mod tests {
 /// mDNS capable adapters must have at least one IP address, must be up and must be of type
 /// ethernet or IEEE 802.11 (WiFi).
 fn is_mdns_capable_ipconfig(adapter: &ipconfig::Adapter) -> bool {
-    !adapter.ip_addresses().is_empty()
-        && adapter.oper_status() == ipconfig::OperStatus::IfOperStatusUp
-        && (adapter.if_type() == ipconfig::IfType::EthernetCsmacd
-            || adapter.if_type() == ipconfig::IfType::Ieee80211)
+    mdns_incapable_reason_ipconfig(adapter).is_none()
 }
 
 #[cfg(windows)]
@@ -597,7 +938,109 @@ fn enumerate_mdns_incapable_interfaces() -> Vec<IfKind> {
 }
 
 #[cfg(windows)]
-fn enumerate_mdns_capable_interfaces() -> Vec<NetworkInterface> {
+/// Determines why a network adapter is not capable of mDNS discovery.
+///
+/// Returns `None` if the adapter is capable, see [`is_mdns_capable_ipconfig`].
+fn mdns_incapable_reason_ipconfig(adapter: &ipconfig::Adapter) -> Option<&'static str> {
+    if adapter.ip_addresses().is_empty() {
+        Some("No IP addresses")
+    } else if adapter.oper_status() != ipconfig::OperStatus::IfOperStatusUp {
+        Some("Not up")
+    } else if !matches!(
+        adapter.if_type(),
+        IfType::EthernetCsmacd | IfType::Ieee80211
+    ) {
+        Some("Neither an ethernet nor a WiFi adapter")
+    } else {
+        None
+    }
+}
+
+#[cfg(windows)]
+mod adapter_details {
+    use std::collections::HashMap;
+    use windows_sys::Win32::{
+        Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR},
+        NetworkManagement::IpHelper::{
+            GetAdaptersAddresses, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER,
+            GAA_FLAG_SKIP_MULTICAST, IP_ADAPTER_ADDRESSES_LH, IP_ADAPTER_NO_MULTICAST,
+        },
+        Networking::WinSock::AF_UNSPEC,
+    };
+
+    /// Details of an adapter that the `ipconfig` crate does not expose.
+    pub struct AdapterDetails {
+        pub index: u32,
+        pub mtu: Option<u32>,
+        pub multicast: bool,
+    }
+
+    /// Reads the details of all adapters, keyed by adapter name.
+    pub fn read() -> std::io::Result<HashMap<String, AdapterDetails>> {
+        let flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+        // Recommended initial size, grown as requested by the API.
+        let mut size: u32 = 15_000;
+        loop {
+            // u64 elements keep the buffer aligned for IP_ADAPTER_ADDRESSES_LH
+            let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+            let first = buffer.as_mut_ptr().cast::<IP_ADAPTER_ADDRESSES_LH>();
+            // SAFETY: The buffer is writable for `size` bytes and suitably aligned.
+            let result = unsafe {
+                GetAdaptersAddresses(
+                    u32::from(AF_UNSPEC),
+                    flags,
+                    std::ptr::null(),
+                    first,
+                    &mut size,
+                )
+            };
+            match result {
+                NO_ERROR => return Ok(collect(first)),
+                ERROR_BUFFER_OVERFLOW => continue,
+                err => return Err(std::io::Error::from_raw_os_error(err as i32)),
+            }
+        }
+    }
+
+    fn collect(mut adapter: *const IP_ADAPTER_ADDRESSES_LH) -> HashMap<String, AdapterDetails> {
+        let mut details = HashMap::new();
+        // SAFETY: The adapters form a linked list within the buffer filled by
+        // GetAdaptersAddresses, which outlives this function.
+        while let Some(current) = unsafe { adapter.as_ref() } {
+            // SAFETY: AdapterName is a NUL terminated string within the buffer, and both unions
+            // are plain integers in all of their variants.
+            let (name, index, adapter_flags) = unsafe {
+                (
+                    std::ffi::CStr::from_ptr(current.AdapterName.cast())
+                        .to_string_lossy()
+                        .into_owned(),
+                    current.Anonymous1.Anonymous.IfIndex,
+                    current.Anonymous2.Flags,
+                )
+            };
+            details.insert(
+                name,
+                AdapterDetails {
+                    index,
+                    // Adapters without a link, like disconnected VPN adapters, report u32::MAX
+                    mtu: Some(current.Mtu).filter(|mtu| *mtu != 0 && *mtu != u32::MAX),
+                    multicast: adapter_flags & IP_ADAPTER_NO_MULTICAST == 0,
+                },
+            );
+            adapter = current.Next;
+        }
+        details
+    }
+}
+
+#[cfg(windows)]
+/// Enumerates all network adapters of the host with their details.
+///
+/// Adapters that cannot be used for mDNS carry the reason in `incapable_reason`. The loopback
+/// adapter is offered as a selection even though it is not multicast capable, as it allows
+/// browsing mDNS services that are only advertised on loopback. Windows does not report whether
+/// an adapter supports broadcast, so it is left unknown.
+fn enumerate_interfaces() -> Vec<NetworkInterface> {
     let adapters = match ipconfig::get_adapters() {
         Ok(adapters) => adapters,
         Err(err) => {
@@ -605,24 +1048,48 @@ fn enumerate_mdns_capable_interfaces() -> Vec<NetworkInterface> {
             return vec![];
         }
     };
+    let details = adapter_details::read().unwrap_or_else(|err| {
+        log::warn!("Failed to read adapter details: {err}");
+        Default::default()
+    });
     let mut interfaces: Vec<NetworkInterface> = adapters
         .iter()
-        .filter(|adapter| {
-            // The loopback adapter is offered as a selection even though it is not
-            // multicast capable, as it allows browsing mDNS services that are only
-            // advertised on loopback.
-            is_mdns_capable_ipconfig(adapter)
-                || (adapter.if_type() == IfType::SoftwareLoopback
-                    && !adapter.ip_addresses().is_empty())
-        })
-        .map(|adapter| NetworkInterface {
-            name: adapter.friendly_name().to_string(),
-            addresses: adapter
-                .ip_addresses()
-                .iter()
-                .map(|ip| ip.to_string())
-                .collect(),
-            enabled: true,
+        .map(|adapter| {
+            let loopback = adapter.if_type() == IfType::SoftwareLoopback;
+            let incapable_reason = if loopback && !adapter.ip_addresses().is_empty() {
+                None
+            } else {
+                mdns_incapable_reason_ipconfig(adapter).map(str::to_string)
+            };
+            let details = details.get(adapter.adapter_name());
+            NetworkInterface {
+                name: adapter.friendly_name().to_string(),
+                addresses: adapter
+                    .ip_addresses()
+                    .iter()
+                    .map(|ip| ip.to_string())
+                    .collect(),
+                enabled: incapable_reason.is_none(),
+                index: details.map_or(0, |details| details.index),
+                mac: adapter
+                    .physical_address()
+                    .filter(|mac| !mac.is_empty())
+                    .map(|mac| {
+                        mac.iter()
+                            .map(|byte| format!("{byte:02x}"))
+                            .collect::<Vec<_>>()
+                            .join(":")
+                    }),
+                flags: InterfaceFlags {
+                    up: adapter.oper_status() == ipconfig::OperStatus::IfOperStatusUp,
+                    multicast: details.is_some_and(|details| details.multicast),
+                    broadcast: None,
+                    point_to_point: matches!(adapter.if_type(), IfType::Ppp | IfType::Tunnel),
+                    loopback,
+                },
+                mtu: details.and_then(|details| details.mtu),
+                incapable_reason,
+            }
         })
         .collect();
     interfaces.sort_by(|a, b| a.name.cmp(&b.name));
@@ -689,35 +1156,215 @@ mod tests {
     }
 }
 
-async fn poll_interfaces(window: Window, disabled_interfaces: Arc<Mutex<HashSet<String>>>) {
+/// Enumerates the network interfaces and emits an `"interfaces-changed"` event if they differ
+/// from `current`.
+///
+/// If the change makes the auto-activation rules match another profile, that profile is activated
+/// first. Returns `false` if the disabled interfaces could not be locked, in which case the caller
+/// should stop watching.
+fn emit_interfaces_if_changed(
+    window: &Window,
+    disabled_interfaces: &Mutex<HashSet<String>>,
+    current: &mut Vec<NetworkInterface>,
+) -> bool {
+    let enumerated = enumerate_interfaces();
+    // The first enumeration is skipped, the profile to start with was chosen at startup
+    if !current.is_empty() {
+        auto_activate_profile(window, current, &enumerated);
+    }
+    let disabled = match disabled_interfaces.lock() {
+        Ok(disabled) => disabled.clone(),
+        Err(err) => {
+            log::error!("Failed to lock disabled interfaces: {err:?}");
+            return false;
+        }
+    };
+    let interfaces = set_interface_enabled_flags(enumerated, &disabled);
+    if interfaces != *current {
+        *current = interfaces.clone();
+        emit_event(
+            window,
+            "interfaces-changed",
+            &InterfacesChangedEvent { interfaces },
+        );
+    }
+    true
+}
+
+/// Activates the profile whose auto-activation rules match the `present` interfaces, if that is
+/// another profile than the one they matched for the `previous` interfaces, and emits a
+/// `"profiles-changed"` event.
+///
+/// A profile chosen by the user is thereby kept until the network changes to one that another
+/// profile is meant for. While browsing, the profile is only activated once browsing stops, as
+/// switching the interfaces and protocols would disturb the running browse.
+fn auto_activate_profile(
+    window: &Window,
+    previous: &[NetworkInterface],
+    present: &[NetworkInterface],
+) {
+    let app = window.app_handle();
+    let state = app.state::<ManagedState>();
+    let profiles = match state.settings.lock() {
+        Ok(settings) => settings.profiles.clone(),
+        Err(err) => {
+            log::error!("Failed to lock settings: {err:?}");
+            return;
+        }
+    };
+    let Some(profile) = profiles.auto_activated(present) else {
+        return;
+    };
+    let mut deferred = match state.deferred_profile.lock() {
+        Ok(deferred) => deferred,
+        Err(err) => {
+            log::error!("Failed to lock deferred profile: {err:?}");
+            return;
+        }
+    };
+    if profiles.active.as_deref() == Some(profile.name.as_str()) {
+        deferred.take();
+        return;
+    }
+    if profiles
+        .auto_activated(previous)
+        .is_some_and(|matched| matched.name == profile.name)
+    {
+        return;
+    }
+    let browsing = match state.queriers.lock() {
+        Ok(queriers) => !queriers.is_empty(),
+        Err(err) => {
+            log::error!("Failed to lock running queriers: {err:?}");
+            return;
+        }
+    };
+    if browsing {
+        log::info!(
+            "Deferring profile {} for the changed interfaces until browsing stops",
+            profile.name
+        );
+        *deferred = Some(profile.name.clone());
+        return;
+    }
+    deferred.take();
+    drop(deferred);
+    log::info!(
+        "Activating profile {} for the changed interfaces",
+        profile.name
+    );
+    apply_auto_activated_profile(window, &state, &profile.name);
+}
+
+/// Activates the auto-activated profile `name` and emits a `"profiles-changed"` event.
+fn apply_auto_activated_profile(window: &Window, state: &ManagedState, name: &str) {
+    if let Err(err) = activate(window.app_handle(), state, Some(name)) {
+        log::error!("Failed to activate profile {name}: {err}");
+        return;
+    }
+    match state.settings.lock() {
+        Ok(settings) => emit_event(
+            window,
+            "profiles-changed",
+            &ProfilesChangedEvent {
+                profiles: settings.profiles.clone(),
+            },
+        ),
+        Err(err) => log::error!("Failed to lock settings: {err:?}"),
+    }
+}
+
+async fn poll_interfaces(
+    window: Window,
+    disabled_interfaces: Arc<Mutex<HashSet<String>>>,
+    settings: SharedSettings,
+    timing_overrides: TimingOverrides,
+) {
     let mut current: Vec<NetworkInterface> = Vec::new();
-    loop {
-        let disabled = match disabled_interfaces.lock() {
-            Ok(disabled) => disabled.clone(),
-            Err(err) => {
-                log::error!("Failed to lock disabled interfaces: {err:?}");
-                return;
+    while emit_interfaces_if_changed(&window, &disabled_interfaces, &mut current) {
+        let interval = effective_timing(&settings, &timing_overrides).interfaces_check_interval();
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod netlink_watch {
+    use netlink_sys::{
+        protocols::NETLINK_ROUTE, AsyncSocket, AsyncSocketExt, SocketAddr, TokioSocket,
+    };
+    use shared_constants::INTERFACES_CHANGE_DEBOUNCE;
+
+    // Multicast groups from linux/rtnetlink.h
+    const RTMGRP_LINK: u32 = 0x1;
+    const RTMGRP_IPV4_IFADDR: u32 = 0x10;
+    const RTMGRP_IPV6_IFADDR: u32 = 0x100;
+
+    /// Opens a route netlink socket subscribed to link and address changes.
+    pub fn subscribe() -> std::io::Result<TokioSocket> {
+        let mut socket = TokioSocket::new(NETLINK_ROUTE)?;
+        socket.socket_mut().bind(&SocketAddr::new(
+            0,
+            RTMGRP_LINK | RTMGRP_IPV4_IFADDR | RTMGRP_IPV6_IFADDR,
+        ))?;
+        Ok(socket)
+    }
+
+    /// Waits for the next link or address change notification.
+    ///
+    /// A single change, like a wifi reconnect, usually results in a burst of notifications, which
+    /// are consumed until the socket stays quiet for [`INTERFACES_CHANGE_DEBOUNCE`].
+    pub async fn next_change(socket: &TokioSocket) -> std::io::Result<()> {
+        socket.recv_from_full().await?;
+        while let Ok(received) =
+            tokio::time::timeout(INTERFACES_CHANGE_DEBOUNCE, socket.recv_from_full()).await
+        {
+            received?;
+        }
+        Ok(())
+    }
+}
+
+/// Watches for changes of the network interfaces and emits them to the frontend.
+///
+/// On Linux the kernel notifies about link and address changes via route netlink, so interfaces
+/// are only re-enumerated when something changed. Elsewhere, or if the netlink socket fails, the
+/// interfaces are polled at regular intervals.
+async fn watch_interfaces(
+    window: Window,
+    disabled_interfaces: Arc<Mutex<HashSet<String>>>,
+    settings: SharedSettings,
+    timing_overrides: TimingOverrides,
+) {
+    #[cfg(target_os = "linux")]
+    match netlink_watch::subscribe() {
+        Ok(socket) => {
+            let mut current: Vec<NetworkInterface> = Vec::new();
+            loop {
+                if !emit_interfaces_if_changed(&window, &disabled_interfaces, &mut current) {
+                    return;
+                }
+                if let Err(err) = netlink_watch::next_change(&socket).await {
+                    log::warn!(
+                        "Failed to receive netlink notification: {err:?}, falling back to polling"
+                    );
+                    break;
+                }
             }
-        };
-        let interfaces =
-            set_interface_enabled_flags(enumerate_mdns_capable_interfaces(), &disabled);
-        if interfaces != current {
-            current = interfaces.clone();
-            emit_event(
-                &window,
-                "interfaces-changed",
-                &InterfacesChangedEvent { interfaces },
+        }
+        Err(err) => {
+            log::warn!(
+                "Failed to subscribe to netlink notifications: {err:?}, falling back to polling"
             );
         }
-        tokio::time::sleep(INTERFACES_LIST_CHECK_INTERVAL).await;
     }
+    poll_interfaces(window, disabled_interfaces, settings, timing_overrides).await;
 }
 
-/// Subscribes to periodic updates of the available mDNS network interfaces.
+/// Subscribes to updates of the available network interfaces.
 ///
-/// Starts a background task that polls the system for mDNS-capable network interfaces at regular
-/// intervals, or emits the current list immediately if a subscription is already active. Emits
-/// `"interfaces-changed"` events to the Tauri window.
+/// Starts a background task that watches the system for network interface changes, or emits the
+/// current list immediately if a subscription is already active. Emits `"interfaces-changed"`
+/// events to the Tauri window.
 #[tauri::command]
 fn subscribe_interfaces(window: Window, state: State<ManagedState>) -> Result<(), String> {
     if state
@@ -725,32 +1372,46 @@ fn subscribe_interfaces(window: Window, state: State<ManagedState>) -> Result<()
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
         .is_ok()
     {
-        tauri::async_runtime::spawn(poll_interfaces(window, state.disabled_interfaces.clone()));
+        tauri::async_runtime::spawn(watch_interfaces(
+            window,
+            state.disabled_interfaces.clone(),
+            state.settings.clone(),
+            state.timing_overrides,
+        ));
         Ok(())
     } else {
-        let disabled = match state.disabled_interfaces.lock() {
-            Ok(disabled) => disabled.clone(),
-            Err(err) => {
-                log::error!("Failed to lock disabled interfaces: {err:?}");
-                return Err(format!("Failed to lock disabled interfaces: {err:?}"));
-            }
-        };
-        let interfaces =
-            set_interface_enabled_flags(enumerate_mdns_capable_interfaces(), &disabled);
-        emit_event(
-            &window,
-            "interfaces-changed",
-            &InterfacesChangedEvent { interfaces },
-        );
-        Ok(())
+        emit_interfaces(&window, &state.disabled_interfaces)
     }
 }
 
+/// Emits an `"interfaces-changed"` event with the current interfaces, regardless of whether they
+/// changed.
+fn emit_interfaces(
+    window: &Window,
+    disabled_interfaces: &Mutex<HashSet<String>>,
+) -> Result<(), String> {
+    let disabled = match disabled_interfaces.lock() {
+        Ok(disabled) => disabled.clone(),
+        Err(err) => {
+            log::error!("Failed to lock disabled interfaces: {err:?}");
+            return Err(format!("Failed to lock disabled interfaces: {err:?}"));
+        }
+    };
+    let interfaces = set_interface_enabled_flags(enumerate_interfaces(), &disabled);
+    emit_event(
+        window,
+        "interfaces-changed",
+        &InterfacesChangedEvent { interfaces },
+    );
+    Ok(())
+}
+
 #[tauri::command]
 /// Subscribes to periodic mDNS daemon metrics updates and emits changes to the frontend.
 ///
 /// Starts a background task that polls the mDNS daemon for metrics at regular intervals.
 /// When metrics contents changes, emits a `"metrics-changed"` event to the Tauri window.
+/// The same task purges expired dead services.
 /// Ensures only one subscription is active at a time for the application window.
 fn subscribe_metrics(window: Window, state: State<ManagedState>) {
     // Avoid multiple subscriptions when the frontend is reloaded.
@@ -761,22 +1422,26 @@ fn subscribe_metrics(window: Window, state: State<ManagedState>) {
     {
         if let Ok(daemon) = state.daemon.lock() {
             let daemon = daemon.clone();
+            let settings = state.settings.clone();
+            let services = state.services.clone();
+            let timing_overrides = state.timing_overrides;
             let mut old_metrics = HashMap::new();
             tauri::async_runtime::spawn(async move {
                 loop {
                     if let Ok(metrics_receiver) = daemon.get_metrics() {
                         if let Ok(metrics) = metrics_receiver.recv_async().await {
-                            if old_metrics == metrics {
-                                continue;
+                            // Unchanged metrics are not emitted, but still wait for the next
+                            // check so the configured interval is honored.
+                            if old_metrics != metrics {
+                                emit_event(
+                                    &window,
+                                    "metrics-changed",
+                                    &MetricsChangedEvent {
+                                        metrics: metrics.clone(),
+                                    },
+                                );
+                                old_metrics = metrics;
                             }
-                            emit_event(
-                                &window,
-                                "metrics-changed",
-                                &MetricsChangedEvent {
-                                    metrics: metrics.clone(),
-                                },
-                            );
-                            old_metrics = metrics;
                         } else {
                             break;
                         }
@@ -784,13 +1449,175 @@ fn subscribe_metrics(window: Window, state: State<ManagedState>) {
                         break;
                     }
 
-                    tokio::time::sleep(METRICS_CHECK_INTERVAL).await;
+                    // Services are otherwise only purged when queried, which a quiet session
+                    // never does.
+                    purge_dead_services(&services, &settings);
+
+                    let interval =
+                        effective_timing(&settings, &timing_overrides).metrics_check_interval();
+                    tokio::time::sleep(interval).await;
                 }
             });
         }
     }
 }
 
+/// Applies `update` to the settings and persists them.
+fn update_settings(
+    app: &AppHandle,
+    state: &ManagedState,
+    update: impl FnOnce(&mut Settings),
+) -> Result<(), String> {
+    let mut settings = state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to lock settings: {e:?}"))?;
+    update(&mut settings);
+    settings::save(app, &settings).map_err(|e| format!("Failed to save settings: {e}"))
+}
+
+#[tauri::command]
+fn get_dead_service_policy(state: State<ManagedState>) -> Result<DeadServicePolicy, String> {
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to lock settings: {e:?}"))?;
+    Ok(settings.dead_service_policy)
+}
+
+#[tauri::command]
+fn set_dead_service_policy(
+    app: AppHandle,
+    state: State<ManagedState>,
+    policy: DeadServicePolicy,
+) -> Result<(), String> {
+    policy.validate().map_err(|e| e.to_string())?;
+    update_settings(&app, &state, |settings| {
+        settings.dead_service_policy = policy
+    })
+}
+
+/// Returns the effective timings, including command line overrides.
+#[tauri::command]
+fn get_timing_config(state: State<ManagedState>) -> TimingConfig {
+    state.timing()
+}
+
+/// Validates and persists new timings, and applies them to the running daemon.
+///
+/// Timings overridden on the command line keep their overridden values for the current session.
+#[tauri::command]
+fn set_timing_config(
+    app: AppHandle,
+    state: State<ManagedState>,
+    config: TimingConfig,
+) -> Result<(), String> {
+    config.validate().map_err(|e| e.to_string())?;
+    update_settings(&app, &state, |settings| settings.timing = config)?;
+    let daemon = state
+        .daemon
+        .lock()
+        .map_err(|e| format!("Failed to lock daemon: {e:?}"))?;
+    apply_ip_check_interval(&daemon, &state.timing());
+    Ok(())
+}
+
+#[tauri::command]
+fn get_update_check_settings(state: State<ManagedState>) -> Result<UpdateCheckSettings, String> {
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to lock settings: {e:?}"))?;
+    Ok(settings.update_check.clone())
+}
+
+/// Validates and persists the schedule of the update checks.
+///
+/// The skipped version, the reminder and the time of the last check are kept, they are only
+/// changed by the dedicated commands.
+#[tauri::command]
+fn set_update_check_settings(
+    app: AppHandle,
+    state: State<ManagedState>,
+    settings: UpdateCheckSettings,
+) -> Result<(), String> {
+    settings.validate().map_err(|e| e.to_string())?;
+    update_settings(&app, &state, |current| {
+        current.update_check.enabled = settings.enabled;
+        current.update_check.interval_hours = settings.interval_hours;
+        current.update_check.skip_on_metered = settings.skip_on_metered;
+    })
+}
+
+/// Returns whether a scheduled update check is due, and if so records that it started.
+///
+/// The start is recorded before the check runs, so a failing check is not retried before the
+/// interval passed.
+#[tauri::command]
+fn start_scheduled_update_check(
+    app: AppHandle,
+    state: State<ManagedState>,
+) -> Result<bool, String> {
+    let now = timestamp_micros();
+    let metered = is_network_metered(&app);
+    let due = state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to lock settings: {e:?}"))?
+        .update_check
+        .is_check_due(now, metered);
+    if due {
+        update_settings(&app, &state, |settings| {
+            settings.update_check.last_check_micros = Some(now)
+        })?;
+    } else if metered {
+        log::debug!("Network is metered, scheduled update check skipped");
+    }
+    Ok(due)
+}
+
+/// Returns whether a scheduled update check offers the update to `version`.
+#[tauri::command]
+fn offers_update(state: State<ManagedState>, version: String) -> Result<bool, String> {
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to lock settings: {e:?}"))?;
+    Ok(settings.update_check.offers(&version, timestamp_micros()))
+}
+
+#[tauri::command]
+fn skip_update_version(
+    app: AppHandle,
+    state: State<ManagedState>,
+    version: String,
+) -> Result<(), String> {
+    update_settings(&app, &state, |settings| {
+        settings.update_check.skip_version(&version)
+    })
+}
+
+#[tauri::command]
+fn remind_about_update_later(app: AppHandle, state: State<ManagedState>) -> Result<(), String> {
+    let now = timestamp_micros();
+    update_settings(&app, &state, |settings| {
+        settings.update_check.remind_later(now)
+    })
+}
+
+/// Whether the active network is metered. Only Android tells, so elsewhere networks count as
+/// unmetered.
+#[cfg(desktop)]
+fn is_network_metered(_app: &AppHandle) -> bool {
+    false
+}
+
+#[cfg(mobile)]
+fn is_network_metered(app: &AppHandle) -> bool {
+    use tauri_plugin_android_update::AndroidUpdateExt;
+    app.is_network_metered()
+}
+
 #[tauri::command]
 fn open_url(app: AppHandle, url: String) -> Result<(), String> {
     let opener = app.opener();
@@ -807,12 +1634,7 @@ fn version() -> String {
 
 #[tauri::command]
 fn get_protocol_flags(state: State<ManagedState>) -> ProtocolFlags {
-    let ipv4_enabled = state.ipv4_enabled.load(Ordering::SeqCst);
-    let ipv6_enabled = state.ipv6_enabled.load(Ordering::SeqCst);
-    ProtocolFlags {
-        ipv4: ipv4_enabled,
-        ipv6: ipv6_enabled,
-    }
+    state.protocol_flags()
 }
 
 /// Applies the configured interface selections to the mDNS daemon.
@@ -854,13 +1676,23 @@ fn apply_interface_selections(
     Ok(())
 }
 
+/// Enumerates the network interfaces that can be selected for mDNS browsing.
+fn enumerate_mdns_capable_interfaces() -> Vec<NetworkInterface> {
+    enumerate_interfaces()
+        .into_iter()
+        .filter(NetworkInterface::is_mdns_capable)
+        .collect()
+}
+
 /// Marks the given interfaces as enabled based on the provided set of disabled interface names.
+///
+/// Interfaces that are not mDNS capable are never marked as enabled.
 fn set_interface_enabled_flags(
     mut interfaces: Vec<NetworkInterface>,
     disabled: &HashSet<String>,
 ) -> Vec<NetworkInterface> {
     for interface in &mut interfaces {
-        interface.enabled = !disabled.contains(&interface.name);
+        interface.enabled = interface.is_mdns_capable() && !disabled.contains(&interface.name);
     }
     interfaces
 }
@@ -887,8 +1719,16 @@ fn set_protocol_flags(state: State<ManagedState>, flags: ProtocolFlags) -> Resul
     Ok(())
 }
 
+/// Applies the selection of enabled interfaces to the mDNS daemon.
+///
+/// Emits an `"interfaces-changed"` event with the new selection, as the interface watcher only
+/// emits when the system reports a change.
 #[tauri::command]
-fn set_interfaces(state: State<ManagedState>, enabled: Vec<String>) -> Result<(), String> {
+fn set_interfaces(
+    window: Window,
+    state: State<ManagedState>,
+    enabled: Vec<String>,
+) -> Result<(), String> {
     let enabled: HashSet<String> = enabled.into_iter().collect();
     let capable_names: HashSet<String> = enumerate_mdns_capable_interfaces()
         .into_iter()
@@ -919,9 +1759,141 @@ fn set_interfaces(state: State<ManagedState>, enabled: Vec<String>) -> Result<()
         return Err(err);
     }
     *disabled_interfaces = new_disabled;
+    drop(daemon);
+    drop(disabled_interfaces);
+    emit_interfaces(&window, &state.disabled_interfaces)
+}
+
+#[tauri::command]
+fn get_profiles(state: State<ManagedState>) -> Result<ProfileSettings, String> {
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|e| format!("Failed to lock settings: {e:?}"))?;
+    Ok(settings.profiles.clone())
+}
+
+/// Saves the current interface selection and protocol flags as profile `name`, along with the
+/// service types to browse for and the rules that activate it.
+///
+/// A profile with the same name is replaced.
+#[tauri::command]
+fn save_profile(
+    app: AppHandle,
+    state: State<ManagedState>,
+    name: String,
+    service_types: Vec<String>,
+    auto_activate: Vec<String>,
+) -> Result<(), String> {
+    let disabled_interfaces = state
+        .disabled_interfaces
+        .lock()
+        .map_err(|e| format!("Failed to lock disabled interfaces: {e:?}"))?
+        .iter()
+        .cloned()
+        .collect();
+    let profile = Profile {
+        name: name.trim().to_string(),
+        disabled_interfaces,
+        protocol_flags: state.protocol_flags(),
+        service_types,
+        auto_activate,
+    };
+    profile.validate().map_err(|e| e.to_string())?;
+    update_settings(&app, &state, |settings| settings.profiles.save(profile))
+}
+
+#[tauri::command]
+fn delete_profile(app: AppHandle, state: State<ManagedState>, name: String) -> Result<(), String> {
+    update_settings(&app, &state, |settings| settings.profiles.remove(&name))
+}
+
+/// Applies the interface selection and protocol flags of `profile` to the mDNS daemon.
+fn apply_profile(state: &ManagedState, profile: &Profile) -> Result<(), String> {
+    let new_disabled: HashSet<String> = profile.disabled_interfaces.iter().cloned().collect();
+    let mut disabled_interfaces = state
+        .disabled_interfaces
+        .lock()
+        .map_err(|e| format!("Failed to lock disabled interfaces: {e:?}"))?;
+    let daemon = state
+        .daemon
+        .lock()
+        .map_err(|e| format!("Failed to lock daemon: {e:?}"))?;
+    let flags = &profile.protocol_flags;
+    apply_interface_selections(&daemon, &new_disabled, flags.ipv4, flags.ipv6)?;
+    *disabled_interfaces = new_disabled;
+    state.ipv4_enabled.store(flags.ipv4, Ordering::SeqCst);
+    state.ipv6_enabled.store(flags.ipv6, Ordering::SeqCst);
     Ok(())
 }
 
+/// Applies and persists profile `name` as the active profile, `None` only deactivates the active
+/// profile and keeps the current interface selection and protocol flags.
+fn activate(app: &AppHandle, state: &ManagedState, name: Option<&str>) -> Result<(), String> {
+    if let Some(name) = name {
+        let profile = state
+            .settings
+            .lock()
+            .map_err(|e| format!("Failed to lock settings: {e:?}"))?
+            .profiles
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("There is no profile named {name}"))?;
+        apply_profile(state, &profile)?;
+    }
+    update_settings(app, state, |settings| {
+        settings.profiles.active = name.map(str::to_string)
+    })
+}
+
+/// Activates profile `name`, or deactivates the active profile if `None`.
+///
+/// Emits an `"interfaces-changed"` event, so the interface selection of the profile shows up in
+/// the frontend.
+#[tauri::command]
+fn activate_profile(
+    app: AppHandle,
+    window: Window,
+    state: State<ManagedState>,
+    name: Option<String>,
+) -> Result<(), String> {
+    if let Err(err) = activate(&app, &state, name.as_deref()) {
+        log::error!("Failed to activate profile: {err}");
+        return Err(err);
+    }
+    emit_interfaces(&window, &state.disabled_interfaces)
+}
+
+/// Activates the profile requested on the command line, otherwise the first profile whose
+/// auto-activation rules match the present interfaces, otherwise the previously active profile.
+fn activate_startup_profile(app: &AppHandle, requested: Option<&str>) {
+    let state = app.state::<ManagedState>();
+    let profiles = match state.settings.lock() {
+        Ok(settings) => settings.profiles.clone(),
+        Err(err) => {
+            log::error!("Failed to lock settings: {err:?}");
+            return;
+        }
+    };
+    let requested = requested.and_then(|name| {
+        let profile = profiles.get(name);
+        if profile.is_none() {
+            log::error!("There is no profile named {name}, ignoring it");
+        }
+        profile
+    });
+    let Some(profile) = requested
+        .or_else(|| profiles.auto_activated(&enumerate_interfaces()))
+        .or_else(|| profiles.active_profile())
+    else {
+        return;
+    };
+    log::info!("Activating profile {}", profile.name);
+    if let Err(err) = activate(app, &state, Some(profile.name.as_str())) {
+        log::error!("Failed to activate profile {}: {err}", profile.name);
+    }
+}
+
 #[tauri::command]
 #[cfg(mobile)]
 fn is_desktop() -> bool {
@@ -958,6 +1930,12 @@ fn theme(window: Window) -> Theme {
 #[cfg(desktop)]
 #[tauri::command]
 fn close_splashscreen(app: AppHandle, state: State<ManagedState>) {
+    #[cfg(target_os = "linux")]
+    if let Some(marker) = StartupMarker::new(GPU_WORKAROUND_CONFIG_APP) {
+        if let Err(err) = marker.clear() {
+            log::warn!("Failed to clear the startup marker: {err}");
+        }
+    }
     if let Some(w) = app.get_webview_window("main") {
         let _ = w.show();
         if state.dev_tools_enabled {
@@ -974,6 +1952,17 @@ fn close_splashscreen(app: AppHandle, state: State<ManagedState>) {
     }
 }
 
+/// Returns why the app started differently than usual, once.
+#[cfg(desktop)]
+#[tauri::command]
+fn startup_notice(state: State<ManagedState>) -> Result<Option<String>, String> {
+    let mut notice = state
+        .startup_notice
+        .lock()
+        .map_err(|e| format!("Failed to lock startup notice: {e:?}"))?;
+    Ok(notice.take())
+}
+
 #[cfg(mobile)]
 #[tauri::command]
 fn theme() -> Theme {
@@ -1042,6 +2031,69 @@ struct Args {
         help = "Print diagnostic notes when applying an NVIDIA workaround"
     )]
     nvidia_workaround_verbose: bool,
+    #[cfg(target_os = "linux")]
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Print the findings of the NVIDIA workaround detection as JSON and exit"
+    )]
+    print_gpu_diagnostics: bool,
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Timeout for verifying a service instance, overrides the settings"
+    )]
+    verify_timeout: Option<u64>,
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Interval for checking the mDNS metrics, overrides the settings"
+    )]
+    metrics_interval: Option<u64>,
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Interval for polling network interfaces where change notifications are unavailable, overrides the settings"
+    )]
+    interfaces_interval: Option<u64>,
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Interval for checking IP address changes, overrides the settings"
+    )]
+    ip_check_interval: Option<u64>,
+    #[arg(
+        long,
+        value_name = "MILLIS",
+        help = "Delay between attempts to start browsing, overrides the settings"
+    )]
+    browse_retry_delay: Option<u64>,
+    #[arg(
+        long,
+        value_name = "COUNT",
+        help = "Number of retries to start browsing, overrides the settings"
+    )]
+    browse_retry_attempts: Option<u64>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Profile to activate at startup, instead of the one matching the network"
+    )]
+    profile: Option<String>,
+}
+
+#[cfg(desktop)]
+impl Args {
+    fn timing_overrides(&self) -> TimingOverrides {
+        TimingOverrides {
+            verify_timeout_secs: self.verify_timeout,
+            metrics_check_interval_secs: self.metrics_interval,
+            interfaces_check_interval_secs: self.interfaces_interval,
+            ip_check_interval_secs: self.ip_check_interval,
+            browse_retry_delay_millis: self.browse_retry_delay,
+            browse_retry_attempts: self.browse_retry_attempts,
+        }
+    }
 }
 
 #[cfg(desktop)]
@@ -1166,22 +2218,100 @@ fn create_main_window(app: &AppHandle) -> Result<tauri::WebviewWindow, tauri::Er
     })
 }
 
+/// The directory name of the GPU workaround config files, the app identifier, so the user file
+/// is next to the settings.
+#[cfg(target_os = "linux")]
+const GPU_WORKAROUND_CONFIG_APP: &str = "com.github.hrzlgnm.mdns-browser";
+
+/// Disables the DMABUF renderer if the previous startup did not complete, which is how the
+/// webview crashes documented in webkit2gtk-nvidia-quirk show, and remembers it in the user's
+/// GPU workaround config so it is applied on every start.
+///
+/// Nothing is done if the renderer is already disabled, since then it was not the cause, or if
+/// the config suppresses it. Returns the notice for the user if the workaround was applied.
+#[cfg(target_os = "linux")]
+fn recover_from_crashed_startup(config: &WorkaroundConfig, verbose: bool) -> Option<String> {
+    let marker = StartupMarker::new(GPU_WORKAROUND_CONFIG_APP)?;
+    if !marker.left_behind()
+        || std::env::var_os("WEBKIT_DISABLE_DMABUF_RENDERER").is_some()
+        || config.setting(WorkaroundKind::DisableWebkitDmabufRenderer)
+            == WorkaroundSetting::Suppress
+    {
+        return None;
+    }
+    log::warn!("The previous start did not complete, disabling the dmabuf renderer");
+    set_webkit_disable_dmabuf_renderer(verbose);
+    let remembered = remember_workaround(
+        GPU_WORKAROUND_CONFIG_APP,
+        Some(WorkaroundKind::DisableWebkitDmabufRenderer),
+    );
+    match (remembered, user_config_path(GPU_WORKAROUND_CONFIG_APP)) {
+        (Ok(()), Some(path)) => Some(format!(
+            "The previous start did not complete, so the dmabuf renderer was disabled, which may \
+             reduce rendering performance. To enable it again, remove the \
+             `remembered-workaround` line from {}.",
+            path.display()
+        )),
+        (remembered, _) => {
+            if let Err(err) = remembered {
+                log::error!("Failed to remember the dmabuf renderer workaround: {err}");
+            }
+            Some(
+                "The previous start did not complete, so the dmabuf renderer was disabled for \
+                 this start, which may reduce rendering performance."
+                    .to_string(),
+            )
+        }
+    }
+}
+
+/// Prints the report of the NVIDIA workaround detection for bug reports and exits.
+#[cfg(target_os = "linux")]
+fn print_gpu_diagnostics() -> ! {
+    match serde_json::to_string_pretty(&webkit2gtk_nvidia_quirk::diagnose()) {
+        Ok(report) => {
+            println!("{report}");
+            std::process::exit(0);
+        }
+        Err(err) => {
+            eprintln!("Failed to serialize the GPU diagnostics: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
 #[cfg(desktop)]
 pub fn run() {
     use chrono::Utc;
     use tauri_plugin_log::{Target, TargetKind};
     let args = Args::parse();
+    let timing_overrides = args.timing_overrides();
+    if let Err(err) = timing_overrides.apply(TimingConfig::default()).validate() {
+        Args::command()
+            .error(clap::error::ErrorKind::ValueValidation, err)
+            .exit();
+    }
 
     #[cfg(target_os = "linux")]
-    {
-        if !args.no_nvidia_workaround {
+    let startup_notice = {
+        if args.print_gpu_diagnostics {
+            print_gpu_diagnostics();
+        }
+        if args.no_nvidia_workaround {
+            None
+        } else {
+            let config = WorkaroundConfig::load(GPU_WORKAROUND_CONFIG_APP);
             let options = ApplyWorkaroundOptions::default()
                 .force_disable_dmabuf(args.disable_dmabuf_renderer)
                 .force_disable_nv_explicit_sync(args.disable_nv_explicit_sync)
-                .verbose(args.nvidia_workaround_verbose);
+                .verbose(args.nvidia_workaround_verbose)
+                .config(config.clone());
             apply_workaround_with_options(options);
+            recover_from_crashed_startup(&config, args.nvidia_workaround_verbose)
         }
-    }
+    };
+    #[cfg(not(target_os = "linux"))]
+    let startup_notice = None;
 
     let mut log_targets = vec![
         Target::new(TargetKind::Stdout),
@@ -1191,6 +2321,7 @@ pub fn run() {
         log_targets.push(Target::new(TargetKind::LogDir { file_name: None }));
     }
     let colors = tauri_plugin_log::fern::colors::ColoredLevelConfig::default();
+    let profile = args.profile.clone();
     tauri::Builder::default()
         .plugin(
             tauri_plugin_log::Builder::default()
@@ -1212,8 +2343,30 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .manage(ManagedState::new(args.enable_devtools))
+        .manage(ManagedState::new(
+            args.enable_devtools,
+            timing_overrides,
+            startup_notice,
+        ))
         .setup(move |app| {
+            // Logged here, as the crash recovery runs before logging is set up
+            if let Ok(notice) = app.state::<ManagedState>().startup_notice.lock() {
+                if let Some(notice) = notice.as_deref() {
+                    log::warn!("{notice}");
+                }
+            }
+            load_settings(app.handle());
+            activate_startup_profile(app.handle(), profile.as_deref());
+
+            // Cleared once the frontend closes the splashscreen, so a webview that crashes
+            // during startup is noticed on the next launch.
+            #[cfg(target_os = "linux")]
+            if let Some(marker) = StartupMarker::new(GPU_WORKAROUND_CONFIG_APP) {
+                if let Err(err) = marker.set() {
+                    log::warn!("Failed to set the startup marker: {err}");
+                }
+            }
+
             // The main window is created programmatically (instead of via
             // tauri.conf.json) so its decoration state can be set at creation
             // time. Runtime decoration changes do not take effect on
@@ -1236,17 +2389,38 @@ pub fn run() {
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            activate_profile,
             autoupdate::restart,
             autoupdate::can_auto_update,
             browse_many,
             browse_types,
+            clear_dead_services,
+            clear_services,
             close_splashscreen,
             copy_to_clipboard,
+            delete_profile,
+            get_dead_service_policy,
+            get_profiles,
             get_protocol_flags,
+            get_service,
+            get_timing_config,
+            get_update_check_settings,
+            is_browsing,
             is_desktop,
+            list_services,
+            offers_update,
             open_url,
+            remind_about_update_later,
+            save_profile,
+            services_since,
+            set_dead_service_policy,
             set_interfaces,
             set_protocol_flags,
+            set_timing_config,
+            set_update_check_settings,
+            skip_update_version,
+            start_scheduled_update_check,
+            startup_notice,
             subscribe_interfaces,
             subscribe_metrics,
             stop_browse,
@@ -1262,6 +2436,12 @@ pub fn run() {
 #[tauri::command]
 fn close_splashscreen() {}
 
+#[cfg(mobile)]
+#[tauri::command]
+fn startup_notice() -> Option<String> {
+    None
+}
+
 #[cfg(mobile)]
 #[tauri::command]
 fn can_auto_update() -> bool {
@@ -1289,20 +2469,48 @@ pub fn run_mobile() {
             tauri_plugin_android_update::Builder::new()
                 .owner("hrzlgnm")
                 .repo("mdns-browser")
+                // Same key as the updater pubkey in tauri.conf.json
+                .pubkey("dW50cnVzdGVkIGNvbW1lbnQ6IG1pbmlzaWduIHB1YmxpYyBrZXk6IDIwQzYxQzI2MkI3NUI1RDgKUldUWXRYVXJKaHpHSUEvVmhlVGtNZW5HNXRNZ2xEREF3UkNtbXAxTW0zR0JJUVcveEhMZHFNMjgK")
                 .build(),
         )
         .manage(ManagedState::new())
+        .setup(|app| {
+            load_settings(app.handle());
+            activate_startup_profile(app.handle(), None);
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
+            activate_profile,
             browse_many,
             browse_types,
             can_auto_update,
+            clear_dead_services,
+            clear_services,
             close_splashscreen,
             copy_to_clipboard,
+            delete_profile,
+            get_dead_service_policy,
+            get_profiles,
             get_protocol_flags,
+            get_service,
+            get_timing_config,
+            get_update_check_settings,
+            is_browsing,
             is_desktop,
+            list_services,
+            offers_update,
             open_url,
+            remind_about_update_later,
+            save_profile,
+            services_since,
+            set_dead_service_policy,
             set_interfaces,
             set_protocol_flags,
+            set_timing_config,
+            set_update_check_settings,
+            skip_update_version,
+            start_scheduled_update_check,
+            startup_notice,
             subscribe_interfaces,
             subscribe_metrics,
             stop_browse,
@@ -0,0 +1,207 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+//! Persistent application settings, stored as JSON in the app config directory.
+
+use models::{DeadServicePolicy, ProfileSettings, TimingConfig, UpdateCheckSettings};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub timing: TimingConfig,
+    pub dead_service_policy: DeadServicePolicy,
+    pub update_check: UpdateCheckSettings,
+    pub profiles: ProfileSettings,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    #[error("Failed to determine the config directory: {0}")]
+    ConfigDir(#[from] tauri::Error),
+    #[error("Failed to access the settings file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to (de)serialize the settings: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Timing overrides given on the command line.
+///
+/// Overrides take precedence over the settings file, but are never persisted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimingOverrides {
+    pub verify_timeout_secs: Option<u64>,
+    pub metrics_check_interval_secs: Option<u64>,
+    pub interfaces_check_interval_secs: Option<u64>,
+    pub ip_check_interval_secs: Option<u64>,
+    pub browse_retry_delay_millis: Option<u64>,
+    pub browse_retry_attempts: Option<u64>,
+}
+
+impl TimingOverrides {
+    pub fn apply(&self, timing: TimingConfig) -> TimingConfig {
+        TimingConfig {
+            verify_timeout_secs: self
+                .verify_timeout_secs
+                .unwrap_or(timing.verify_timeout_secs),
+            metrics_check_interval_secs: self
+                .metrics_check_interval_secs
+                .unwrap_or(timing.metrics_check_interval_secs),
+            interfaces_check_interval_secs: self
+                .interfaces_check_interval_secs
+                .unwrap_or(timing.interfaces_check_interval_secs),
+            ip_check_interval_secs: self
+                .ip_check_interval_secs
+                .unwrap_or(timing.ip_check_interval_secs),
+            browse_retry_delay_millis: self
+                .browse_retry_delay_millis
+                .unwrap_or(timing.browse_retry_delay_millis),
+            browse_retry_attempts: self
+                .browse_retry_attempts
+                .unwrap_or(timing.browse_retry_attempts),
+        }
+    }
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, SettingsError> {
+    Ok(app.path().app_config_dir()?.join(SETTINGS_FILE_NAME))
+}
+
+/// Reads the settings from `path`, a missing file yields the default settings.
+fn load_from(path: &Path) -> Result<Settings, SettingsError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Settings::default()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn save_to(path: &Path, settings: &Settings) -> Result<(), SettingsError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(settings)?)?;
+    Ok(())
+}
+
+/// Loads the settings of the app.
+///
+/// Falls back to the defaults for anything that can not be read or does not pass validation, so
+/// a broken settings file never prevents the app from starting.
+pub fn load(app: &AppHandle) -> Settings {
+    let mut settings = settings_path(app)
+        .and_then(|path| load_from(&path))
+        .unwrap_or_else(|err| {
+            log::warn!("Failed to load settings: {err}, using defaults");
+            Settings::default()
+        });
+    if let Err(err) = settings.timing.validate() {
+        log::warn!("Invalid timing settings: {err}, using defaults");
+        settings.timing = TimingConfig::default();
+    }
+    if let Err(err) = settings.dead_service_policy.validate() {
+        log::warn!("Invalid dead service policy: {err}, using defaults");
+        settings.dead_service_policy = DeadServicePolicy::default();
+    }
+    if let Err(err) = settings.update_check.validate() {
+        log::warn!("Invalid update check settings: {err}, using defaults");
+        settings.update_check = UpdateCheckSettings::default();
+    }
+    settings.profiles.profiles.retain(|profile| {
+        let valid = profile.validate();
+        if let Err(err) = &valid {
+            log::warn!("Invalid profile {}: {err}, dropping it", profile.name);
+        }
+        valid.is_ok()
+    });
+    if settings.profiles.active_profile().is_none() {
+        settings.profiles.active = None;
+    }
+    settings
+}
+
+pub fn save(app: &AppHandle, settings: &Settings) -> Result<(), SettingsError> {
+    save_to(&settings_path(app)?, settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::Profile;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "mdns-browser-settings-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_load_from_missing_file_yields_defaults() {
+        let dir = temp_dir("missing");
+        let settings = load_from(&dir.join(SETTINGS_FILE_NAME)).expect("To load defaults");
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = temp_dir("round-trip");
+        let path = dir.join(SETTINGS_FILE_NAME);
+        let settings = Settings {
+            timing: TimingConfig {
+                verify_timeout_secs: 30,
+                ..Default::default()
+            },
+            dead_service_policy: DeadServicePolicy {
+                hide_after_secs: Some(30),
+                purge_after_mins: None,
+            },
+            update_check: UpdateCheckSettings {
+                skipped_version: Some("2.0.0".to_string()),
+                ..Default::default()
+            },
+            profiles: ProfileSettings {
+                profiles: vec![Profile {
+                    name: "office".to_string(),
+                    auto_activate: vec!["192.168.10.0/24".to_string()],
+                    ..Default::default()
+                }],
+                active: Some("office".to_string()),
+            },
+        };
+        save_to(&path, &settings).expect("To save settings");
+        assert_eq!(load_from(&path).expect("To load settings"), settings);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_from_malformed_file_fails() {
+        let dir = temp_dir("malformed");
+        let path = dir.join(SETTINGS_FILE_NAME);
+        std::fs::create_dir_all(&dir).expect("To create dir");
+        std::fs::write(&path, "{ not json").expect("To write file");
+        assert!(matches!(load_from(&path), Err(SettingsError::Json(_))));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_timing_overrides_take_precedence() {
+        let overrides = TimingOverrides {
+            verify_timeout_secs: Some(60),
+            ..Default::default()
+        };
+        let timing = overrides.apply(TimingConfig {
+            verify_timeout_secs: 10,
+            metrics_check_interval_secs: 5,
+            ..Default::default()
+        });
+        assert_eq!(timing.verify_timeout_secs, 60);
+        assert_eq!(timing.metrics_check_interval_secs, 5);
+    }
+}
@@ -5,14 +5,15 @@ use leptos::prelude::*;
 use leptos::task::spawn_local;
 use models::*;
 use serde::{Deserialize, Serialize};
-use shared_constants::{GITHUB_BASE_URL, SHOW_NO_UPDATE_DURATION};
+use shared_constants::{GITHUB_BASE_URL, SHOW_NO_UPDATE_DURATION, UPDATE_CHECK_TICK};
 use tauri_sys::core::{Channel, invoke, invoke_result};
 use thaw::{
     Accordion, AccordionHeader, AccordionItem, Button, ButtonAppearance, ButtonSize, Flex, Layout,
-    Text, Toast, ToastBody, ToastTitle, ToasterInjection,
+    Text, Toast, ToastBody, ToastFooter, ToastTitle, ToasterInjection,
 };
 
 use super::is_desktop::IsDesktopInjection;
+use super::update_check_settings::UpdateCheckSchedule;
 use futures::StreamExt;
 
 /// The metadata `plugin:updater|check` resolves to. On desktop the app uses the
@@ -24,6 +25,8 @@ struct UpdaterMetadata {
     rid: u32,
     version: String,
     current_version: String,
+    body: Option<String>,
+    date: Option<String>,
 }
 
 impl From<UpdaterMetadata> for UpdateMetadata {
@@ -31,13 +34,15 @@ impl From<UpdaterMetadata> for UpdateMetadata {
         Self {
             version: metadata.version,
             current_version: metadata.current_version,
+            notes: metadata.body,
+            pub_date: metadata.date,
         }
     }
 }
 
-/// The progress events `plugin:updater|download_and_install` emits on its
-/// channel. The app does not surface progress, but the command requires the
-/// channel argument.
+/// The progress events `plugin:updater|download_and_install` and
+/// `plugin:android-update|download_and_install` emit on their channel. The app
+/// does not surface progress, but the commands require the channel argument.
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "event", content = "data")]
 enum DownloadEvent {
@@ -104,6 +109,35 @@ struct DownloadArgs {
     on_event: ChannelRef,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AndroidDownloadArgs {
+    on_event: ChannelRef,
+}
+
+/// Creates a channel for download progress events, logged as they arrive.
+fn log_download_progress() -> ChannelRef {
+    let on_event = Channel::<DownloadEvent>::new();
+    let on_event_arg = ChannelRef(on_event.id());
+    spawn_local(async move {
+        let mut events = on_event;
+        while let Some(event) = events.next().await {
+            match event {
+                DownloadEvent::Started { content_length } => {
+                    log::info!("update download started: {content_length:?}");
+                }
+                DownloadEvent::Progress { chunk_length } => {
+                    log::info!("update download progress: {chunk_length}");
+                }
+                DownloadEvent::Finished => {
+                    log::info!("update download finished");
+                }
+            }
+        }
+    });
+    on_event_arg
+}
+
 /// Resolves to the update metadata and, on desktop, the resource id of the
 /// pending update for [`download_and_install`].
 async fn check_update(is_desktop: bool) -> Result<(Option<UpdateMetadata>, Option<u32>), String> {
@@ -125,36 +159,98 @@ async fn download_and_install(is_desktop: bool, rid: Option<u32>) -> Result<(),
         let Some(rid) = rid else {
             return Err("there is no pending update".to_string());
         };
-        let on_event = Channel::<DownloadEvent>::new();
-        let on_event_arg = ChannelRef(on_event.id());
-        spawn_local(async move {
-            let mut events = on_event;
-            while let Some(event) = events.next().await {
-                match event {
-                    DownloadEvent::Started { content_length } => {
-                        log::info!("update download started: {content_length:?}");
-                    }
-                    DownloadEvent::Progress { chunk_length } => {
-                        log::info!("update download progress: {chunk_length}");
-                    }
-                    DownloadEvent::Finished => {
-                        log::info!("update download finished");
-                    }
-                }
-            }
-        });
         invoke_result::<(), String>(
             "plugin:updater|download_and_install",
             &DownloadArgs {
                 rid,
-                on_event: on_event_arg,
+                on_event: log_download_progress(),
             },
         )
         .await?;
         let _ = invoke_result::<(), String>("restart", &()).await;
         Ok(())
     } else {
-        invoke_result::<(), String>("plugin:android-update|download_and_install", &()).await
+        invoke_result::<(), String>(
+            "plugin:android-update|download_and_install",
+            &AndroidDownloadArgs {
+                on_event: log_download_progress(),
+            },
+        )
+        .await
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct VersionArgs<'a> {
+    version: &'a str,
+}
+
+/// Runs a scheduled update check if one is due, resolving to the update if the user did not skip
+/// it or ask to be reminded later.
+async fn scheduled_update_check(
+    is_desktop: bool,
+) -> Result<Option<(UpdateMetadata, Option<u32>)>, String> {
+    if !invoke_result::<bool, String>("start_scheduled_update_check", &()).await? {
+        return Ok(None);
+    }
+    let (update, rid) = check_update(is_desktop).await?;
+    let Some(update) = update else {
+        return Ok(None);
+    };
+    let offered = invoke_result::<bool, String>(
+        "offers_update",
+        &VersionArgs {
+            version: &update.version,
+        },
+    )
+    .await?;
+    if !offered {
+        log::info!("update to {} was skipped or postponed", update.version);
+        return Ok(None);
+    }
+    Ok(Some((update, rid)))
+}
+
+fn create_update_available_toast(
+    version: String,
+    install_label: &'static str,
+    install: Action<(), ()>,
+    remind_later: Action<(), ()>,
+    skip: Action<String, ()>,
+) -> impl IntoView {
+    let skipped_version = version.clone();
+    view! {
+        <Toast>
+            <ToastTitle>"Update available"</ToastTitle>
+            <ToastBody>{format!("Version {version} is available.")}</ToastBody>
+            <ToastFooter>
+                <Button
+                    appearance=ButtonAppearance::Primary
+                    size=ButtonSize::Small
+                    on_click=move |_| {
+                        install.dispatch(());
+                    }
+                >
+                    {install_label}
+                </Button>
+                <Button
+                    size=ButtonSize::Small
+                    on_click=move |_| {
+                        remind_later.dispatch(());
+                    }
+                >
+                    "Remind me later"
+                </Button>
+                <Button
+                    size=ButtonSize::Small
+                    on_click=move |_| {
+                        skip.dispatch(skipped_version.clone());
+                    }
+                >
+                    "Skip this version"
+                </Button>
+            </ToastFooter>
+        </Toast>
     }
 }
 
@@ -233,12 +329,92 @@ pub fn About() -> impl IntoView {
         }
     });
 
+    let remind_later_action = Action::new_local(move |_: &()| async move {
+        if let Err(e) = invoke_result::<(), String>("remind_about_update_later", &()).await {
+            log::error!("failed to postpone update: {e}");
+        }
+    });
+
+    let skip_version_action = Action::new_local(move |version: &String| {
+        let version = version.clone();
+        async move {
+            if let Err(e) = invoke_result::<(), String>(
+                "skip_update_version",
+                &VersionArgs { version: &version },
+            )
+            .await
+            {
+                log::error!("failed to skip update: {e}");
+            }
+            set_pending_rid.set(None);
+            set_update.set(None);
+        }
+    });
+
+    let scheduled_check_action = Action::new_local(move |_: &()| async move {
+        let is_desktop = is_desktop.get_untracked();
+        match scheduled_update_check(is_desktop).await {
+            Ok(Some((metadata, rid))) => {
+                let version = metadata.version.clone();
+                set_pending_rid.set(rid);
+                set_update.set(Some(metadata));
+                let install_label = if is_desktop {
+                    "Download and Install"
+                } else {
+                    "Open release page"
+                };
+                toaster.dispatch_toast(
+                    move || {
+                        create_update_available_toast(
+                            version,
+                            install_label,
+                            download_and_install_action,
+                            remind_later_action,
+                            skip_version_action,
+                        )
+                    },
+                    Default::default(),
+                );
+            }
+            Ok(None) => {}
+            // Background checks fail quietly, e.g. while offline
+            Err(e) => log::warn!("scheduled update check failed: {e}"),
+        }
+    });
+
+    // Checks once the updater is known to be usable, and then every tick, the backend decides
+    // whether a check is due
+    Effect::new(move |_| {
+        if can_auto_update.get() {
+            scheduled_check_action.dispatch(());
+        }
+    });
+    if let Ok(handle) = set_interval_with_handle(
+        move || {
+            if can_auto_update.get_untracked() {
+                scheduled_check_action.dispatch(());
+            }
+        },
+        UPDATE_CHECK_TICK,
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
     let update_available = Signal::derive(move || update.get().is_some());
     let installable_version = Signal::derive(move || {
         update
             .get()
             .map_or_else(|| None, |metadata| Some(metadata.version))
     });
+    let update_notes = Signal::derive(move || {
+        update.get().and_then(|metadata| {
+            let notes = metadata.notes.filter(|notes| !notes.trim().is_empty())?;
+            Some(match metadata.pub_date {
+                Some(pub_date) => format!("{} ({pub_date})\n\n{notes}", metadata.version),
+                None => format!("{}\n\n{notes}", metadata.version),
+            })
+        })
+    });
     let on_install_update_click = move |_| {
         download_and_install_action.dispatch(());
     };
@@ -376,6 +552,24 @@ pub fn About() -> impl IntoView {
                             </Show>
                         </Show>
                     </Flex>
+                    <Show
+                        when=move || update_notes.with(|notes| notes.is_some())
+                        fallback=move || {
+                            view! { <div class="hidden" /> }
+                        }
+                    >
+                        <Text class="update-release-notes">
+                            {move || update_notes.get().unwrap_or_default()}
+                        </Text>
+                    </Show>
+                    <Show
+                        when=move || { can_auto_update.get() }
+                        fallback=move || {
+                            view! { <div class="hidden" /> }
+                        }
+                    >
+                        <UpdateCheckSchedule />
+                    </Show>
                 </AccordionItem>
             </Accordion>
         </Layout>
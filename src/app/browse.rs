@@ -2,21 +2,25 @@
 // SPDX-License-Identifier: MIT-0
 
 use chrono::{DateTime, Local};
+use futures::StreamExt;
 use leptos::prelude::*;
 use leptos::task::spawn_local;
 use models::*;
 use reactive_stores::{Field, Store, StoreFieldIterator};
 use serde::{Deserialize, Serialize};
-use shared_constants::{AUTO_COMPLETE_AUTO_FOCUS_DELAY, VERIFY_TIMEOUT};
+use shared_constants::AUTO_COMPLETE_AUTO_FOCUS_DELAY;
 use std::collections::HashSet;
 use strsim::jaro_winkler;
-use tauri_sys::core::invoke;
+use tauri_sys::{
+    core::{invoke, invoke_result},
+    event::listen,
+};
 use thaw::{
-    AutoComplete, AutoCompleteOption, AutoCompleteRef, AutoCompleteSize, Badge, BadgeAppearance,
-    BadgeColor, BadgeSize, Button, ButtonAppearance, ButtonSize, Card, CardHeader, CardPreview,
-    ComponentRef, Dialog, DialogBody, DialogSurface, Flex, FlexAlign, FlexGap, FlexJustify, Grid,
-    GridItem, Icon, Input, Layout, Scrollbar, Select, Table, TableBody, TableCell, TableRow, Text,
-    TextTag,
+    Accordion, AccordionHeader, AccordionItem, AutoComplete, AutoCompleteOption, AutoCompleteRef,
+    AutoCompleteSize, Badge, BadgeAppearance, BadgeColor, BadgeSize, Button, ButtonAppearance,
+    ButtonSize, Card, CardHeader, CardPreview, ComponentRef, Dialog, DialogBody, DialogSurface,
+    Flex, FlexAlign, FlexGap, FlexJustify, Grid, GridItem, Icon, Input, Layout, Scrollbar, Select,
+    Table, TableBody, TableCell, TableRow, Text, TextTag,
 };
 
 use super::{
@@ -24,12 +28,18 @@ use super::{
     backtop::BackTop,
     clipboard::CopyToClipBoardButton,
     css::get_class,
+    dead_services::{DeadServicePolicyInjection, now_micros},
+    interface_matrix::InterfaceMatrix,
     invoke::invoke_no_args,
     is_desktop::IsDesktopInjection,
-    listen::{listen_add_remove, listen_events},
+    listen::listen_events,
     network_interfaces::HasEnabledInterfacesInjection,
+    profiles::ProfilesInjection,
     protocol_flags::ProtocolFlags,
-    values_table::ValuesTable,
+    service_table::ServiceTable,
+    timing_settings::TimingConfigInjection,
+    values_table::{TxtRecordsTable, ValuesTable},
+    virtual_list::{VirtualLayout, use_visible_range},
 };
 
 /// Injection providing a signal that tracks whether a browse is currently active.
@@ -60,31 +70,55 @@ pub(crate) async fn browse_types() {
     invoke_no_args("browse_types").await;
 }
 
-/// Listens for service type discovery events and updates the provided signal accordingly.
+/// Adds a value to a sorted list of unique values.
+fn insert_sorted_unique(writer: WriteSignal<ServiceTypes>, value: String) {
+    writer.update(|values| {
+        if let Err(position) = values.binary_search(&value) {
+            values.insert(position, value);
+        }
+    });
+}
+
+/// Listens for service type and subtype discovery events and updates the provided signals accordingly.
 ///
-/// This function reacts to `"service-type-found"` events, ensuring the signal
-/// contains a unique, sorted list of service types discovered on the network.
-async fn listen_to_service_type_events(writer: WriteSignal<ServiceTypes>) {
+/// This function reacts to `"service-type-found"` and `"service-subtype-found"` events, ensuring
+/// the signals contain unique, sorted lists of the service types and subtypes discovered on the
+/// network. Subtypes are kept apart, so browsing all service types does not browse them twice.
+async fn listen_to_service_type_events(
+    writer: WriteSignal<ServiceTypes>,
+    subtype_writer: WriteSignal<ServiceTypes>,
+) {
+    // Listening before browsing starts, so no subtype found early is missed
+    match listen::<ServiceSubtypeFoundEvent>("service-subtype-found").await {
+        Ok(mut events) => spawn_local(async move {
+            while let Some(event) = events.next().await {
+                insert_sorted_unique(subtype_writer, event.payload.subtype);
+            }
+        }),
+        Err(err) => log::error!("Failed to listen to event: service-subtype-found. Error: {err:?}"),
+    }
     listen_events(
         browse_types,
         "service-type-found",
-        move |event: ServiceTypeFoundEvent| {
-            let mut set = HashSet::new();
-            writer.update(|sts| {
-                sts.push(event.service_type);
-                sts.retain(|st| set.insert(st.clone()));
-                sts.sort();
-            });
-        },
+        move |event: ServiceTypeFoundEvent| insert_sorted_unique(writer, event.service_type),
     )
     .await;
 }
 
+/// Orders service types and subtypes, so subtypes follow the service type they belong to.
+fn service_type_order_key(service_type: &str) -> (&str, bool, &str) {
+    match subtype_service_type(service_type) {
+        Some(parent) => (parent, true, service_type),
+        None => (service_type, false, service_type),
+    }
+}
+
 #[derive(Store, Default)]
 struct Resolved {
     #[store(key: String = |rs| rs.instance_fullname.clone())]
     services: Vec<ResolvedService>,
     sort_by: SortKind,
+    then_by: Vec<SortKind>,
     query: String,
 }
 
@@ -94,7 +128,7 @@ struct Filtered {
     services: Vec<ResolvedService>,
 }
 
-fn to_local_timestamp(timestamp_micros: u64) -> String {
+pub fn to_local_timestamp(timestamp_micros: u64) -> String {
     DateTime::from_timestamp_micros(timestamp_micros as i64)
         .map(|dt| {
             dt.with_timezone(&Local)
@@ -104,48 +138,74 @@ fn to_local_timestamp(timestamp_micros: u64) -> String {
         .unwrap_or_else(|| "Invalid timestamp".to_string())
 }
 
-/// Listens for service resolution and removal events, updating the store accordingly.
+/// Inserts or updates a resolved service in the store.
 ///
-/// On receiving a `"service-resolved"` event, updates or inserts the resolved service and
-/// reapplies sorting.  On receiving a `"service-removed"` event, marks the corresponding service as dead
-/// Listens for service resolution and removal events, updating the resolved services store accordingly.
+/// Services older than the stored version are ignored, so a snapshot from the backend and live
+/// events can be applied in any order.
+fn upsert_service(store: Store<Resolved>, mut service: ResolvedService) {
+    if let Some(rs) = store
+        .services()
+        .iter_unkeyed()
+        .find(|rs| rs.read_untracked().instance_fullname == service.instance_fullname)
+    {
+        if rs.read_untracked().updated_at_micros > service.updated_at_micros {
+            return;
+        }
+        service.merge_sightings(&rs.read_untracked().sightings);
+        // Only update if fields other than timestamps have changed
+        if !rs.read().matches_except_updated_at(&service) {
+            *rs.write() = service;
+        } else {
+            rs.sightings().set(service.sightings);
+        }
+    } else {
+        store.services().write().push(service);
+    }
+}
+
+/// Restores the services and the browsing state kept by the backend.
 ///
-/// On receiving a service resolution event, updates or inserts the resolved service and reapplies sorting.
-/// On receiving a service removal event, marks the corresponding service as dead with a timestamp and reapplies sorting.
-/// This function operates asynchronously and reacts to network events in real time.
+/// The backend owns the service table, so a frontend reload continues where it left off.
+async fn hydrate_from_backend(store: Store<Resolved>, browsing: RwSignal<bool>) {
+    match invoke_result::<Vec<ResolvedService>, String>("list_services", &()).await {
+        Ok(services) => {
+            for service in services {
+                upsert_service(store, service);
+            }
+            apply_sort(store);
+        }
+        Err(e) => log::error!("failed to list services: {e}"),
+    }
+    match invoke_result::<bool, String>("is_browsing", &()).await {
+        Ok(is_browsing) => browsing.set(is_browsing),
+        Err(e) => log::error!("failed to get browsing state: {e}"),
+    }
+}
+
+/// Listens for batches of service resolutions and removals, updating the store accordingly.
+///
+/// Resolved services of a batch are updated or inserted before removed services are marked as
+/// dead with their timestamp, sorting is reapplied once per batch.
 async fn listen_for_resolve_events(store: Store<Resolved>) {
-    listen_add_remove(
+    listen_events(
         async || {},
-        "service-resolved",
-        move |event: ServiceResolvedEvent| {
-            store
-                .services()
-                .iter_unkeyed()
-                .find(|rs| rs.read_untracked().instance_fullname == event.service.instance_fullname)
-                .map(|rs| {
-                    // Only update if fields other than timestamp have changed
-                    if !rs.read().matches_except_updated_at(&event.service) {
-                        *rs.write() = event.service.clone();
-                    }
-                })
-                .unwrap_or_else(|| {
-                    store.services().write().push(event.service.clone());
-                });
-            // TODO: Replace by a binary search insert replace
-            apply_sort_kind(store, &store.sort_by().get_untracked());
-        },
-        "service-removed",
-        move |event: ServiceRemovedEvent| {
-            if let Some(rs) = store.services().iter_unkeyed().find(|rs| {
-                let rs = rs.read_untracked();
-                rs.instance_fullname == event.instance_name && !rs.dead
-            }) {
-                let mut dead = rs.read().clone();
-                dead.die_at(event.at_micros);
-                *rs.write() = dead;
-                // TODO: Replace by a binary search insert replace
-                apply_sort_kind(store, &store.sort_by().get_untracked());
+        "services-batch",
+        move |batch: ServicesBatchEvent| {
+            for service in batch.resolved {
+                upsert_service(store, service);
             }
+            for removed in batch.removed {
+                if let Some(rs) = store.services().iter_unkeyed().find(|rs| {
+                    let rs = rs.read_untracked();
+                    rs.instance_fullname == removed.instance_name && !rs.dead
+                }) {
+                    let mut dead = rs.read().clone();
+                    dead.die_at(removed.at_micros);
+                    *rs.write() = dead;
+                }
+            }
+            // TODO: Replace by a binary search insert replace
+            apply_sort(store);
         },
     )
     .await;
@@ -171,6 +231,14 @@ async fn stop_browse() {
     let _ = invoke_no_args("stop_browse").await;
 }
 
+async fn clear_services() {
+    let _ = invoke_no_args("clear_services").await;
+}
+
+async fn clear_dead_services() {
+    let _ = invoke_no_args("clear_dead_services").await;
+}
+
 #[derive(Serialize, Deserialize)]
 #[allow(non_snake_case)]
 struct VerifyArgs<'a> {
@@ -216,6 +284,7 @@ fn get_prefix(s: &str) -> &str {
 /// Component that auto completes service types
 #[component]
 fn AutoCompleteServiceType(
+    #[prop(into)] subtypes: Signal<ServiceTypes>,
     #[prop(optional, into)] value: RwSignal<String>,
     #[prop(optional, into)] disabled: Signal<bool>,
     #[prop(optional, into)] invalid: Signal<bool>,
@@ -223,17 +292,22 @@ fn AutoCompleteServiceType(
 ) -> impl IntoView {
     let service_types = ServiceTypesInjection::expect_context();
     let service_type_options = Memo::<Vec<_>>::new(move |_| {
-        service_types
+        let mut options = service_types
             .get()
             .into_iter()
+            .chain(subtypes.get())
             .filter(|s| {
                 let input = value.get();
                 let lookup = get_prefix(input.as_str());
-                let prefix = get_prefix(s.split('.').next().unwrap_or(s));
+                // Subtypes are offered along with the service type they belong to
+                let service_type = subtype_service_type(s).unwrap_or(s);
+                let prefix = get_prefix(service_type.split('.').next().unwrap_or(service_type));
                 jaro_winkler(lookup, prefix) >= 0.75 || is_subsequence(lookup, prefix)
             })
             .map(|service_type| (service_type.to_string(), service_type.to_string()))
-            .collect()
+            .collect::<Vec<_>>();
+        options.sort_by(|a, b| service_type_order_key(&a.0).cmp(&service_type_order_key(&b.0)));
+        options
     });
 
     let is_desktop = IsDesktopInjection::expect_context();
@@ -262,10 +336,62 @@ fn AutoCompleteServiceType(
     }
 }
 
+/// Component that explains why a service type is invalid and offers a correction
+#[component]
+fn ServiceTypeDiagnostics(
+    #[prop(into)] lint: Signal<ServiceTypeLint>,
+    value: RwSignal<String>,
+) -> impl IntoView {
+    let on_suggestion_click = move |_| {
+        if let Some(suggestion) = lint.get_untracked().suggestion {
+            value.set(suggestion);
+        }
+    };
+    view! {
+        <Show when=move || !lint.with(|lint| lint.diagnostics.is_empty())>
+            <Flex vertical=true gap=FlexGap::Small>
+                <For
+                    each=move || lint.get().diagnostics
+                    key=|diagnostic| diagnostic.to_string()
+                    let:diagnostic
+                >
+                    <Text class=match diagnostic.severity {
+                        LintSeverity::Error => "service-type-diagnostic-error",
+                        LintSeverity::Warning => "service-type-diagnostic-warning",
+                    }>{diagnostic.to_string()}</Text>
+                </For>
+                <Show when=move || lint.with(|lint| lint.suggestion.is_some())>
+                    <Flex justify=FlexJustify::Start>
+                        <Button
+                            appearance=ButtonAppearance::Subtle
+                            size=ButtonSize::Small
+                            on_click=on_suggestion_click
+                        >
+                            {move || {
+                                format!("Use {}", lint.get().suggestion.unwrap_or_default())
+                            }}
+                        </Button>
+                    </Flex>
+                </Show>
+            </Flex>
+        </Show>
+    }
+}
+
 fn drop_trailing_dot(fqn: &str) -> String {
     fqn.strip_suffix(".").unwrap_or(fqn).to_owned()
 }
 
+/// Labels a subtype in the subtype filter by its subtype label, followed by its service type.
+fn subtype_option_label(subtype: &str) -> String {
+    match (subtype_label(subtype), subtype_service_type(subtype)) {
+        (Some(label), Some(service_type)) => {
+            format!("{label} ({})", drop_local_and_trailing_dot(service_type))
+        }
+        _ => drop_local_and_trailing_dot(subtype),
+    }
+}
+
 /// Removes a trailing ".local." suffix and any trailing dot from the provided string.
 ///
 /// If the input ends with ".local.", that suffix is removed. Afterwards, any trailing dot is also removed.
@@ -279,7 +405,7 @@ fn drop_trailing_dot(fqn: &str) -> String {
 /// let alias = drop_local_and_trailing_dot("service.");
 /// assert_eq!(alias, "service");
 /// ```
-fn drop_local_and_trailing_dot(fqn: &str) -> String {
+pub fn drop_local_and_trailing_dot(fqn: &str) -> String {
     let without_local = fqn.strip_suffix(".local.").unwrap_or(fqn);
     drop_trailing_dot(without_local)
 }
@@ -446,6 +572,7 @@ fn ResolvedServiceItem(
             resolved_service.instance_fullname().track();
             resolved_service.dead().track();
             resolved_service.subtype().track();
+            resolved_service.sightings().track();
         })
     });
 
@@ -454,6 +581,7 @@ fn ResolvedServiceItem(
         async move { verify_instance(instance_fullname.clone()).await }
     });
     let verifying = RwSignal::new(false);
+    let timing = TimingConfigInjection::expect_context();
     let on_verify_click = move |_| {
         verifying.set(true);
         if let Some(instance_fullname) =
@@ -465,7 +593,7 @@ fn ResolvedServiceItem(
             move || {
                 verifying.set(false);
             },
-            VERIFY_TIMEOUT,
+            timing.get_untracked().verify_timeout(),
         )
     };
 
@@ -510,9 +638,16 @@ fn ResolvedServiceItem(
             .unwrap_or_default()
     });
 
-    let txts = Memo::new(move |_| {
+    let txts = Memo::new(move |_| rs.get().map(|rs| rs.txt).unwrap_or_default());
+
+    let txt_warnings = Memo::new(move |_| {
         rs.get()
-            .map(|rs| rs.txt.iter().map(|t| t.to_string()).collect::<Vec<_>>())
+            .map(|rs| {
+                rs.txt_warnings()
+                    .iter()
+                    .map(|w| w.to_string())
+                    .collect::<Vec<_>>()
+            })
             .unwrap_or_default()
     });
 
@@ -525,6 +660,35 @@ fn ResolvedServiceItem(
             .unwrap_or_default()
     });
 
+    let interfaces = Memo::new(move |_| {
+        rs.get()
+            .map(|rs| {
+                rs.sightings
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default()
+    });
+
+    let sightings = Memo::new(move |_| {
+        rs.get()
+            .map(|rs| {
+                rs.sightings
+                    .iter()
+                    .map(|s| {
+                        format!(
+                            "{s} first seen {} last seen {}",
+                            to_local_timestamp(s.first_seen_micros),
+                            to_local_timestamp(s.last_seen_micros)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    });
+
     let title = Memo::new(move |_| {
         rs.get()
             .map(|rs| rs.get_instance_name())
@@ -577,7 +741,11 @@ fn ResolvedServiceItem(
             .unwrap_or_default()
     });
 
-    let instance_fullname = resolved_service.instance_fullname();
+    let instance_fullname = Memo::new(move |_| {
+        rs.get()
+            .map(|rs| rs.get_escaped_instance_fullname())
+            .unwrap_or_default()
+    });
     let service_type = resolved_service.service_type();
     let service_type_display = Memo::new(move |_| {
         service_type
@@ -628,6 +796,11 @@ fn ResolvedServiceItem(
                                 button_text=first_address_display
                                 copy_text=first_address_for_copy
                             />
+                            <ResolvedRow
+                                label="Interfaces"
+                                text=interfaces
+                                button_text=interfaces
+                            />
                             <ResolvedRow
                                 label="Updated at"
                                 text=updated_at
@@ -643,6 +816,23 @@ fn ResolvedServiceItem(
                                     >
                                         "Details"
                                     </Button>
+                                    <Show when=move || !txt_warnings.with(|w| w.is_empty())>
+                                        <Badge
+                                            appearance=BadgeAppearance::Tint
+                                            size=BadgeSize::Small
+                                            color=BadgeColor::Warning
+                                            attr:title=move || txt_warnings.get().join("\n")
+                                        >
+                                            {move || {
+                                                let count = txt_warnings.with(|w| w.len());
+                                                if count == 1 {
+                                                    "1 TXT warning".to_string()
+                                                } else {
+                                                    format!("{count} TXT warnings")
+                                                }
+                                            }}
+                                        </Badge>
+                                    </Show>
                                     <Dialog open=show_details>
                                         <DialogSurface>
                                             <DialogBody class="resolved-service-details-dialog-body">
@@ -674,7 +864,15 @@ fn ResolvedServiceItem(
                                                                 title="IPs".to_string()
                                                                 copy_values=addrs_for_copy
                                                             />
-                                                            <ValuesTable values=txts title="TXT".to_string() />
+                                                            <ValuesTable
+                                                                values=sightings
+                                                                title="Interfaces".to_string()
+                                                            />
+                                                            <TxtRecordsTable records=txts />
+                                                            <ValuesTable
+                                                                values=txt_warnings
+                                                                title="TXT warnings".to_string()
+                                                            />
                                                         </Flex>
                                                     </Scrollbar>
                                                 </Flex>
@@ -724,8 +922,8 @@ impl ServiceTypesInjection {
     }
 }
 
-#[derive(Clone, Debug, Default)]
-enum SortKind {
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortKind {
     InstanceAsc,
     InstanceDesc,
     #[default]
@@ -741,6 +939,87 @@ enum SortKind {
     TimestampDesc,
 }
 
+impl SortKind {
+    const ALL: [SortKind; 12] = [
+        SortKind::InstanceAsc,
+        SortKind::InstanceDesc,
+        SortKind::HostnameAsc,
+        SortKind::HostnameDesc,
+        SortKind::PortAsc,
+        SortKind::PortDesc,
+        SortKind::ServiceTypeAsc,
+        SortKind::ServiceTypeDesc,
+        SortKind::IpAddrAsc,
+        SortKind::IpAddrDesc,
+        SortKind::TimestampAsc,
+        SortKind::TimestampDesc,
+    ];
+
+    /// The value of the sort kind in the sort by select.
+    fn value(&self) -> &'static str {
+        match self {
+            SortKind::InstanceAsc => "InstanceAsc",
+            SortKind::InstanceDesc => "InstanceDesc",
+            SortKind::HostnameAsc => "HostnameAsc",
+            SortKind::HostnameDesc => "HostnameDesc",
+            SortKind::PortAsc => "PortAsc",
+            SortKind::PortDesc => "PortDesc",
+            SortKind::ServiceTypeAsc => "ServiceTypeAsc",
+            SortKind::ServiceTypeDesc => "ServiceTypeDesc",
+            SortKind::IpAddrAsc => "IpAddrAsc",
+            SortKind::IpAddrDesc => "IpAddrDesc",
+            SortKind::TimestampAsc => "TimestampAsc",
+            SortKind::TimestampDesc => "TimestampDesc",
+        }
+    }
+
+    fn from_value(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.value() == value)
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            SortKind::InstanceAsc => "Instance (Ascending)",
+            SortKind::InstanceDesc => "Instance (Descending)",
+            SortKind::HostnameAsc => "Hostname (Ascending)",
+            SortKind::HostnameDesc => "Hostname (Descending)",
+            SortKind::PortAsc => "Port (Ascending)",
+            SortKind::PortDesc => "Port (Descending)",
+            SortKind::ServiceTypeAsc => "Service Type (Ascending)",
+            SortKind::ServiceTypeDesc => "Service Type (Descending)",
+            SortKind::IpAddrAsc => "IP (Ascending)",
+            SortKind::IpAddrDesc => "IP (Descending)",
+            SortKind::TimestampAsc => "Last Updated (Ascending)",
+            SortKind::TimestampDesc => "Last Updated (Descending)",
+        }
+    }
+
+    fn criterion(&self) -> SortCriterion {
+        match self {
+            SortKind::InstanceAsc => SortCriterion::ascending(SortField::Instance),
+            SortKind::InstanceDesc => SortCriterion::descending(SortField::Instance),
+            SortKind::HostnameAsc => SortCriterion::ascending(SortField::Hostname),
+            SortKind::HostnameDesc => SortCriterion::descending(SortField::Hostname),
+            SortKind::PortAsc => SortCriterion::ascending(SortField::Port),
+            SortKind::PortDesc => SortCriterion::descending(SortField::Port),
+            SortKind::ServiceTypeAsc => SortCriterion::ascending(SortField::ServiceType),
+            SortKind::ServiceTypeDesc => SortCriterion::descending(SortField::ServiceType),
+            SortKind::IpAddrAsc => SortCriterion::ascending(SortField::Address),
+            SortKind::IpAddrDesc => SortCriterion::descending(SortField::Address),
+            SortKind::TimestampAsc => SortCriterion::ascending(SortField::LastUpdated),
+            SortKind::TimestampDesc => SortCriterion::descending(SortField::LastUpdated),
+        }
+    }
+}
+
+/// Renders an option for every sort kind, for the sort by and then by selects.
+fn sort_kind_options() -> impl IntoView {
+    SortKind::ALL
+        .into_iter()
+        .map(|sort_kind| view! { <option label=sort_kind.label() value=sort_kind.value() /> })
+        .collect_view()
+}
+
 fn start_auto_focus_timer(
     comp_ref: impl Fn() -> Option<AutoCompleteRef> + 'static,
     tutorial_timeout: impl FnOnce(Option<TimeoutHandle>) + 'static,
@@ -760,64 +1039,16 @@ fn start_auto_focus_timer(
     });
 }
 
-fn apply_sort_kind(store: Store<Resolved>, sort_kind: &SortKind) {
-    match sort_kind {
-        SortKind::InstanceAsc => store
-            .services()
-            .write()
-            .sort_by(|a, b| a.instance_fullname.cmp(&b.instance_fullname)),
-        SortKind::InstanceDesc => store
-            .services()
-            .write()
-            .sort_by(|a, b| b.instance_fullname.cmp(&a.instance_fullname)),
-        SortKind::HostnameAsc => {
-            store
-                .services()
-                .write()
-                .sort_by(|a, b| match a.hostname.cmp(&b.hostname) {
-                    std::cmp::Ordering::Equal => a.service_type.cmp(&b.service_type),
-                    other => other,
-                })
-        }
-        SortKind::HostnameDesc => {
-            store
-                .services()
-                .write()
-                .sort_by(|a, b| match b.hostname.cmp(&a.hostname) {
-                    std::cmp::Ordering::Equal => b.service_type.cmp(&a.service_type),
-                    other => other,
-                })
-        }
-        SortKind::PortAsc => store.services().write().sort_by_key(|i| i.port),
-        SortKind::PortDesc => store
-            .services()
-            .write()
-            .sort_by_key(|i| std::cmp::Reverse(i.port)),
-        SortKind::ServiceTypeAsc => store
-            .services()
-            .write()
-            .sort_by(|a, b| a.service_type.cmp(&b.service_type)),
-        SortKind::ServiceTypeDesc => store
-            .services()
-            .write()
-            .sort_by(|a, b| b.service_type.cmp(&a.service_type)),
-        SortKind::IpAddrAsc => store
-            .services()
-            .write()
-            .sort_by(|a, b| a.addresses.cmp(&b.addresses)),
-        SortKind::IpAddrDesc => store
-            .services()
-            .write()
-            .sort_by(|a, b| b.addresses.cmp(&a.addresses)),
-        SortKind::TimestampAsc => store
-            .services()
-            .write()
-            .sort_by_key(|i| i.updated_at_micros),
-        SortKind::TimestampDesc => store
-            .services()
-            .write()
-            .sort_by_key(|i| std::cmp::Reverse(i.updated_at_micros)),
-    }
+/// Sorts the services by the selected sort kind, breaking ties by the selected "then by" kinds.
+fn apply_sort(store: Store<Resolved>) {
+    let criteria: Vec<SortCriterion> = std::iter::once(store.sort_by().get_untracked())
+        .chain(store.then_by().get_untracked())
+        .map(|sort_kind| sort_kind.criterion())
+        .collect();
+    store
+        .services()
+        .write()
+        .sort_by(|a, b| compare_services(a, b, &criteria));
 }
 
 /// Renders the main service browsing interface with filtering, sorting, and interactive controls.
@@ -837,46 +1068,129 @@ fn apply_sort_kind(store: Store<Resolved>, sort_kind: &SortKind) {
 /// ```
 #[component]
 pub fn Browse() -> impl IntoView {
-    // Stop any previously started browsing, to ensure we not browsing after a frontend reload
-    spawn_local(stop_browse());
-
     let (service_types, set_service_types) = signal(ServiceTypes::new());
+    let (found_subtypes, set_found_subtypes) = signal(ServiceTypes::new());
     provide_context(ServiceTypesInjection(service_types));
-    LocalResource::new(move || listen_to_service_type_events(set_service_types));
+    LocalResource::new(move || {
+        listen_to_service_type_events(set_service_types, set_found_subtypes)
+    });
     let store = Store::new(Resolved::default());
     let filtered = Store::new(Filtered::default());
 
+    // Subtypes of resolved services, not all responders announce them during type browsing
+    let resolved_subtypes = Memo::new(move |_| {
+        let mut subtypes = store
+            .services()
+            .read()
+            .iter()
+            .filter_map(|rs| rs.subtype.clone())
+            .collect::<Vec<_>>();
+        subtypes.sort_by(|a, b| service_type_order_key(a).cmp(&service_type_order_key(b)));
+        subtypes.dedup();
+        subtypes
+    });
+    // Offered both for completion and as filter, a found subtype may match services resolved later
+    let subtypes = Signal::derive(move || {
+        let mut subtypes = found_subtypes.get();
+        for subtype in resolved_subtypes.get() {
+            if !subtypes.contains(&subtype) {
+                subtypes.push(subtype);
+            }
+        }
+        subtypes
+    });
+    let subtype_filter = RwSignal::new(String::new());
+
+    // Ticks every second, so dead services are hidden and purged according to the policy
+    let now = RwSignal::new(now_micros());
+    if let Ok(handle) = set_interval_with_handle(
+        move || now.set(now_micros()),
+        std::time::Duration::from_secs(1),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+    let dead_service_policy = DeadServicePolicyInjection::expect_context();
+    let hidden = Memo::new(move |_| {
+        let now = now.get();
+        let policy = dead_service_policy.get();
+        store
+            .services()
+            .read()
+            .iter()
+            .filter(|rs| policy.is_hidden(rs, now))
+            .map(|rs| rs.instance_fullname.clone())
+            .collect::<HashSet<_>>()
+    });
+
+    Effect::new(move |_| {
+        let now = now.get();
+        let policy = dead_service_policy.get();
+        if store
+            .services()
+            .read_untracked()
+            .iter()
+            .any(|rs| policy.is_purged(rs, now))
+        {
+            store
+                .services()
+                .write()
+                .retain(|rs| !policy.is_purged(rs, now));
+        }
+    });
+
     Effect::watch(
-        move || (store.query().get(), store.services().get()),
-        move |(query, services), _, _| {
+        move || {
+            (
+                store.query().get(),
+                store.services().get(),
+                hidden.get(),
+                subtype_filter.get(),
+            )
+        },
+        move |(query, services, hidden, subtype_filter), _, _| {
             let mut services = services.clone();
-            services.retain(|rs| rs.matches_query(query));
+            services.retain(|rs| {
+                !hidden.contains(&rs.instance_fullname)
+                    && rs.matches_query(query)
+                    && (subtype_filter.is_empty() || rs.subtype.as_ref() == Some(subtype_filter))
+            });
             *filtered.services().write() = services;
         },
         true,
     );
 
+    let has_dead_services = Memo::new(move |_| store.services().read().iter().any(|rs| rs.dead));
+    let on_clear_dead_click = move |_| {
+        store.services().write().retain(|rs| !rs.dead);
+        spawn_local(clear_dead_services());
+    };
+
     let sort_value = RwSignal::new("HostnameAsc".to_string());
 
-    Effect::new(move |_| match sort_value.get().as_str() {
-        "InstanceAsc" => store.sort_by().set(SortKind::InstanceAsc),
-        "InstanceDesc" => store.sort_by().set(SortKind::InstanceDesc),
-        "HostnameAsc" => store.sort_by().set(SortKind::HostnameAsc),
-        "HostnameDesc" => store.sort_by().set(SortKind::HostnameDesc),
-        "PortAsc" => store.sort_by().set(SortKind::PortAsc),
-        "PortDesc" => store.sort_by().set(SortKind::PortDesc),
-        "ServiceTypeAsc" => store.sort_by().set(SortKind::ServiceTypeAsc),
-        "ServiceTypeDesc" => store.sort_by().set(SortKind::ServiceTypeDesc),
-        "IpAddrAsc" => store.sort_by().set(SortKind::IpAddrAsc),
-        "IpAddrDesc" => store.sort_by().set(SortKind::IpAddrDesc),
-        "TimestampAsc" => store.sort_by().set(SortKind::TimestampAsc),
-        "TimestampDesc" => store.sort_by().set(SortKind::TimestampDesc),
-        _ => {}
+    Effect::new(move |_| {
+        if let Some(sort_kind) = SortKind::from_value(&sort_value.get()) {
+            store.sort_by().set(sort_kind);
+        }
+    });
+    let on_sort = Callback::new(move |sort_kind: SortKind| {
+        sort_value.set(sort_kind.value().to_string());
+    });
+    let show_table = RwSignal::new("Cards".to_string());
+
+    // Ties of the sort by key are broken by the service type unless chosen otherwise
+    let then_by_value = RwSignal::new("ServiceTypeAsc".to_string());
+    let and_then_by_value = RwSignal::new(String::new());
+    Effect::new(move |_| {
+        let then_by = [then_by_value.get(), and_then_by_value.get()]
+            .iter()
+            .filter_map(|value| SortKind::from_value(value))
+            .collect::<Vec<_>>();
+        store.then_by().set(then_by);
     });
 
     Effect::watch(
-        move || store.sort_by().get(),
-        move |sort_kind, _, _| apply_sort_kind(store, sort_kind),
+        move || (store.sort_by().get(), store.then_by().get()),
+        move |_, _, _| apply_sort(store),
         false,
     );
 
@@ -884,10 +1198,17 @@ pub fn Browse() -> impl IntoView {
     let service_type = RwSignal::new(String::new());
     let not_browsing = Signal::derive(move || !browsing.get());
     let service_type_invalid = Signal::derive(move || {
-        // TODO: report a meaningful error to the user
         !service_type.get().is_empty()
             && check_service_type_fully_qualified(service_type.get().clone().as_str()).is_err()
     });
+    let service_type_lint = Memo::new(move |_| {
+        let service_type = service_type.get();
+        if service_type.is_empty() {
+            ServiceTypeLint::default()
+        } else {
+            lint_service_type(&service_type)
+        }
+    });
 
     let has_enabled_interfaces = HasEnabledInterfacesInjection::expect_context();
     let service_type_input_disabled = Signal::derive(move || browsing.get());
@@ -900,9 +1221,14 @@ pub fn Browse() -> impl IntoView {
         async move { browse_many(input.clone()).await }
     });
 
-    let browse_action = Action::new_local(|input: &String| {
+    let profiles = ProfilesInjection::expect_context();
+    let browse_action = Action::new_local(|input: &ServiceTypes| {
         let input = input.clone();
-        async move { browse_many(vec![input]).await }
+        async move {
+            // A fresh browse starts with an empty service table in the backend
+            clear_services().await;
+            browse_many(input).await
+        }
     });
 
     Effect::watch(
@@ -917,9 +1243,11 @@ pub fn Browse() -> impl IntoView {
 
             let added: Vec<_> = new_set.difference(&old_set).cloned().collect();
 
+            // The active profile may restrict browsing to its own service types
             if !added.is_empty()
                 && browsing.get_untracked()
                 && service_type.get_untracked().is_empty()
+                && profiles.with_untracked(|profiles| profiles.watched_service_types().is_none())
             {
                 log::info!("Added services while browsing all: {added:?}, browsing");
                 browse_all_action.dispatch(added.clone());
@@ -960,9 +1288,12 @@ pub fn Browse() -> impl IntoView {
         browsing.set(true);
         let value = service_type.get_untracked();
         if value.is_empty() {
-            browse_all_action.dispatch(service_types.get_untracked());
+            let watched = profiles
+                .with_untracked(|profiles| profiles.watched_service_types().cloned())
+                .unwrap_or_else(|| service_types.get_untracked());
+            browse_action.dispatch(watched);
         } else {
-            browse_action.dispatch(value);
+            browse_action.dispatch(vec![value]);
         }
     };
 
@@ -982,10 +1313,34 @@ pub fn Browse() -> impl IntoView {
     };
 
     LocalResource::new(move || listen_for_resolve_events(store));
+    LocalResource::new(move || hydrate_from_backend(store, browsing));
     let is_desktop = IsDesktopInjection::expect_context();
     let layout_class = get_class(&is_desktop, "browse-layout");
     let input_class = get_class(&is_desktop, "input");
     let grid_class = get_class(&is_desktop, "resolved-service-grid");
+    // Keep in sync with the resolved-service-grid classes in styles.css
+    let grid_layout = Signal::derive(move || {
+        let (min_item_width, gap) = if is_desktop.get() {
+            (520.0, 10.0)
+        } else {
+            (480.0, 8.0)
+        };
+        VirtualLayout {
+            min_item_width: Some(min_item_width),
+            gap,
+            estimated_row_height: 320.0,
+        }
+    });
+    let grid_container = NodeRef::<leptos::html::Div>::new();
+    let grid_content = NodeRef::<leptos::html::Div>::new();
+    let visible = use_visible_range(
+        grid_container,
+        grid_content,
+        Signal::derive(move || filtered.services().read().len()),
+        grid_layout,
+    );
+    // Shared by the matrix and the table, so the services are cloned once per change
+    let filtered_services = Memo::new(move |_| filtered.services().get());
     view! {
         <Layout class=layout_class>
             <BackTop threshold=100 />
@@ -993,6 +1348,7 @@ pub fn Browse() -> impl IntoView {
                 <ProtocolFlags disabled=browsing />
                 <Flex gap=FlexGap::Small align=FlexAlign::Center justify=FlexJustify::Start>
                     <AutoCompleteServiceType
+                        subtypes
                         invalid=service_type_invalid
                         value=service_type
                         disabled=service_type_input_disabled
@@ -1012,6 +1368,13 @@ pub fn Browse() -> impl IntoView {
                     >
                         "Stop"
                     </Button>
+                    <Button
+                        appearance=ButtonAppearance::Secondary
+                        on_click=on_clear_dead_click
+                        disabled=Signal::derive(move || !has_dead_services.get())
+                    >
+                        "Clear dead"
+                    </Button>
                     <Badge
                         appearance=BadgeAppearance::Tint
                         size=BadgeSize::Large
@@ -1026,21 +1389,21 @@ pub fn Browse() -> impl IntoView {
                         }}
                     </Badge>
                 </Flex>
+                <ServiceTypeDiagnostics lint=service_type_lint value=service_type />
                 <Flex gap=FlexGap::Small align=FlexAlign::Center justify=FlexJustify::Start>
                     <Text>"Sort by"</Text>
                     <Select default_value="HostnameAsc" value=sort_value>
-                        <option label="Instance (Ascending)" value="InstanceAsc" />
-                        <option label="Instance (Descending)" value="InstanceDesc" />
-                        <option label="Hostname (Ascending)" value="HostnameAsc" />
-                        <option label="Hostname (Descending)" value="HostnameDesc" />
-                        <option label="Port (Ascending)" value="PortAsc" />
-                        <option label="Port (Descending)" value="PortDesc" />
-                        <option label="Service Type (Ascending)" value="ServiceTypeAsc" />
-                        <option label="Service Type (Descending)" value="ServiceTypeDesc" />
-                        <option label="IP (Ascending)" value="IpAddrAsc" />
-                        <option label="IP (Descending)" value="IpAddrDesc" />
-                        <option label="Last Updated (Ascending)" value="TimestampAsc" />
-                        <option label="Last Updated (Descending)" value="TimestampDesc" />
+                        {sort_kind_options()}
+                    </Select>
+                    <Text>"then by"</Text>
+                    <Select default_value="ServiceTypeAsc" value=then_by_value>
+                        <option label="None" value="" />
+                        {sort_kind_options()}
+                    </Select>
+                    <Text>"then by"</Text>
+                    <Select default_value="" value=and_then_by_value>
+                        <option label="None" value="" />
+                        {sort_kind_options()}
                     </Select>
                     <Input
                         value=store.query()
@@ -1048,17 +1411,74 @@ pub fn Browse() -> impl IntoView {
                         class=input_class
                         on_focus=on_quick_filter_focus
                     />
+                    <Text>"Subtype"</Text>
+                    <Select default_value="" value=subtype_filter>
+                        <option label="All" value="" />
+                        <For
+                            each=move || subtypes.get()
+                            key=|subtype| subtype.clone()
+                            let:subtype
+                        >
+                            <option
+                                label=subtype_option_label(&subtype)
+                                value=subtype.clone()
+                            />
+                        </For>
+                    </Select>
+                    <Text>"View"</Text>
+                    <Select default_value="Cards" value=show_table>
+                        <option label="Cards" value="Cards" />
+                        <option label="Table" value="Table" />
+                    </Select>
                 </Flex>
+                <Accordion multiple=true>
+                    <AccordionItem value="interface-matrix">
+                        <AccordionHeader slot>"Interface matrix"</AccordionHeader>
+                        <InterfaceMatrix services=filtered_services />
+                    </AccordionItem>
+                </Accordion>
             </Flex>
-            <Grid class=grid_class>
-                <For
-                    each=move || filtered.services()
-                    key=move |row| row.get().instance_fullname
-                    let:resolved_service
-                >
-                    <ResolvedServiceItem resolved_service browsing />
-                </For>
-            </Grid>
+            <Show
+                when=move || show_table.get() == "Table"
+                fallback=move || {
+                    view! {
+                        <div
+                            node_ref=grid_container
+                            style:padding-top=move || {
+                                format!("{}px", visible.get().padding_top)
+                            }
+                            style:padding-bottom=move || {
+                                format!("{}px", visible.get().padding_bottom)
+                            }
+                        >
+                            <div node_ref=grid_content>
+                                <Grid class=grid_class>
+                                    <For
+                                        each=move || {
+                                            let range = visible.get();
+                                            filtered
+                                                .services()
+                                                .into_iter()
+                                                .skip(range.start)
+                                                .take(range.end - range.start)
+                                        }
+                                        key=move |row| row.get().instance_fullname
+                                        let:resolved_service
+                                    >
+                                        <ResolvedServiceItem resolved_service browsing />
+                                    </For>
+                                </Grid>
+                            </div>
+                        </div>
+                    }
+                }
+            >
+                <ServiceTable
+                    services=filtered_services
+                    sort_by=Signal::derive(move || store.sort_by().get())
+                    on_sort
+                />
+            </Show>
         </Layout>
     }
 }
@@ -0,0 +1,130 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+use leptos::prelude::*;
+use models::DeadServicePolicy;
+use serde::{Deserialize, Serialize};
+use tauri_sys::core::invoke_result;
+use thaw::{
+    Flex, FlexAlign, FlexGap, FlexJustify, Select, Text, Toast, ToastBody, ToastTitle,
+    ToasterInjection,
+};
+
+/// Injection providing the policy for services that were removed from the network.
+#[derive(Clone, Debug)]
+pub struct DeadServicePolicyInjection(pub RwSignal<DeadServicePolicy>);
+
+impl DeadServicePolicyInjection {
+    #[track_caller]
+    pub fn expect_context() -> RwSignal<DeadServicePolicy> {
+        expect_context::<Self>().0
+    }
+}
+
+/// Returns the current time in microseconds since the unix epoch.
+///
+/// `SystemTime` is not available in the browser, so the time is taken from JavaScript.
+pub fn now_micros() -> u64 {
+    (js_sys::Date::now() * 1_000.0) as u64
+}
+
+pub async fn get_dead_service_policy(policy: RwSignal<DeadServicePolicy>) {
+    match invoke_result::<DeadServicePolicy, String>("get_dead_service_policy", &()).await {
+        Ok(loaded) => policy.set(loaded),
+        Err(e) => log::error!("failed to get dead service policy: {e}"),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DeadServicePolicyArgs {
+    policy: DeadServicePolicy,
+}
+
+async fn set_dead_service_policy(policy: DeadServicePolicy) -> Result<(), String> {
+    invoke_result::<(), String>("set_dead_service_policy", &DeadServicePolicyArgs { policy }).await
+}
+
+fn create_policy_error_toast(message: String) -> impl IntoView {
+    view! {
+        <Toast>
+            <ToastTitle>"Failed to apply dead service policy"</ToastTitle>
+            <ToastBody>{message}</ToastBody>
+        </Toast>
+    }
+}
+
+const NEVER: &str = "never";
+
+fn to_option_value(value: Option<u64>) -> String {
+    value.map_or_else(|| NEVER.to_string(), |value| value.to_string())
+}
+
+fn from_option_value(value: &str) -> Option<u64> {
+    value.parse().ok()
+}
+
+/// Component for configuring when dead services are hidden and purged.
+///
+/// Changes are applied immediately and persisted in the backend.
+#[component]
+pub fn DeadServicePolicySettings() -> impl IntoView {
+    let policy = DeadServicePolicyInjection::expect_context();
+    let hide_value = RwSignal::new(NEVER.to_string());
+    let purge_value = RwSignal::new(NEVER.to_string());
+
+    Effect::new(move |_| {
+        let policy = policy.get();
+        hide_value.set(to_option_value(policy.hide_after_secs));
+        purge_value.set(to_option_value(policy.purge_after_mins));
+    });
+
+    let toaster = ToasterInjection::expect_context();
+    let set_policy_action = Action::new_local(move |new_policy: &DeadServicePolicy| {
+        let new_policy = *new_policy;
+        async move {
+            match set_dead_service_policy(new_policy).await {
+                Ok(()) => policy.set(new_policy),
+                Err(e) => {
+                    log::error!("failed to set dead service policy: {e}");
+                    toaster
+                        .dispatch_toast(move || create_policy_error_toast(e), Default::default());
+                }
+            }
+        }
+    });
+
+    Effect::watch(
+        move || (hide_value.get(), purge_value.get()),
+        move |(hide, purge), _, _| {
+            let new_policy = DeadServicePolicy {
+                hide_after_secs: from_option_value(hide),
+                purge_after_mins: from_option_value(purge),
+            };
+            if new_policy != policy.get_untracked() {
+                set_policy_action.dispatch(new_policy);
+            }
+        },
+        false,
+    );
+
+    view! {
+        <Flex gap=FlexGap::Small align=FlexAlign::Center justify=FlexJustify::Start>
+            <Text>"Hide dead services"</Text>
+            <Select value=hide_value>
+                <option label="Never" value=NEVER />
+                <option label="After 10 seconds" value="10" />
+                <option label="After 30 seconds" value="30" />
+                <option label="After 1 minute" value="60" />
+                <option label="After 5 minutes" value="300" />
+            </Select>
+            <Text>"Purge dead services"</Text>
+            <Select value=purge_value>
+                <option label="Never" value=NEVER />
+                <option label="After 1 minute" value="1" />
+                <option label="After 5 minutes" value="5" />
+                <option label="After 15 minutes" value="15" />
+                <option label="After 1 hour" value="60" />
+            </Select>
+        </Flex>
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+use leptos::prelude::*;
+use models::ResolvedService;
+use std::collections::BTreeSet;
+use thaw::{Table, TableCell, TableHeader, TableHeaderCell, TableRow, Text};
+
+use super::virtual_table::VirtualServiceRows;
+
+/// Collects the names of all interfaces any of the given services was heard on, sorted by name.
+fn interface_columns(services: &[ResolvedService]) -> Vec<String> {
+    services
+        .iter()
+        .flat_map(|service| {
+            service
+                .sightings
+                .iter()
+                .map(|sighting| sighting.interface.name.clone())
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Renders the cells of a service row, one per interface column.
+fn matrix_cells(service: &ResolvedService, columns: &[String]) -> impl IntoView + use<> {
+    let cells = columns
+        .iter()
+        .map(|column| {
+            let families = service
+                .sightings
+                .iter()
+                .find(|sighting| &sighting.interface.name == column)
+                .map(|sighting| sighting.families())
+                .unwrap_or_default();
+            view! { <TableCell>{families}</TableCell> }
+        })
+        .collect::<Vec<_>>();
+    view! {
+        <TableCell>
+            <Text>{service.get_instance_name()}</Text>
+        </TableCell>
+        {cells}
+    }
+}
+
+/// Component that shows a matrix of services and the interfaces they were heard on
+///
+/// Each cell lists the IP families the service was heard with on the interface of its column,
+/// which helps spotting interfaces where multicast traffic is filtered. Only the rows around the
+/// viewport are mounted, and a row is kept while its service updates.
+#[component]
+pub fn InterfaceMatrix(#[prop(into)] services: Signal<Vec<ResolvedService>>) -> impl IntoView {
+    let columns = Memo::new(move |_| services.with(|services| interface_columns(services)));
+
+    view! {
+        <Table class="interface-matrix">
+            <TableHeader>
+                <TableRow>
+                    <TableHeaderCell>"Instance"</TableHeaderCell>
+                    <For each=move || columns.get() key=|column| column.clone() let:column>
+                        <TableHeaderCell>{column}</TableHeaderCell>
+                    </For>
+                </TableRow>
+            </TableHeader>
+            <VirtualServiceRows
+                services
+                row=move |service: Memo<ResolvedService>| {
+                    view! {
+                        <TableRow>
+                            {move || service.with(|service| matrix_cells(service, &columns.get()))}
+                        </TableRow>
+                    }
+                }
+            />
+        </Table>
+    }
+}
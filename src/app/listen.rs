@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: MIT-0
 
 use super::invoke::invoke_no_args;
-use futures::{StreamExt, select};
+use futures::StreamExt;
 use serde::de::DeserializeOwned;
 use std::future::Future;
 use tauri_sys::event::listen;
@@ -84,86 +84,3 @@ where
     )
     .await;
 }
-
-/// Listens concurrently for "added" and "removed" events, processing each payload with the provided closures.
-///
-/// Awaits the subscriber future before entering the event loop. For each event received on the added or removed streams, invokes the corresponding processing closure with the event payload. Exits when both streams are complete.
-///
-/// # Type Parameters
-///
-/// - `A`: Type of the payload for added events.
-/// - `R`: Type of the payload for removed events.
-///
-/// # Parameters
-///
-/// - `added_event_name`: Name of the event stream for added items.
-/// - `removed_event_name`: Name of the event stream for removed items.
-/// - `process_added`: Closure to process each added event payload.
-/// - `process_removed`: Closure to process each removed event payload.
-///
-/// # Examples
-///
-/// ```
-/// use serde::Deserialize;
-///
-/// #[derive(Debug, Deserialize)]
-/// struct Item { id: u32 }
-///
-/// async fn subscribe() { /* ... */ }
-///
-/// listen_add_remove(
-///     subscribe,
-///     "item-added",
-///     |item: Item| println!("Added: {:?}", item),
-///     "item-removed",
-///     |item: Item| println!("Removed: {:?}", item),
-/// ).await;
-/// ```
-pub(crate) async fn listen_add_remove<A, R, FA, FR, S, Fut>(
-    subscriber: S,
-    added_event_name: &str,
-    mut process_added: FA,
-    removed_event_name: &str,
-    mut process_removed: FR,
-) where
-    A: DeserializeOwned + 'static + std::fmt::Debug,
-    R: DeserializeOwned + 'static + std::fmt::Debug,
-    FA: FnMut(A) + 'static,
-    FR: FnMut(R) + 'static,
-    S: FnOnce() -> Fut,
-    Fut: Future<Output = ()>,
-{
-    let mut added_fused = match listen::<A>(added_event_name).await {
-        Ok(added) => added.fuse(),
-        Err(err) => {
-            log::error!("Failed to listen to event: {added_event_name}. Error: {err:?}");
-            return;
-        }
-    };
-
-    let mut removed_fused = match listen::<R>(removed_event_name).await {
-        Ok(removed) => removed.fuse(),
-        Err(err) => {
-            log::error!("Failed to listen to event: {removed_event_name}. Error: {err:?}");
-            return;
-        }
-    };
-
-    subscriber().await;
-
-    loop {
-        select! {
-            added = added_fused.next() => {
-                if let Some(added) = added {
-                    process_added(added.payload);
-                }
-            },
-            removed = removed_fused.next() => {
-                if let Some(removed) = removed {
-                    process_removed(removed.payload);
-                }
-            },
-            complete => break,
-        }
-    }
-}
@@ -5,11 +5,14 @@ use super::{
     about::About,
     browse::{Browse, BrowsingInjection},
     css::get_class,
+    dead_services::{DeadServicePolicyInjection, get_dead_service_policy},
     invoke::invoke_no_args,
     is_desktop::{IsDesktopInjection, get_is_desktop},
     metrics::Metrics,
     network_interfaces::{HasEnabledInterfacesInjection, NetworkInterfaces},
+    profiles::{Profiles, ProfilesInjection, listen_for_profile_changes},
     theme_switcher::ThemeSwitcher,
+    timing_settings::{TimingConfigInjection, TimingSettings, get_timing_config},
 };
 use js_sys::{
     Function, Reflect,
@@ -19,14 +22,45 @@ use leptos::{
     ev::{DragEvent, Event},
     prelude::*,
 };
+use models::{DeadServicePolicy, ProfileSettings, TimingConfig};
+use tauri_sys::core::invoke_result;
 use thaw::{
-    ConfigProvider, Flex, FlexJustify, Grid, GridItem, Layout, Text, Theme, ToasterProvider,
+    ConfigProvider, Flex, FlexJustify, Grid, GridItem, Layout, Text, Theme, Toast, ToastBody,
+    ToastTitle, ToasterInjection, ToasterProvider,
 };
 
 async fn close_splashscreen() {
     let _ = invoke_no_args("close_splashscreen").await;
 }
 
+fn create_startup_notice_toast(notice: String) -> impl IntoView {
+    view! {
+        <Toast>
+            <ToastTitle>"Rendering workaround applied"</ToastTitle>
+            <ToastBody>{notice}</ToastBody>
+        </Toast>
+    }
+}
+
+/// Shows why the app started differently than usual, e.g. after the previous start crashed.
+#[component]
+fn StartupNotice() -> impl IntoView {
+    let toaster = ToasterInjection::expect_context();
+    LocalResource::new(move || async move {
+        match invoke_result::<Option<String>, String>("startup_notice", &()).await {
+            Ok(Some(notice)) => {
+                log::warn!("{notice}");
+                toaster.dispatch_toast(
+                    move || create_startup_notice_toast(notice),
+                    Default::default(),
+                );
+            }
+            Ok(None) => {}
+            Err(e) => log::error!("failed to get startup notice: {e}"),
+        }
+    });
+}
+
 /// The main app component
 #[component]
 pub fn Main() -> impl IntoView {
@@ -107,9 +141,19 @@ pub fn Main() -> impl IntoView {
     provide_context(BrowsingInjection(browsing));
     let has_enabled_interfaces = RwSignal::new(false);
     provide_context(HasEnabledInterfacesInjection(has_enabled_interfaces));
+    let timing = RwSignal::new(TimingConfig::default());
+    LocalResource::new(move || get_timing_config(timing));
+    provide_context(TimingConfigInjection(timing));
+    let dead_service_policy = RwSignal::new(DeadServicePolicy::default());
+    LocalResource::new(move || get_dead_service_policy(dead_service_policy));
+    provide_context(DeadServicePolicyInjection(dead_service_policy));
+    let profiles = RwSignal::new(ProfileSettings::default());
+    LocalResource::new(move || listen_for_profile_changes(profiles));
+    provide_context(ProfilesInjection(profiles));
     view! {
         <ConfigProvider theme>
             <ToasterProvider>
+                <StartupNotice />
                 <Layout class=layout_class>
                     <Suspense fallback=|| view! { <Text>"Loading"</Text> }>
                         <Grid cols=2>
@@ -123,7 +167,9 @@ pub fn Main() -> impl IntoView {
                             </GridItem>
                         </Grid>
                         <Metrics />
+                        <Profiles disabled=browsing />
                         <NetworkInterfaces disabled=browsing />
+                        <TimingSettings />
                         <Browse />
                     </Suspense>
                 </Layout>
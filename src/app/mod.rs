@@ -6,12 +6,20 @@ mod backtop;
 mod browse;
 mod clipboard;
 mod css;
+mod dead_services;
+mod interface_matrix;
 mod invoke;
 mod is_desktop;
 mod listen;
 pub mod main;
 mod metrics;
 mod network_interfaces;
+mod profiles;
 mod protocol_flags;
+mod service_table;
 mod theme_switcher;
+mod timing_settings;
+mod update_check_settings;
 mod values_table;
+mod virtual_list;
+mod virtual_table;
@@ -8,7 +8,8 @@ use serde::{Deserialize, Serialize};
 use tauri_sys::core::invoke_result;
 use thaw::{
     Accordion, AccordionHeader, AccordionItem, Checkbox, Flex, FlexAlign, FlexGap, FlexJustify,
-    Layout, Toast, ToastBody, ToastTitle, ToasterInjection,
+    Layout, Table, TableBody, TableCell, TableHeader, TableHeaderCell, TableRow, Toast, ToastBody,
+    ToastTitle, ToasterInjection,
 };
 
 use super::{css::get_class, is_desktop::IsDesktopInjection, listen::listen_to_named_event};
@@ -75,6 +76,8 @@ fn create_set_interfaces_error_toast(message: String) -> impl IntoView {
 }
 
 /// Renders a single network interface as a checkbox with its name and IP addresses as label.
+///
+/// Interfaces that are not mDNS capable are shown with the reason and can not be selected.
 #[component]
 fn NetworkInterfaceItem(
     #[prop(into)] interface: Field<NetworkInterface>,
@@ -83,19 +86,47 @@ fn NetworkInterfaceItem(
     let label = Memo::new(move |_| {
         let name = interface.name().get();
         let addresses = interface.addresses().get();
-        if addresses.is_empty() {
+        let label = if addresses.is_empty() {
             name
         } else {
             format!("{name} ({})", addresses.join(", "))
+        };
+        match interface.incapable_reason().get() {
+            Some(reason) => format!("{label}: {reason}"),
+            None => label,
         }
     });
+    let disabled =
+        Signal::derive(move || disabled.get() || interface.incapable_reason().get().is_some());
     view! { <Checkbox checked=interface.enabled() disabled=disabled label=label /> }
 }
 
+/// Renders a table row with the details of a single network interface.
+#[component]
+fn NetworkInterfaceDetailsRow(#[prop(into)] interface: Field<NetworkInterface>) -> impl IntoView {
+    let details = Memo::new(move |_| interface.get());
+    view! {
+        <TableRow>
+            <TableCell>{move || details.get().name}</TableCell>
+            <TableCell>{move || details.get().index}</TableCell>
+            <TableCell>{move || details.get().mac.unwrap_or_default()}</TableCell>
+            <TableCell>
+                {move || details.get().mtu.map(|mtu| mtu.to_string()).unwrap_or_default()}
+            </TableCell>
+            <TableCell>{move || details.get().flags.to_string()}</TableCell>
+            <TableCell>{move || details.get().addresses.join(", ")}</TableCell>
+            <TableCell>
+                {move || details.get().incapable_reason.unwrap_or_else(|| "Capable".to_string())}
+            </TableCell>
+        </TableRow>
+    }
+}
+
 /// Component for selecting the network interfaces used for mDNS browsing.
 ///
-/// Displays a checkbox per mDNS-capable network interface. The checkboxes are disabled while a
-/// browse is active, and selections are applied to the backend daemon.
+/// Displays a checkbox per network interface, where interfaces that are not mDNS capable can not be
+/// selected. The checkboxes are disabled while a browse is active, and selections are applied to
+/// the backend daemon. A second section lists the details of all interfaces.
 #[component]
 pub fn NetworkInterfaces(#[prop(optional, into)] disabled: Signal<bool>) -> impl IntoView {
     let store = Store::new(InterfacesState::default());
@@ -169,6 +200,31 @@ pub fn NetworkInterfaces(#[prop(optional, into)] disabled: Signal<bool>) -> impl
                         </For>
                     </Flex>
                 </AccordionItem>
+                <AccordionItem value="network-interface-details">
+                    <AccordionHeader slot>"Network interface details"</AccordionHeader>
+                    <Table>
+                        <TableHeader>
+                            <TableRow>
+                                <TableHeaderCell>"Name"</TableHeaderCell>
+                                <TableHeaderCell>"Index"</TableHeaderCell>
+                                <TableHeaderCell>"MAC"</TableHeaderCell>
+                                <TableHeaderCell>"MTU"</TableHeaderCell>
+                                <TableHeaderCell>"Flags"</TableHeaderCell>
+                                <TableHeaderCell>"Addresses"</TableHeaderCell>
+                                <TableHeaderCell>"mDNS"</TableHeaderCell>
+                            </TableRow>
+                        </TableHeader>
+                        <TableBody>
+                            <For
+                                each=move || store.interfaces()
+                                key=move |interface| interface.get().name
+                                let:interface
+                            >
+                                <NetworkInterfaceDetailsRow interface />
+                            </For>
+                        </TableBody>
+                    </Table>
+                </AccordionItem>
             </Accordion>
         </Layout>
     }
@@ -0,0 +1,217 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+use leptos::prelude::*;
+use models::{ProfileSettings, ProfilesChangedEvent};
+use serde::{Deserialize, Serialize};
+use tauri_sys::core::invoke_result;
+use thaw::{
+    Accordion, AccordionHeader, AccordionItem, Button, ButtonAppearance, Field, Flex, FlexAlign,
+    FlexGap, FlexJustify, Input, Layout, Select, Text, Toast, ToastBody, ToastTitle,
+    ToasterInjection,
+};
+
+use super::{css::get_class, is_desktop::IsDesktopInjection, listen::listen_events};
+
+/// Injection providing the saved profiles and the active one.
+///
+/// The browse view uses it to browse for the service types of the active profile.
+#[derive(Clone, Debug)]
+pub struct ProfilesInjection(pub RwSignal<ProfileSettings>);
+
+impl ProfilesInjection {
+    #[track_caller]
+    pub fn expect_context() -> RwSignal<ProfileSettings> {
+        expect_context::<Self>().0
+    }
+}
+
+pub async fn get_profiles(profiles: RwSignal<ProfileSettings>) {
+    match invoke_result::<ProfileSettings, String>("get_profiles", &()).await {
+        Ok(loaded) => profiles.set(loaded),
+        Err(e) => log::error!("failed to get profiles: {e}"),
+    }
+}
+
+/// Loads the profiles and keeps them in sync with the profiles the backend activates when the
+/// network interfaces change.
+pub async fn listen_for_profile_changes(profiles: RwSignal<ProfileSettings>) {
+    listen_events(
+        async || get_profiles(profiles).await,
+        "profiles-changed",
+        move |event: ProfilesChangedEvent| profiles.set(event.profiles),
+    )
+    .await;
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveProfileArgs {
+    name: String,
+    service_types: Vec<String>,
+    auto_activate: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ActivateProfileArgs {
+    name: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct DeleteProfileArgs {
+    name: String,
+}
+
+#[derive(Clone)]
+enum ProfileRequest {
+    Activate(Option<String>),
+    Save(SaveProfileArgs),
+    Delete(DeleteProfileArgs),
+}
+
+async fn send_profile_request(request: ProfileRequest) -> Result<(), String> {
+    match request {
+        ProfileRequest::Activate(name) => {
+            invoke_result::<(), String>("activate_profile", &ActivateProfileArgs { name }).await
+        }
+        ProfileRequest::Save(args) => invoke_result::<(), String>("save_profile", &args).await,
+        ProfileRequest::Delete(args) => invoke_result::<(), String>("delete_profile", &args).await,
+    }
+}
+
+fn create_profile_error_toast(message: String) -> impl IntoView {
+    view! {
+        <Toast>
+            <ToastTitle>"Failed to update profiles"</ToastTitle>
+            <ToastBody>{message}</ToastBody>
+        </Toast>
+    }
+}
+
+/// Splits a comma separated input into its trimmed, non-empty entries.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+const NO_PROFILE: &str = "";
+
+/// Component for switching between profiles, and for saving the current interface selection and
+/// protocol flags as a profile.
+///
+/// The selection is disabled while browsing, as it changes the interfaces browsed on.
+#[component]
+pub fn Profiles(#[prop(optional, into)] disabled: Signal<bool>) -> impl IntoView {
+    let profiles = ProfilesInjection::expect_context();
+    let active_value = RwSignal::new(NO_PROFILE.to_string());
+    let name = RwSignal::new(String::new());
+    let service_types = RwSignal::new(String::new());
+    let auto_activate = RwSignal::new(String::new());
+
+    // The inputs start out with the active profile, so it can be edited and saved again. They are
+    // only re-seeded when another profile becomes active, to keep what is being typed.
+    let active_name = Memo::new(move |_| profiles.with(|profiles| profiles.active.clone()));
+    Effect::watch(
+        move || active_name.get(),
+        move |active, _, _| {
+            active_value.set(active.clone().unwrap_or_default());
+            profiles.with_untracked(|profiles| {
+                if let Some(profile) = profiles.active_profile() {
+                    name.set(profile.name.clone());
+                    service_types.set(profile.service_types.join(", "));
+                    auto_activate.set(profile.auto_activate.join(", "));
+                }
+            });
+        },
+        true,
+    );
+
+    let toaster = ToasterInjection::expect_context();
+    let profile_action = Action::new_local(move |request: &ProfileRequest| {
+        let request = request.clone();
+        async move {
+            if let Err(e) = send_profile_request(request).await {
+                log::error!("failed to update profiles: {e}");
+                toaster.dispatch_toast(move || create_profile_error_toast(e), Default::default());
+            }
+            get_profiles(profiles).await;
+        }
+    });
+
+    Effect::watch(
+        move || active_value.get(),
+        move |active, _, _| {
+            let active = Some(active.clone()).filter(|name| name != NO_PROFILE);
+            if active != profiles.with_untracked(|profiles| profiles.active.clone()) {
+                profile_action.dispatch(ProfileRequest::Activate(active));
+            }
+        },
+        false,
+    );
+
+    let on_save_click = move |_| {
+        profile_action.dispatch(ProfileRequest::Save(SaveProfileArgs {
+            name: name.get_untracked(),
+            service_types: split_list(&service_types.get_untracked()),
+            auto_activate: split_list(&auto_activate.get_untracked()),
+        }));
+    };
+    let on_delete_click = move |_| {
+        profile_action.dispatch(ProfileRequest::Delete(DeleteProfileArgs {
+            name: active_value.get_untracked(),
+        }));
+    };
+    let delete_disabled = Signal::derive(move || active_value.get() == NO_PROFILE);
+
+    let is_desktop = IsDesktopInjection::expect_context();
+    let layout_class = get_class(&is_desktop, "profiles-layout");
+    view! {
+        <Layout class=layout_class>
+            <Accordion multiple=true>
+                <AccordionItem value="profiles">
+                    <AccordionHeader slot>"Profiles"</AccordionHeader>
+                    <Flex vertical=true gap=FlexGap::Small>
+                        <Flex gap=FlexGap::Small align=FlexAlign::Center justify=FlexJustify::Start>
+                            <Text>"Active profile"</Text>
+                            <Select value=active_value disabled>
+                                <option label="None" value=NO_PROFILE />
+                                <For
+                                    each=move || profiles.get().profiles
+                                    key=|profile| profile.name.clone()
+                                    let:profile
+                                >
+                                    <option label=profile.name.clone() value=profile.name />
+                                </For>
+                            </Select>
+                            <Button
+                                appearance=ButtonAppearance::Secondary
+                                on_click=on_delete_click
+                                disabled=delete_disabled
+                            >
+                                "Delete"
+                            </Button>
+                        </Flex>
+                        <Flex gap=FlexGap::Small align=FlexAlign::End justify=FlexJustify::Start>
+                            <Field label="Name">
+                                <Input value=name />
+                            </Field>
+                            <Field label="Service types, all if empty">
+                                <Input value=service_types placeholder="_http._tcp.local., ..." />
+                            </Field>
+                            <Field label="Activate on interfaces or subnets">
+                                <Input value=auto_activate placeholder="eth0, 192.168.10.0/24, ..." />
+                            </Field>
+                            <Button appearance=ButtonAppearance::Primary on_click=on_save_click>
+                                "Save current settings"
+                            </Button>
+                        </Flex>
+                    </Flex>
+                </AccordionItem>
+            </Accordion>
+        </Layout>
+    }
+}
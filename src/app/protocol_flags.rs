@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: MIT-0
 
 use leptos::prelude::*;
+use leptos::task::spawn_local;
 use reactive_stores::Store;
 use serde::{Deserialize, Serialize};
 
@@ -9,7 +10,7 @@ use models::{ProtocolFlags, ProtocolFlagsStoreFields};
 use tauri_sys::core::invoke;
 use thaw::{Checkbox, Flex, FlexAlign, FlexGap, FlexJustify};
 
-use super::browse::browse_types;
+use super::{browse::browse_types, profiles::ProfilesInjection};
 
 /// Asynchronously fetches the current protocol flags from the backend and updates the provided reactive store.
 ///
@@ -55,6 +56,14 @@ pub fn ProtocolFlags(#[prop(optional, into)] disabled: Signal<bool>) -> impl Int
     let protocol_flags = Store::new(ProtocolFlags::default());
     LocalResource::new(move || get_protocol_flags(protocol_flags));
 
+    // Activating a profile changes the protocol flags in the backend
+    let profiles = ProfilesInjection::expect_context();
+    Effect::watch(
+        move || profiles.with(|profiles| profiles.active.clone()),
+        move |_, _, _| spawn_local(get_protocol_flags(protocol_flags)),
+        false,
+    );
+
     let set_protocol_flags_action = Action::new_local(|flags: &ProtocolFlags| {
         let flags = flags.clone();
         async move {
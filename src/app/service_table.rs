@@ -0,0 +1,262 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+use leptos::prelude::*;
+use models::ResolvedService;
+use thaw::{
+    Checkbox, Flex, FlexGap, Icon, Table, TableCell, TableCellLayout, TableHeader, TableHeaderCell,
+    TableRow, Text,
+};
+
+use super::{
+    browse::{SortKind, drop_local_and_trailing_dot, to_local_timestamp},
+    values_table::CopyableTableCell,
+    virtual_table::VirtualServiceRows,
+};
+
+/// Columns of the service table, in display order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ServiceColumn {
+    Instance,
+    ServiceType,
+    Hostname,
+    Port,
+    Address,
+    TxtCount,
+    LastUpdated,
+    Alive,
+}
+
+const ALL_COLUMNS: [ServiceColumn; 8] = [
+    ServiceColumn::Instance,
+    ServiceColumn::ServiceType,
+    ServiceColumn::Hostname,
+    ServiceColumn::Port,
+    ServiceColumn::Address,
+    ServiceColumn::TxtCount,
+    ServiceColumn::LastUpdated,
+    ServiceColumn::Alive,
+];
+
+impl ServiceColumn {
+    fn label(&self) -> &'static str {
+        match self {
+            ServiceColumn::Instance => "Instance",
+            ServiceColumn::ServiceType => "Type",
+            ServiceColumn::Hostname => "Host",
+            ServiceColumn::Port => "Port",
+            ServiceColumn::Address => "Address",
+            ServiceColumn::TxtCount => "TXT",
+            ServiceColumn::LastUpdated => "Last updated",
+            ServiceColumn::Alive => "Alive",
+        }
+    }
+
+    /// Class applied to the header and cells, used to size the column.
+    fn class(&self) -> &'static str {
+        match self {
+            ServiceColumn::Instance => "service-table-instance",
+            ServiceColumn::ServiceType => "service-table-type",
+            ServiceColumn::Hostname => "service-table-host",
+            ServiceColumn::Port => "service-table-port",
+            ServiceColumn::Address => "service-table-address",
+            ServiceColumn::TxtCount => "service-table-txt",
+            ServiceColumn::LastUpdated => "service-table-updated",
+            ServiceColumn::Alive => "service-table-alive",
+        }
+    }
+
+    /// The ascending and descending sort of the column, if it can be sorted by.
+    fn sort_kinds(&self) -> Option<(SortKind, SortKind)> {
+        match self {
+            ServiceColumn::Instance => Some((SortKind::InstanceAsc, SortKind::InstanceDesc)),
+            ServiceColumn::ServiceType => {
+                Some((SortKind::ServiceTypeAsc, SortKind::ServiceTypeDesc))
+            }
+            ServiceColumn::Hostname => Some((SortKind::HostnameAsc, SortKind::HostnameDesc)),
+            ServiceColumn::Port => Some((SortKind::PortAsc, SortKind::PortDesc)),
+            ServiceColumn::Address => Some((SortKind::IpAddrAsc, SortKind::IpAddrDesc)),
+            ServiceColumn::LastUpdated => Some((SortKind::TimestampAsc, SortKind::TimestampDesc)),
+            ServiceColumn::TxtCount | ServiceColumn::Alive => None,
+        }
+    }
+}
+
+/// Renders a header cell, clicking a sortable header toggles between ascending and descending.
+#[component]
+fn ServiceColumnHeader(
+    column: ServiceColumn,
+    #[prop(into)] sort_by: Signal<SortKind>,
+    on_sort: Callback<SortKind>,
+) -> impl IntoView {
+    let indicator = move || match column.sort_kinds() {
+        Some((asc, _)) if sort_by.get() == asc => " ▲",
+        Some((_, desc)) if sort_by.get() == desc => " ▼",
+        _ => "",
+    };
+    let on_click = move |_| {
+        if let Some((asc, desc)) = column.sort_kinds() {
+            on_sort.run(if sort_by.get_untracked() == asc {
+                desc
+            } else {
+                asc
+            });
+        }
+    };
+    let class = if column.sort_kinds().is_some() {
+        format!("{} service-table-sortable", column.class())
+    } else {
+        column.class().to_string()
+    };
+    view! {
+        <TableHeaderCell class=class on:click=on_click>
+            {column.label()}
+            {indicator}
+        </TableHeaderCell>
+    }
+}
+
+/// Renders a single cell of a service row.
+fn service_cell(column: ServiceColumn, service: &ResolvedService) -> impl IntoView + use<> {
+    let content = match column {
+        ServiceColumn::Instance => view! {
+            <CopyableTableCell
+                text=service.get_instance_name()
+                copy_text=service.get_escaped_instance_fullname()
+            />
+        }
+        .into_any(),
+        ServiceColumn::ServiceType => view! {
+            <CopyableTableCell
+                text=drop_local_and_trailing_dot(&service.service_type)
+                copy_text=service.service_type.clone()
+            />
+        }
+        .into_any(),
+        ServiceColumn::Hostname => view! {
+            <CopyableTableCell
+                text=drop_local_and_trailing_dot(&service.hostname)
+                copy_text=service.hostname.clone()
+            />
+        }
+        .into_any(),
+        ServiceColumn::Port => {
+            view! { <CopyableTableCell text=service.port.to_string() /> }.into_any()
+        }
+        ServiceColumn::Address => match service.addresses.first() {
+            Some(address) => view! {
+                <CopyableTableCell text=address.to_string() copy_text=address.to_ip_string() />
+            }
+            .into_any(),
+            None => view! { <Text>"-"</Text> }.into_any(),
+        },
+        ServiceColumn::TxtCount => {
+            let warnings = service
+                .txt_warnings()
+                .iter()
+                .map(|w| w.to_string())
+                .collect::<Vec<_>>();
+            let warning_icon = (!warnings.is_empty()).then(|| {
+                view! {
+                    <Icon
+                        icon=icondata::MdiAlert
+                        class="service-type-diagnostic-warning"
+                        attr:title=warnings.join("\n")
+                    />
+                }
+            });
+            view! {
+                <Text>{service.txt.len()}</Text>
+                {warning_icon}
+            }
+            .into_any()
+        }
+        ServiceColumn::LastUpdated => {
+            view! { <Text>{to_local_timestamp(service.updated_at_micros)}</Text> }.into_any()
+        }
+        ServiceColumn::Alive => {
+            let class = if service.dead {
+                "resolved-service-dead"
+            } else {
+                "resolved-service-alive"
+            };
+            view! { <Icon icon=icondata::MdiCircle class=class /> }.into_any()
+        }
+    };
+    view! {
+        <TableCell class=column.class()>
+            <TableCellLayout truncate=true>{content}</TableCellLayout>
+        </TableCell>
+    }
+}
+
+/// Component that shows services as a dense table, one row per service
+///
+/// Only the rows around the viewport are mounted. Columns can be hidden, and clicking the header
+/// of a column sorts by it.
+#[component]
+pub fn ServiceTable(
+    #[prop(into)] services: Signal<Vec<ResolvedService>>,
+    #[prop(into)] sort_by: Signal<SortKind>,
+    on_sort: Callback<SortKind>,
+) -> impl IntoView {
+    let columns = RwSignal::new(ALL_COLUMNS.to_vec());
+    let column_checkboxes = ALL_COLUMNS
+        .iter()
+        .map(|&column| {
+            let checked = RwSignal::new(true);
+            Effect::watch(
+                move || checked.get(),
+                move |checked, _, _| {
+                    columns.update(|columns| {
+                        columns.retain(|c| *c != column);
+                        if *checked {
+                            columns.push(column);
+                            columns.sort_by_key(|c| ALL_COLUMNS.iter().position(|a| a == c));
+                        }
+                    })
+                },
+                false,
+            );
+            view! { <Checkbox checked label=column.label() /> }
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        <Flex vertical=true gap=FlexGap::Small>
+            <Flex gap=FlexGap::Small>{column_checkboxes}</Flex>
+            <Table class="service-table">
+                <TableHeader>
+                    <TableRow>
+                        <For
+                            each=move || columns.get()
+                            key=|column| *column
+                            let:column
+                        >
+                            <ServiceColumnHeader column sort_by on_sort />
+                        </For>
+                    </TableRow>
+                </TableHeader>
+                <VirtualServiceRows
+                    services
+                    row=move |service: Memo<ResolvedService>| {
+                        view! {
+                            <TableRow>
+                                {move || {
+                                    service
+                                        .with(|service| {
+                                            columns
+                                                .get()
+                                                .into_iter()
+                                                .map(|column| service_cell(column, service))
+                                                .collect::<Vec<_>>()
+                                        })
+                                }}
+                            </TableRow>
+                        }
+                    }
+                />
+            </Table>
+        </Flex>
+    }
+}
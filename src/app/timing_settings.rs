@@ -0,0 +1,193 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+use leptos::prelude::*;
+use models::TimingConfig;
+use serde::{Deserialize, Serialize};
+use tauri_sys::core::{invoke, invoke_result};
+use thaw::{
+    Accordion, AccordionHeader, AccordionItem, Button, ButtonAppearance, Field, Flex, FlexAlign,
+    FlexGap, FlexJustify, Input, InputType, Layout, Toast, ToastBody, ToastTitle, ToasterInjection,
+};
+
+use super::{
+    css::get_class, dead_services::DeadServicePolicySettings, is_desktop::IsDesktopInjection,
+};
+
+/// Injection providing the effective timing configuration of the backend.
+///
+/// The browse view uses it to keep the verify spinner in sync with the backend's verify timeout.
+#[derive(Clone, Debug)]
+pub struct TimingConfigInjection(pub RwSignal<TimingConfig>);
+
+impl TimingConfigInjection {
+    #[track_caller]
+    pub fn expect_context() -> RwSignal<TimingConfig> {
+        expect_context::<Self>().0
+    }
+}
+
+pub async fn get_timing_config(timing: RwSignal<TimingConfig>) {
+    let config = invoke::<TimingConfig>("get_timing_config", &()).await;
+    timing.set(config);
+}
+
+#[derive(Serialize, Deserialize)]
+struct TimingConfigArgs {
+    config: TimingConfig,
+}
+
+async fn set_timing_config(config: TimingConfig) -> Result<(), String> {
+    invoke_result::<(), String>("set_timing_config", &TimingConfigArgs { config }).await
+}
+
+fn create_timing_error_toast(message: String) -> impl IntoView {
+    view! {
+        <Toast>
+            <ToastTitle>"Failed to apply timings"</ToastTitle>
+            <ToastBody>{message}</ToastBody>
+        </Toast>
+    }
+}
+
+/// Parses the text of a timing input, naming the field in the error.
+fn parse_timing(label: &str, value: &str) -> Result<u64, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("{label} must be a non-negative whole number"))
+}
+
+/// Renders a numeric input for a single timing value.
+#[component]
+fn TimingInput(#[prop(into)] label: String, value: RwSignal<String>) -> impl IntoView {
+    view! {
+        <Field label>
+            <Input value input_type=InputType::Number />
+        </Field>
+    }
+}
+
+/// Component for tuning the timings of the backend.
+///
+/// The inputs are initialized from the effective timings, including command line overrides.
+/// Applying validates and persists the values in the backend.
+#[component]
+pub fn TimingSettings() -> impl IntoView {
+    let timing = TimingConfigInjection::expect_context();
+    let verify_timeout = RwSignal::new(String::new());
+    let metrics_interval = RwSignal::new(String::new());
+    let interfaces_interval = RwSignal::new(String::new());
+    let ip_check_interval = RwSignal::new(String::new());
+    let browse_retry_delay = RwSignal::new(String::new());
+    let browse_retry_attempts = RwSignal::new(String::new());
+
+    Effect::new(move |_| {
+        let config = timing.get();
+        verify_timeout.set(config.verify_timeout_secs.to_string());
+        metrics_interval.set(config.metrics_check_interval_secs.to_string());
+        interfaces_interval.set(config.interfaces_check_interval_secs.to_string());
+        ip_check_interval.set(config.ip_check_interval_secs.to_string());
+        browse_retry_delay.set(config.browse_retry_delay_millis.to_string());
+        browse_retry_attempts.set(config.browse_retry_attempts.to_string());
+    });
+
+    let toaster = ToasterInjection::expect_context();
+    let apply_action = Action::new_local(move |config: &TimingConfig| {
+        let config = *config;
+        async move {
+            match set_timing_config(config).await {
+                // Reloaded, as command line overrides stay in effect for the session
+                Ok(()) => get_timing_config(timing).await,
+                Err(e) => {
+                    log::error!("failed to set timings: {e}");
+                    toaster
+                        .dispatch_toast(move || create_timing_error_toast(e), Default::default());
+                }
+            }
+        }
+    });
+
+    let parse_inputs = move || -> Result<TimingConfig, String> {
+        let config = TimingConfig {
+            verify_timeout_secs: parse_timing("Verify timeout", &verify_timeout.get_untracked())?,
+            metrics_check_interval_secs: parse_timing(
+                "Metrics check interval",
+                &metrics_interval.get_untracked(),
+            )?,
+            interfaces_check_interval_secs: parse_timing(
+                "Interfaces check interval",
+                &interfaces_interval.get_untracked(),
+            )?,
+            ip_check_interval_secs: parse_timing(
+                "IP check interval",
+                &ip_check_interval.get_untracked(),
+            )?,
+            browse_retry_delay_millis: parse_timing(
+                "Browse retry delay",
+                &browse_retry_delay.get_untracked(),
+            )?,
+            browse_retry_attempts: parse_timing(
+                "Browse retry attempts",
+                &browse_retry_attempts.get_untracked(),
+            )?,
+        };
+        config.validate().map_err(|e| e.to_string())?;
+        Ok(config)
+    };
+
+    let on_apply_click = move |_| {
+        let config = parse_inputs();
+        match config {
+            Ok(config) => {
+                apply_action.dispatch(config);
+            }
+            Err(e) => {
+                toaster.dispatch_toast(move || create_timing_error_toast(e), Default::default());
+            }
+        }
+    };
+
+    let is_desktop = IsDesktopInjection::expect_context();
+    let layout_class = get_class(&is_desktop, "timing-settings-layout");
+    view! {
+        <Layout class=layout_class>
+            <Accordion multiple=true>
+                <AccordionItem value="timing-settings">
+                    <AccordionHeader slot>"Advanced"</AccordionHeader>
+                    <Flex vertical=true gap=FlexGap::Small>
+                        <DeadServicePolicySettings />
+                        <Flex gap=FlexGap::Small align=FlexAlign::End justify=FlexJustify::Start>
+                            <TimingInput label="Verify timeout (s)" value=verify_timeout />
+                            <TimingInput
+                                label="Metrics check interval (s)"
+                                value=metrics_interval
+                            />
+                            <TimingInput
+                                label="Interfaces check interval (s)"
+                                value=interfaces_interval
+                            />
+                            <TimingInput label="IP check interval (s)" value=ip_check_interval />
+                            <TimingInput
+                                label="Browse retry delay (ms)"
+                                value=browse_retry_delay
+                            />
+                            <TimingInput
+                                label="Browse retry attempts"
+                                value=browse_retry_attempts
+                            />
+                        </Flex>
+                        <Flex justify=FlexJustify::Start>
+                            <Button
+                                appearance=ButtonAppearance::Primary
+                                on_click=on_apply_click
+                            >
+                                "Apply"
+                            </Button>
+                        </Flex>
+                    </Flex>
+                </AccordionItem>
+            </Accordion>
+        </Layout>
+    }
+}
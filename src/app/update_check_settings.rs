@@ -0,0 +1,126 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+use leptos::prelude::*;
+use models::UpdateCheckSettings;
+use serde::{Deserialize, Serialize};
+use tauri_sys::core::invoke_result;
+use thaw::{
+    Checkbox, Flex, FlexAlign, FlexGap, FlexJustify, Select, Text, Toast, ToastBody, ToastTitle,
+    ToasterInjection,
+};
+
+use super::is_desktop::IsDesktopInjection;
+
+async fn get_update_check_settings(settings: RwSignal<UpdateCheckSettings>) {
+    match invoke_result::<UpdateCheckSettings, String>("get_update_check_settings", &()).await {
+        Ok(loaded) => settings.set(loaded),
+        Err(e) => log::error!("failed to get update check settings: {e}"),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct UpdateCheckSettingsArgs {
+    settings: UpdateCheckSettings,
+}
+
+async fn set_update_check_settings(settings: UpdateCheckSettings) -> Result<(), String> {
+    invoke_result::<(), String>(
+        "set_update_check_settings",
+        &UpdateCheckSettingsArgs { settings },
+    )
+    .await
+}
+
+fn create_update_check_error_toast(message: String) -> impl IntoView {
+    view! {
+        <Toast>
+            <ToastTitle>"Failed to apply update check schedule"</ToastTitle>
+            <ToastBody>{message}</ToastBody>
+        </Toast>
+    }
+}
+
+const NEVER: &str = "never";
+
+fn to_interval_value(settings: &UpdateCheckSettings) -> String {
+    if settings.enabled {
+        settings.interval_hours.to_string()
+    } else {
+        NEVER.to_string()
+    }
+}
+
+/// Component for configuring how often updates are checked for in the background.
+///
+/// Changes are applied immediately and persisted in the backend.
+#[component]
+pub fn UpdateCheckSchedule() -> impl IntoView {
+    let settings = RwSignal::new(UpdateCheckSettings::default());
+    LocalResource::new(move || get_update_check_settings(settings));
+    let interval_value = RwSignal::new(to_interval_value(&settings.get_untracked()));
+    let skip_on_metered = RwSignal::new(settings.get_untracked().skip_on_metered);
+
+    Effect::new(move |_| {
+        settings.with(|settings| {
+            interval_value.set(to_interval_value(settings));
+            skip_on_metered.set(settings.skip_on_metered);
+        });
+    });
+
+    let toaster = ToasterInjection::expect_context();
+    let set_settings_action = Action::new_local(move |new_settings: &UpdateCheckSettings| {
+        let new_settings = new_settings.clone();
+        async move {
+            match set_update_check_settings(new_settings.clone()).await {
+                Ok(()) => settings.set(new_settings),
+                Err(e) => {
+                    log::error!("failed to set update check settings: {e}");
+                    toaster.dispatch_toast(
+                        move || create_update_check_error_toast(e),
+                        Default::default(),
+                    );
+                }
+            }
+        }
+    });
+
+    Effect::watch(
+        move || (interval_value.get(), skip_on_metered.get()),
+        move |(interval, skip_on_metered), _, _| {
+            let current = settings.get_untracked();
+            let new_settings = UpdateCheckSettings {
+                enabled: interval != NEVER,
+                interval_hours: interval.parse().unwrap_or(current.interval_hours),
+                skip_on_metered: *skip_on_metered,
+                ..current.clone()
+            };
+            if new_settings != current {
+                set_settings_action.dispatch(new_settings);
+            }
+        },
+        false,
+    );
+
+    let is_desktop = IsDesktopInjection::expect_context();
+    view! {
+        <Flex gap=FlexGap::Small align=FlexAlign::Center justify=FlexJustify::Start>
+            <Text>"Check for updates"</Text>
+            <Select value=interval_value>
+                <option label="Never" value=NEVER />
+                <option label="Every hour" value="1" />
+                <option label="Every 6 hours" value="6" />
+                <option label="Daily" value="24" />
+                <option label="Weekly" value="168" />
+            </Select>
+            <Show
+                when=move || { !is_desktop.get() }
+                fallback=move || {
+                    view! { <div class="hidden" /> }
+                }
+            >
+                <Checkbox checked=skip_on_metered label="Not on metered networks" />
+            </Show>
+        </Flex>
+    }
+}
@@ -2,9 +2,13 @@
 // SPDX-License-Identifier: MIT-0
 
 use leptos::prelude::*;
+use models::{TxtRecord, TxtValueFormat, TxtValueKind};
 use thaw::{Table, TableBody, TableCell, TableCellLayout, TableHeader, TableHeaderCell, TableRow};
 
-use thaw::{Button, ButtonAppearance, ButtonSize, ToasterInjection};
+use thaw::{
+    Badge, BadgeAppearance, BadgeColor, BadgeSize, Button, ButtonAppearance, ButtonSize, Flex,
+    FlexAlign, FlexGap, Select, ToasterInjection,
+};
 use thaw_utils::Model;
 
 use super::{
@@ -14,7 +18,7 @@ use super::{
 
 /// Component that allows to copy the shown text to the clipboard
 #[component]
-fn CopyableTableCell(
+pub fn CopyableTableCell(
     #[prop(optional, into)] class: MaybeProp<String>,
     text: String,
     #[prop(optional, into)] copy_text: MaybeProp<String>,
@@ -98,3 +102,105 @@ pub fn ValuesTable(
         </Show>
     }
 }
+
+/// Renders a single TXT record with a selectable representation of its value
+///
+/// The copy button copies the value in the selected representation, or the key for an attribute
+/// without value.
+#[component]
+fn TxtRecordRow(record: TxtRecord) -> impl IntoView {
+    let format = RwSignal::new(TxtValueFormat::default().label().to_string());
+    let selected = Memo::new(move |_| {
+        let format = format.get();
+        TxtValueFormat::ALL
+            .into_iter()
+            .find(|f| f.label() == format)
+            .unwrap_or_default()
+    });
+    let kind = match record.value_kind() {
+        TxtValueKind::Absent => Some("no value".to_string()),
+        TxtValueKind::Empty => Some("empty".to_string()),
+        TxtValueKind::Binary => Some(format!(
+            "binary, {} bytes",
+            record.raw.as_ref().map_or(0, |raw| raw.len())
+        )),
+        TxtValueKind::Text => None,
+    };
+    let has_value = record.value_kind() != TxtValueKind::Absent;
+    let options = TxtValueFormat::ALL
+        .into_iter()
+        .map(|f| view! { <option label=f.label() value=f.label() /> })
+        .collect::<Vec<_>>();
+    view! {
+        <TableRow>
+            <TableCell>
+                <Flex gap=FlexGap::Small align=FlexAlign::Center>
+                    {has_value
+                        .then(|| {
+                            view! {
+                                <Select default_value="Text" value=format>
+                                    {options}
+                                </Select>
+                            }
+                        })}
+                    {kind
+                        .map(|kind| {
+                            view! {
+                                <Badge
+                                    appearance=BadgeAppearance::Tint
+                                    size=BadgeSize::Small
+                                    color=BadgeColor::Subtle
+                                >
+                                    {kind}
+                                </Badge>
+                            }
+                        })}
+                    <TableCellLayout truncate=true>
+                        {move || {
+                            let format = selected.get();
+                            view! {
+                                <CopyableTableCell
+                                    text=record.to_string_as(format)
+                                    copy_text=record
+                                        .format_value(format)
+                                        .unwrap_or_else(|| record.key.clone())
+                                />
+                            }
+                        }}
+                    </TableCellLayout>
+                </Flex>
+            </TableCell>
+        </TableRow>
+    }
+}
+
+/// Component to render TXT records into a table, each value can be shown as text, hex or base64
+#[component]
+pub fn TxtRecordsTable(#[prop(into)] records: Signal<Vec<TxtRecord>>) -> impl IntoView {
+    let has_records = Signal::derive(move || records.with(|r| !r.is_empty()));
+    view! {
+        <Show
+            when=move || has_records.get()
+            fallback=move || {
+                view! { <div class="hidden" /> }
+            }
+        >
+            <Table>
+                <TableHeader>
+                    <TableRow>
+                        <TableHeaderCell>"TXT"</TableHeaderCell>
+                    </TableRow>
+                </TableHeader>
+                <TableBody>
+                    <For
+                        each=move || records.get()
+                        key=|record| (record.key.clone(), record.raw.clone(), record.val.clone())
+                        let:record
+                    >
+                        <TxtRecordRow record />
+                    </For>
+                </TableBody>
+            </Table>
+        </Show>
+    }
+}
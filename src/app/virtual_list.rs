@@ -0,0 +1,249 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+//! Windowed rendering for long lists and grids, so only the rows around the viewport are mounted.
+//!
+//! The page scrolls as a whole, so the window is derived from the position of the list within the
+//! browser window. Rows outside the window are replaced by padding of the same height.
+
+use js_sys::wasm_bindgen::JsCast;
+use leptos::{ev, html::ElementType, prelude::*};
+
+/// Rows mounted above and below the viewport, so fast scrolling does not reveal empty space.
+const OVERSCAN_ROWS: usize = 3;
+
+/// Rows assumed to fit into the viewport before anything has been measured.
+const INITIAL_ROWS: usize = 10;
+
+/// The part of a list to mount, in items, and the padding standing in for the rest.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VisibleRange {
+    pub start: usize,
+    pub end: usize,
+    pub padding_top: f64,
+    pub padding_bottom: f64,
+}
+
+/// Returns how many items of at least `min_item_width` fit next to each other, like a CSS grid
+/// with `repeat(auto-fill, minmax(min_item_width, 1fr))` does.
+pub fn columns_for_width(width: f64, min_item_width: f64, gap: f64) -> usize {
+    if width <= 0.0 || min_item_width <= 0.0 {
+        return 1;
+    }
+    (((width + gap) / (min_item_width + gap)).floor() as usize).max(1)
+}
+
+/// Calculates the items to mount for a viewport.
+///
+/// `offset` is how far the viewport has scrolled past the top of the list, `row_height` includes
+/// the gap between rows.
+pub fn visible_range(
+    item_count: usize,
+    columns: usize,
+    row_height: f64,
+    offset: f64,
+    viewport_height: f64,
+    overscan_rows: usize,
+) -> VisibleRange {
+    let columns = columns.max(1);
+    let rows = item_count.div_ceil(columns);
+    if rows == 0 || row_height <= 0.0 {
+        return VisibleRange {
+            start: 0,
+            end: item_count.min(INITIAL_ROWS * columns),
+            ..Default::default()
+        };
+    }
+    let first_row = ((offset.max(0.0) / row_height).floor() as usize)
+        .saturating_sub(overscan_rows)
+        .min(rows);
+    let last_row = (((offset.max(0.0) + viewport_height.max(0.0)) / row_height).ceil() as usize)
+        .saturating_add(overscan_rows)
+        .clamp(first_row, rows);
+    VisibleRange {
+        start: (first_row * columns).min(item_count),
+        end: (last_row * columns).min(item_count),
+        padding_top: first_row as f64 * row_height,
+        padding_bottom: (rows - last_row) as f64 * row_height,
+    }
+}
+
+/// Layout of the items of a virtualized list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VirtualLayout {
+    /// Minimum width of an item, `None` for a list with a single column.
+    pub min_item_width: Option<f64>,
+    /// Gap between items, both horizontally and vertically.
+    pub gap: f64,
+    /// Height of a row, including the gap, until it has been measured.
+    pub estimated_row_height: f64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Viewport {
+    offset: f64,
+    height: f64,
+    width: f64,
+}
+
+/// Tracks the window position of the list in `container` and returns the items to mount.
+///
+/// The top of `container` is where the list starts. `content` is the element holding only the
+/// mounted items, its height is used to measure the actual row height after every change of the
+/// mounted items. For a table, these can be a spacer `tbody` above the rows and the `tbody` of the
+/// rows, so the header is not counted as part of the list.
+pub fn use_visible_range<C, E>(
+    container: NodeRef<C>,
+    content: NodeRef<E>,
+    item_count: Signal<usize>,
+    layout: Signal<VirtualLayout>,
+) -> Memo<VisibleRange>
+where
+    C: ElementType + 'static,
+    C::Output: JsCast + Clone + 'static,
+    E: ElementType + 'static,
+    E::Output: JsCast + Clone + 'static,
+{
+    let viewport = RwSignal::new(Viewport::default());
+    let row_height = RwSignal::new(layout.get_untracked().estimated_row_height);
+
+    let measure = move || {
+        let Some(container) = container.get_untracked() else {
+            return;
+        };
+        let container = container.unchecked_ref::<web_sys::Element>();
+        let window = window();
+        let measured = Viewport {
+            offset: -container.get_bounding_client_rect().top(),
+            height: window
+                .inner_height()
+                .ok()
+                .and_then(|height| height.as_f64())
+                .unwrap_or_default(),
+            width: container.client_width() as f64,
+        };
+        if measured != viewport.get_untracked() {
+            viewport.set(measured);
+        }
+    };
+
+    let scroll_handle = window_event_listener(ev::scroll, move |_| measure());
+    let resize_handle = window_event_listener(ev::resize, move |_| measure());
+    on_cleanup(move || {
+        scroll_handle.remove();
+        resize_handle.remove();
+    });
+    Effect::new(move |_| {
+        if container.get().is_some() {
+            measure();
+        }
+    });
+
+    let columns = Memo::new(move |_| {
+        let layout = layout.get();
+        layout.min_item_width.map_or(1, |min_item_width| {
+            columns_for_width(viewport.get().width, min_item_width, layout.gap)
+        })
+    });
+
+    let range = Memo::new(move |_| {
+        let viewport = viewport.get();
+        visible_range(
+            item_count.get(),
+            columns.get(),
+            row_height.get(),
+            viewport.offset,
+            viewport.height,
+            OVERSCAN_ROWS,
+        )
+    });
+
+    Effect::new(move |_| {
+        let range = range.get();
+        let columns = columns.get();
+        let Some(content) = content.get() else {
+            return;
+        };
+        let rows = (range.end - range.start).div_ceil(columns);
+        if rows == 0 {
+            return;
+        }
+        let gap = layout.get_untracked().gap;
+        // Measured once the mounted items have been laid out
+        request_animation_frame(move || {
+            let height = content
+                .unchecked_ref::<web_sys::HtmlElement>()
+                .offset_height();
+            let measured = (height as f64 + gap) / rows as f64;
+            if measured > 0.0 && (measured - row_height.get_untracked()).abs() >= 1.0 {
+                row_height.set(measured);
+            }
+        });
+    });
+
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_for_width() {
+        assert_eq!(columns_for_width(1000.0, 520.0, 10.0), 1);
+        assert_eq!(columns_for_width(1050.0, 520.0, 10.0), 2);
+        assert_eq!(columns_for_width(1600.0, 520.0, 10.0), 3);
+        assert_eq!(columns_for_width(100.0, 520.0, 10.0), 1);
+        assert_eq!(columns_for_width(0.0, 520.0, 10.0), 1);
+    }
+
+    #[test]
+    fn test_visible_range_at_top() {
+        let range = visible_range(1000, 2, 100.0, 0.0, 500.0, 3);
+        assert_eq!(range.start, 0);
+        assert_eq!(range.end, 16);
+        assert_eq!(range.padding_top, 0.0);
+        assert_eq!(range.padding_bottom, 492.0 * 100.0);
+    }
+
+    #[test]
+    fn test_visible_range_scrolled() {
+        let range = visible_range(1000, 2, 100.0, 10_000.0, 500.0, 3);
+        assert_eq!(range.start, 194);
+        assert_eq!(range.end, 216);
+        assert_eq!(range.padding_top, 97.0 * 100.0);
+        assert_eq!(range.padding_bottom, 392.0 * 100.0);
+    }
+
+    #[test]
+    fn test_visible_range_at_bottom_with_partial_row() {
+        let range = visible_range(11, 2, 100.0, 10_000.0, 500.0, 3);
+        assert_eq!(range.start, 11);
+        assert_eq!(range.end, 11);
+        assert_eq!(range.padding_top, 600.0);
+        assert_eq!(range.padding_bottom, 0.0);
+
+        let range = visible_range(11, 2, 100.0, 300.0, 500.0, 0);
+        assert_eq!(range.start, 6);
+        assert_eq!(range.end, 11);
+        assert_eq!(range.padding_bottom, 0.0);
+    }
+
+    #[test]
+    fn test_visible_range_above_list() {
+        let range = visible_range(100, 1, 50.0, -200.0, 400.0, 0);
+        assert_eq!(range.start, 0);
+        assert_eq!(range.end, 8);
+    }
+
+    #[test]
+    fn test_visible_range_unmeasured() {
+        let range = visible_range(100, 2, 0.0, 0.0, 0.0, 3);
+        assert_eq!(range.start, 0);
+        assert_eq!(range.end, INITIAL_ROWS * 2);
+        assert_eq!(
+            visible_range(0, 2, 100.0, 0.0, 500.0, 3),
+            VisibleRange::default()
+        );
+    }
+}
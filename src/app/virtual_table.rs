@@ -0,0 +1,81 @@
+// Copyright 2026 hrzlgnm
+// SPDX-License-Identifier: MIT-0
+
+use leptos::{html, prelude::*};
+use models::ResolvedService;
+use std::collections::HashMap;
+
+use super::virtual_list::{VirtualLayout, use_visible_range};
+
+/// Height of a table row until it has been measured.
+const ESTIMATED_ROW_HEIGHT: f64 = 44.0;
+
+/// Renders a spacer row of `height` pixels, standing in for rows that are not mounted.
+fn spacer_row(height: impl Fn() -> f64 + Send + Sync + 'static) -> impl IntoView {
+    view! {
+        <tr style:height=move || format!("{}px", height())>
+            <td style="padding: 0"></td>
+        </tr>
+    }
+}
+
+/// Table body with one row per service, of which only the rows around the viewport are mounted.
+///
+/// Goes inside a `Table` after its header. A row is kept while its service updates, `row` gets
+/// the service of the row as a memo, so the cells can update in place.
+#[component]
+pub fn VirtualServiceRows<V, VF>(
+    #[prop(into)] services: Signal<Vec<ResolvedService>>,
+    row: VF,
+) -> impl IntoView
+where
+    V: IntoView + 'static,
+    VF: Fn(Memo<ResolvedService>) -> V + Clone + Send + Sync + 'static,
+{
+    let container = NodeRef::<html::Tbody>::new();
+    let content = NodeRef::<html::Tbody>::new();
+    let visible = use_visible_range(
+        container,
+        content,
+        Signal::derive(move || services.with(|services| services.len())),
+        Signal::stored(VirtualLayout {
+            min_item_width: None,
+            gap: 0.0,
+            estimated_row_height: ESTIMATED_ROW_HEIGHT,
+        }),
+    );
+    let rows = Memo::new(move |_| {
+        let range = visible.get();
+        services.with(|services| services[range.start..range.end.min(services.len())].to_vec())
+    });
+    // Position of each mounted row, so a row finds its service without searching the others
+    let positions = Memo::new(move |_| {
+        rows.with(|rows| {
+            rows.iter()
+                .enumerate()
+                .map(|(index, service)| (service.instance_fullname.clone(), index))
+                .collect::<HashMap<_, _>>()
+        })
+    });
+
+    view! {
+        <tbody node_ref=container>{spacer_row(move || visible.get().padding_top)}</tbody>
+        <tbody node_ref=content class="thaw-table-body">
+            <For
+                each=move || rows.get()
+                key=|service| service.instance_fullname.clone()
+                children=move |service| {
+                    let instance_fullname = service.instance_fullname.clone();
+                    let service = Memo::new(move |_| {
+                        positions
+                            .with(|positions| positions.get(&instance_fullname).copied())
+                            .and_then(|index| rows.with(|rows| rows.get(index).cloned()))
+                            .unwrap_or_else(|| service.clone())
+                    });
+                    row(service)
+                }
+            />
+        </tbody>
+        <tbody>{spacer_row(move || visible.get().padding_bottom)}</tbody>
+    }
+}